@@ -24,6 +24,11 @@ impl<T> Positional<T> {
         Self { position, contents }
     }
 
+    /// Returns a reference to the value a Positional wraps.
+    pub fn contents(&self) -> &T {
+        &self.contents
+    }
+
     /// Unwraps the contents of a Positional returning the value it wraps.
     /// Transforming the type Positional<T> -> T.
     pub fn unwrap(self) -> T {
@@ -0,0 +1,36 @@
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    ($($mnemonic:ident),*) => {
+        /// Mnemonic represents the Atmel mnemonics of the classic AVR,
+        /// including the aliases of its branch, flag and register
+        /// instructions. Whether a mnemonic is valid for its operands is left
+        /// to encoding.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($mnemonic,)*
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($mnemonic) => Ok(Self::$mnemonic),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    ADC, ADD, ADIW, AND, ANDI, ASR, BCLR, BLD, BRBC, BRBS, BRCC, BRCS, BREAK, BREQ, BRGE, BRHC,
+    BRHS, BRID, BRIE, BRLO, BRLT, BRMI, BRNE, BRPL, BRSH, BRTC, BRTS, BRVC, BRVS, BSET, BST, CALL,
+    CBI, CBR, CLC, CLH, CLI, CLN, CLR, CLS, CLT, CLV, CLZ, COM, CP, CPC, CPI, CPSE, DEC, ELPM, EOR,
+    FMUL, FMULS, FMULSU, ICALL, IJMP, IN, INC, JMP, LD, LDD, LDI, LDS, LPM, LSL, LSR, MOV, MOVW,
+    MUL, MULS, MULSU, NEG, NOP, OR, ORI, OUT, POP, PUSH, RCALL, RET, RETI, RJMP, ROL, ROR, SBC,
+    SBCI, SBI, SBIC, SBIS, SBIW, SBR, SBRC, SBRS, SEC, SEH, SEI, SEN, SES, SET, SEV, SEZ, SER,
+    SLEEP, SPM, ST, STD, STS, SUB, SUBI, SWAP, TST, WDR
+);
@@ -0,0 +1,506 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+use crate::backends::layout;
+use mnemonic::Mnemonic;
+
+/// Value represents a numeric operand, wrapping the literal or reference
+/// shared by every backend to optionally select its low or high byte or the
+/// byte address of a label.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A literal or reference, `value`.
+    Plain(layout::Value),
+    /// The low byte of a value, `lo8(value)`.
+    Lo8(Box<Value>),
+    /// The second byte of a value, `hi8(value)`.
+    Hi8(Box<Value>),
+    /// The byte address of a word-addressed label, `byte(value)`.
+    Byte(Box<Value>),
+}
+
+/// Pointer represents the X, Y and Z pointer registers, formed from the
+/// register pairs `r27:r26`, `r29:r28` and `r31:r30` respectively.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pointer {
+    X,
+    Y,
+    Z,
+}
+
+/// Operand represents each form of AVR operand, generalized over its value
+/// so that operands may be parsed with references and encoded once
+/// resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    /// One of the general purpose registers, `r0` through `r31`.
+    Register(u8),
+    /// A pair of consecutive registers, given high register first such as
+    /// `r25:r24`, by its low register.
+    RegisterPair(u8),
+    /// Data memory addressed by a pointer, such as `x`.
+    Indirect(Pointer),
+    /// Data memory addressed by a pointer that is incremented afterwards,
+    /// such as `x+`.
+    PostIncrement(Pointer),
+    /// Data memory addressed by a pointer that is decremented beforehand,
+    /// such as `-x`.
+    PreDecrement(Pointer),
+    /// Data memory addressed by a pointer and a 6-bit displacement, such
+    /// as `y+2`.
+    Displacement(Pointer, V),
+    /// An immediate, address, bit or jump target, depending on the
+    /// instruction.
+    Constant(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        let operand = match self {
+            Self::Register(n) => Operand::Register(*n),
+            Self::RegisterPair(n) => Operand::RegisterPair(*n),
+            Self::Indirect(p) => Operand::Indirect(*p),
+            Self::PostIncrement(p) => Operand::PostIncrement(*p),
+            Self::PreDecrement(p) => Operand::PreDecrement(*p),
+            Self::Displacement(p, v) => Operand::Displacement(*p, f(v)?),
+            Self::Constant(v) => Operand::Constant(f(v)?),
+        };
+
+        Ok(operand)
+    }
+}
+
+/// Instruction represents a single AVR mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Resolved represents a value that may still reference an unresolved
+/// symbol, in which case the value is zeroed and left unvalidated. As the
+/// size of an instruction depends only on its mnemonic, this allows
+/// instructions to be sized before all symbols are known.
+pub type Resolved = Option<i64>;
+
+/// Emits each 16-bit instruction word least significant byte first.
+fn words(words: &[u16]) -> Encoding {
+    Ok(words.iter().flat_map(|word| word.to_le_bytes()).collect())
+}
+
+fn unsigned(value: Resolved, max: u32) -> Result<u32, EncodeErr> {
+    match value.unwrap_or(0) {
+        v if (0..=i64::from(max)).contains(&v) => Ok(v as u32),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn immediate(value: Resolved) -> Result<u16, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x80..=0xff => Ok(u16::from(v as u8)),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+/// Returns the 4-bit index of one of `r16` through `r31`, the only
+/// registers accepted by instructions taking an immediate.
+fn upper(register: u8) -> Result<u16, EncodeErr> {
+    match register {
+        16..=31 => Ok(u16::from(register - 16)),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Returns the 3-bit index of one of `r16` through `r23`, as taken by the
+/// signed and fractional multiplies.
+fn multiplicand(register: u8) -> Result<u16, EncodeErr> {
+    match register {
+        16..=23 => Ok(u16::from(register - 16)),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Returns the low register of a register pair, given either as a pair or
+/// by its low register, which must be even.
+fn pair(operand: &Operand<Resolved>) -> Result<u8, EncodeErr> {
+    match operand {
+        Operand::Register(n) | Operand::RegisterPair(n) if n % 2 == 0 => Ok(*n),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes an instruction taking a destination and source register, with
+/// the 5-bit source split around the destination.
+fn two_registers(opcode: u16, d: u8, r: u8) -> u16 {
+    let (d, r) = (u16::from(d), u16::from(r));
+    opcode | ((r & 0x10) << 5) | (d << 4) | (r & 0x0f)
+}
+
+/// Encodes an instruction taking one of `r16` through `r31` and an 8-bit
+/// immediate, with the immediate split around the register.
+fn register_immediate(opcode: u16, d: u8, k: u16) -> Result<u16, EncodeErr> {
+    Ok(opcode | ((k & 0xf0) << 4) | (upper(d)? << 4) | (k & 0x0f))
+}
+
+/// Returns the word offset of a relative jump or branch, positioned at the
+/// provided byte offset, to its target word address, masked to the
+/// provided width.
+fn relative(position: usize, target: Resolved, bits: u32) -> Result<u16, EncodeErr> {
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(0),
+    };
+
+    let offset = target - (position / 2 + 1) as i64;
+    let range = 1i64 << (bits - 1);
+    if (-range..range).contains(&offset) {
+        Ok(offset as u16 & ((1 << bits) - 1))
+    } else {
+        Err(EncodeErr::BranchOutOfRange(offset as isize))
+    }
+}
+
+/// Returns the opcode of a load through a pointer, with stores setting bit
+/// 9 of the same opcode.
+fn indirect(operand: &Operand<Resolved>) -> Result<u16, EncodeErr> {
+    use Operand as O;
+    use Pointer as P;
+
+    match operand {
+        O::Indirect(P::X) => Ok(0x900c),
+        O::PostIncrement(P::X) => Ok(0x900d),
+        O::PreDecrement(P::X) => Ok(0x900e),
+        O::Indirect(P::Y) => Ok(0x8008),
+        O::PostIncrement(P::Y) => Ok(0x9009),
+        O::PreDecrement(P::Y) => Ok(0x900a),
+        O::Indirect(P::Z) => Ok(0x8000),
+        O::PostIncrement(P::Z) => Ok(0x9001),
+        O::PreDecrement(P::Z) => Ok(0x9002),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Returns the opcode of a load through the Y or Z pointer with a 6-bit
+/// displacement, scattered across the opcode.
+fn displacement(operand: &Operand<Resolved>) -> Result<u16, EncodeErr> {
+    let (base, q) = match operand {
+        Operand::Displacement(Pointer::Y, q) => (0x8008, q),
+        Operand::Displacement(Pointer::Z, q) => (0x8000, q),
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    let q = unsigned(*q, 0x3f)? as u16;
+    Ok(base | ((q & 0x20) << 8) | ((q & 0x18) << 7) | (q & 0x07))
+}
+
+/// Returns the opcode and status register bit of a conditional branch.
+fn conditional_branch(mnemonic: Mnemonic) -> (u16, u16) {
+    use Mnemonic as M;
+
+    let set = match mnemonic {
+        M::BREQ | M::BRCS | M::BRLO | M::BRMI | M::BRVS | M::BRLT | M::BRHS | M::BRTS | M::BRIE => {
+            0xf000
+        }
+        _ => 0xf400,
+    };
+    let bit = match mnemonic {
+        M::BRCS | M::BRLO | M::BRCC | M::BRSH => 0,
+        M::BREQ | M::BRNE => 1,
+        M::BRMI | M::BRPL => 2,
+        M::BRVS | M::BRVC => 3,
+        M::BRLT | M::BRGE => 4,
+        M::BRHS | M::BRHC => 5,
+        M::BRTS | M::BRTC => 6,
+        _ => 7,
+    };
+
+    (set, bit)
+}
+
+/// Returns the opcode and status register bit of a flag set or clear.
+fn flag(mnemonic: Mnemonic) -> (u16, u16) {
+    use Mnemonic as M;
+
+    let set = match mnemonic {
+        M::SEC | M::SEZ | M::SEN | M::SEV | M::SES | M::SEH | M::SET | M::SEI => 0x9408,
+        _ => 0x9488,
+    };
+    let bit = match mnemonic {
+        M::SEC | M::CLC => 0,
+        M::SEZ | M::CLZ => 1,
+        M::SEN | M::CLN => 2,
+        M::SEV | M::CLV => 3,
+        M::SES | M::CLS => 4,
+        M::SEH | M::CLH => 5,
+        M::SET | M::CLT => 6,
+        _ => 7,
+    };
+
+    (set, bit)
+}
+
+/// Encodes an instruction, positioned at the provided byte offset, against
+/// its resolved operands. Jump and branch targets are word addresses.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<Resolved>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    match (mnemonic, operands) {
+        (M::NOP, []) => words(&[0x0000]),
+        (M::RET, []) => words(&[0x9508]),
+        (M::RETI, []) => words(&[0x9518]),
+        (M::IJMP, []) => words(&[0x9409]),
+        (M::ICALL, []) => words(&[0x9509]),
+        (M::SLEEP, []) => words(&[0x9588]),
+        (M::BREAK, []) => words(&[0x9598]),
+        (M::WDR, []) => words(&[0x95a8]),
+        (M::SPM, []) => words(&[0x95e8]),
+        (M::LPM, []) => words(&[0x95c8]),
+        (M::ELPM, []) => words(&[0x95d8]),
+        (
+            M::SEC
+            | M::CLC
+            | M::SEZ
+            | M::CLZ
+            | M::SEN
+            | M::CLN
+            | M::SEV
+            | M::CLV
+            | M::SES
+            | M::CLS
+            | M::SEH
+            | M::CLH
+            | M::SET
+            | M::CLT
+            | M::SEI
+            | M::CLI,
+            [],
+        ) => {
+            let (opcode, bit) = flag(mnemonic);
+            words(&[opcode | (bit << 4)])
+        }
+        (M::BSET | M::BCLR, [O::Constant(bit)]) => {
+            let opcode = if mnemonic == M::BSET { 0x9408 } else { 0x9488 };
+            words(&[opcode | ((unsigned(*bit, 7)? as u16) << 4)])
+        }
+        (
+            M::ADD
+            | M::ADC
+            | M::SUB
+            | M::SBC
+            | M::AND
+            | M::OR
+            | M::EOR
+            | M::MOV
+            | M::CP
+            | M::CPC
+            | M::CPSE
+            | M::MUL,
+            [O::Register(d), O::Register(r)],
+        ) => {
+            let opcode = match mnemonic {
+                M::ADD => 0x0c00,
+                M::ADC => 0x1c00,
+                M::SUB => 0x1800,
+                M::SBC => 0x0800,
+                M::AND => 0x2000,
+                M::OR => 0x2800,
+                M::EOR => 0x2400,
+                M::MOV => 0x2c00,
+                M::CP => 0x1400,
+                M::CPC => 0x0400,
+                M::CPSE => 0x1000,
+                _ => 0x9c00,
+            };
+            words(&[two_registers(opcode, *d, *r)])
+        }
+        (M::LSL | M::ROL | M::TST | M::CLR, [O::Register(d)]) => {
+            let opcode = match mnemonic {
+                M::LSL => 0x0c00,
+                M::ROL => 0x1c00,
+                M::TST => 0x2000,
+                _ => 0x2400,
+            };
+            words(&[two_registers(opcode, *d, *d)])
+        }
+        (
+            M::SUBI | M::SBCI | M::ANDI | M::ORI | M::SBR | M::CBR | M::CPI | M::LDI,
+            [O::Register(d), O::Constant(k)],
+        ) => {
+            let (opcode, k) = match mnemonic {
+                M::SUBI => (0x5000, immediate(*k)?),
+                M::SBCI => (0x4000, immediate(*k)?),
+                M::ANDI => (0x7000, immediate(*k)?),
+                M::ORI | M::SBR => (0x6000, immediate(*k)?),
+                M::CBR => (0x7000, !immediate(*k)? & 0xff),
+                M::CPI => (0x3000, immediate(*k)?),
+                _ => (0xe000, immediate(*k)?),
+            };
+            words(&[register_immediate(opcode, *d, k)?])
+        }
+        (M::SER, [O::Register(d)]) => words(&[register_immediate(0xe000, *d, 0xff)?]),
+        (
+            M::COM
+            | M::NEG
+            | M::SWAP
+            | M::INC
+            | M::ASR
+            | M::LSR
+            | M::ROR
+            | M::DEC
+            | M::PUSH
+            | M::POP,
+            [O::Register(d)],
+        ) => {
+            let opcode = match mnemonic {
+                M::COM => 0x9400,
+                M::NEG => 0x9401,
+                M::SWAP => 0x9402,
+                M::INC => 0x9403,
+                M::ASR => 0x9405,
+                M::LSR => 0x9406,
+                M::ROR => 0x9407,
+                M::DEC => 0x940a,
+                M::PUSH => 0x920f,
+                _ => 0x900f,
+            };
+            words(&[opcode | (u16::from(*d) << 4)])
+        }
+        (M::ADIW | M::SBIW, [dst, O::Constant(k)]) => {
+            let d = match pair(dst)? {
+                d @ (24 | 26 | 28 | 30) => u16::from(d - 24) / 2,
+                _ => return Err(EncodeErr::InvalidOperands),
+            };
+            let k = unsigned(*k, 0x3f)? as u16;
+            let opcode = if mnemonic == M::ADIW { 0x9600 } else { 0x9700 };
+            words(&[opcode | ((k & 0x30) << 2) | (d << 4) | (k & 0x0f)])
+        }
+        (M::MOVW, [dst, src]) => {
+            let (d, r) = (u16::from(pair(dst)?), u16::from(pair(src)?));
+            words(&[0x0100 | ((d / 2) << 4) | (r / 2)])
+        }
+        (M::MULS, [O::Register(d), O::Register(r)]) => {
+            words(&[0x0200 | (upper(*d)? << 4) | upper(*r)?])
+        }
+        (M::MULSU | M::FMUL | M::FMULS | M::FMULSU, [O::Register(d), O::Register(r)]) => {
+            let opcode = match mnemonic {
+                M::MULSU => 0x0300,
+                M::FMUL => 0x0308,
+                M::FMULS => 0x0380,
+                _ => 0x0388,
+            };
+            words(&[opcode | (multiplicand(*d)? << 4) | multiplicand(*r)?])
+        }
+        (M::RJMP | M::RCALL, [O::Constant(target)]) => {
+            let opcode = if mnemonic == M::RJMP { 0xc000 } else { 0xd000 };
+            words(&[opcode | relative(position, *target, 12)?])
+        }
+        (M::JMP | M::CALL, [O::Constant(target)]) => {
+            let k = unsigned(*target, 0x3f_ffff)?;
+            let opcode = if mnemonic == M::JMP { 0x940c } else { 0x940e };
+            let high = (((k >> 17) & 0x1f) << 4) as u16 | ((k >> 16) & 0x01) as u16;
+            words(&[opcode | high, k as u16])
+        }
+        (M::BRBS | M::BRBC, [O::Constant(bit), O::Constant(target)]) => {
+            let opcode = if mnemonic == M::BRBS { 0xf000 } else { 0xf400 };
+            let bit = unsigned(*bit, 7)? as u16;
+            words(&[opcode | (relative(position, *target, 7)? << 3) | bit])
+        }
+        (
+            M::BREQ
+            | M::BRNE
+            | M::BRCS
+            | M::BRCC
+            | M::BRSH
+            | M::BRLO
+            | M::BRMI
+            | M::BRPL
+            | M::BRGE
+            | M::BRLT
+            | M::BRHS
+            | M::BRHC
+            | M::BRTS
+            | M::BRTC
+            | M::BRVS
+            | M::BRVC
+            | M::BRIE
+            | M::BRID,
+            [O::Constant(target)],
+        ) => {
+            let (opcode, bit) = conditional_branch(mnemonic);
+            words(&[opcode | (relative(position, *target, 7)? << 3) | bit])
+        }
+        (M::SBRC | M::SBRS | M::BST | M::BLD, [O::Register(d), O::Constant(bit)]) => {
+            let opcode = match mnemonic {
+                M::SBRC => 0xfc00,
+                M::SBRS => 0xfe00,
+                M::BST => 0xfa00,
+                _ => 0xf800,
+            };
+            words(&[opcode | (u16::from(*d) << 4) | unsigned(*bit, 7)? as u16])
+        }
+        (M::SBIC | M::SBIS | M::SBI | M::CBI, [O::Constant(address), O::Constant(bit)]) => {
+            let opcode = match mnemonic {
+                M::SBIC => 0x9900,
+                M::SBIS => 0x9b00,
+                M::SBI => 0x9a00,
+                _ => 0x9800,
+            };
+            let address = unsigned(*address, 0x1f)? as u16;
+            words(&[opcode | (address << 3) | unsigned(*bit, 7)? as u16])
+        }
+        (M::IN, [O::Register(d), O::Constant(address)]) => {
+            let address = unsigned(*address, 0x3f)? as u16;
+            words(&[0xb000 | ((address & 0x30) << 5) | (u16::from(*d) << 4) | (address & 0x0f)])
+        }
+        (M::OUT, [O::Constant(address), O::Register(r)]) => {
+            let address = unsigned(*address, 0x3f)? as u16;
+            words(&[0xb800 | ((address & 0x30) << 5) | (u16::from(*r) << 4) | (address & 0x0f)])
+        }
+        (M::LDS, [O::Register(d), O::Constant(address)]) => words(&[
+            0x9000 | (u16::from(*d) << 4),
+            unsigned(*address, 0xffff)? as u16,
+        ]),
+        (M::STS, [O::Constant(address), O::Register(r)]) => words(&[
+            0x9200 | (u16::from(*r) << 4),
+            unsigned(*address, 0xffff)? as u16,
+        ]),
+        (M::LD, [O::Register(d), src]) => words(&[indirect(src)? | (u16::from(*d) << 4)]),
+        (M::ST, [dst, O::Register(r)]) => words(&[indirect(dst)? | 0x0200 | (u16::from(*r) << 4)]),
+        (M::LDD, [O::Register(d), src]) => words(&[displacement(src)? | (u16::from(*d) << 4)]),
+        (M::STD, [dst, O::Register(r)]) => {
+            words(&[displacement(dst)? | 0x0200 | (u16::from(*r) << 4)])
+        }
+        (M::LPM | M::ELPM, [O::Register(d), src]) => {
+            let opcode = match (mnemonic, src) {
+                (M::LPM, O::Indirect(Pointer::Z)) => 0x9004,
+                (M::LPM, O::PostIncrement(Pointer::Z)) => 0x9005,
+                (M::ELPM, O::Indirect(Pointer::Z)) => 0x9006,
+                (M::ELPM, O::PostIncrement(Pointer::Z)) => 0x9007,
+                _ => return Err(EncodeErr::InvalidOperands),
+            };
+            words(&[opcode | (u16::from(*d) << 4)])
+        }
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::backends::avr::instruction_set::mnemonic::Mnemonic;
+use crate::backends::avr::instruction_set::{encode, EncodeErr, Operand, Pointer};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &[$($operand),*], 0));
+    };
+}
+
+#[test]
+fn should_encode_register_operations() {
+    gen_encode_test!(
+        Mnemonic::ADD,
+        [Operand::Register(1), Operand::Register(2)],
+        vec![0x12, 0x0c]
+    );
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [Operand::Register(31), Operand::Register(16)],
+        vec![0xf0, 0x2f]
+    );
+    gen_encode_test!(Mnemonic::CLR, [Operand::Register(24)], vec![0x88, 0x27]);
+    gen_encode_test!(Mnemonic::INC, [Operand::Register(16)], vec![0x03, 0x95]);
+    gen_encode_test!(Mnemonic::PUSH, [Operand::Register(28)], vec![0xcf, 0x93]);
+}
+
+#[test]
+fn should_encode_immediates_to_upper_registers() {
+    gen_encode_test!(
+        Mnemonic::LDI,
+        [Operand::Register(16), Operand::Constant(Some(0xff))],
+        vec![0x0f, 0xef]
+    );
+    gen_encode_test!(
+        Mnemonic::CPI,
+        [Operand::Register(17), Operand::Constant(Some(0x2a))],
+        vec![0x1a, 0x32]
+    );
+    gen_encode_test!(
+        Mnemonic::CBR,
+        [Operand::Register(16), Operand::Constant(Some(0x01))],
+        vec![0x0e, 0x7f]
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Mnemonic::LDI,
+            &[Operand::Register(15), Operand::Constant(Some(0x01))],
+            0
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x100)),
+        encode(
+            Mnemonic::LDI,
+            &[Operand::Register(16), Operand::Constant(Some(0x100))],
+            0
+        )
+    );
+}
+
+#[test]
+fn should_encode_register_pairs() {
+    gen_encode_test!(
+        Mnemonic::ADIW,
+        [Operand::RegisterPair(24), Operand::Constant(Some(1))],
+        vec![0x01, 0x96]
+    );
+    gen_encode_test!(
+        Mnemonic::SBIW,
+        [Operand::Register(30), Operand::Constant(Some(0x3f))],
+        vec![0xff, 0x97]
+    );
+    gen_encode_test!(
+        Mnemonic::MOVW,
+        [Operand::RegisterPair(30), Operand::RegisterPair(24)],
+        vec![0xfc, 0x01]
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Mnemonic::ADIW,
+            &[Operand::RegisterPair(22), Operand::Constant(Some(1))],
+            0
+        )
+    );
+}
+
+#[test]
+fn should_encode_loads_and_stores_through_pointers() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(0), Operand::PostIncrement(Pointer::X)],
+        vec![0x0d, 0x90]
+    );
+    gen_encode_test!(
+        Mnemonic::ST,
+        [Operand::PreDecrement(Pointer::Y), Operand::Register(1)],
+        vec![0x1a, 0x92]
+    );
+    gen_encode_test!(
+        Mnemonic::LDD,
+        [
+            Operand::Register(24),
+            Operand::Displacement(Pointer::Y, Some(0x3f))
+        ],
+        vec![0x8f, 0xad]
+    );
+    gen_encode_test!(
+        Mnemonic::STD,
+        [
+            Operand::Displacement(Pointer::Z, Some(2)),
+            Operand::Register(16)
+        ],
+        vec![0x02, 0x83]
+    );
+    gen_encode_test!(
+        Mnemonic::LPM,
+        [Operand::Register(0), Operand::PostIncrement(Pointer::Z)],
+        vec![0x05, 0x90]
+    );
+    gen_encode_test!(
+        Mnemonic::STS,
+        [Operand::Constant(Some(0x0100)), Operand::Register(16)],
+        vec![0x00, 0x93, 0x00, 0x01]
+    );
+}
+
+#[test]
+fn should_encode_io_and_bit_instructions() {
+    gen_encode_test!(
+        Mnemonic::OUT,
+        [Operand::Constant(Some(0x3f)), Operand::Register(0)],
+        vec![0x0f, 0xbe]
+    );
+    gen_encode_test!(
+        Mnemonic::IN,
+        [Operand::Register(16), Operand::Constant(Some(0x16))],
+        vec![0x06, 0xb3]
+    );
+    gen_encode_test!(
+        Mnemonic::SBI,
+        [Operand::Constant(Some(0x17)), Operand::Constant(Some(5))],
+        vec![0xbd, 0x9a]
+    );
+    gen_encode_test!(Mnemonic::SEI, [], vec![0x78, 0x94]);
+    gen_encode_test!(Mnemonic::CLI, [], vec![0xf8, 0x94]);
+}
+
+#[test]
+fn should_encode_jumps_in_program_words() {
+    gen_encode_test!(
+        Mnemonic::RJMP,
+        [Operand::Constant(Some(0))],
+        vec![0xff, 0xcf]
+    );
+    gen_encode_test!(
+        Mnemonic::RCALL,
+        [Operand::Constant(Some(5))],
+        vec![0x04, 0xd0]
+    );
+    gen_encode_test!(
+        Mnemonic::JMP,
+        [Operand::Constant(Some(0x1234))],
+        vec![0x0c, 0x94, 0x34, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::CALL,
+        [Operand::Constant(Some(0x3f_ffff))],
+        vec![0xff, 0x95, 0xff, 0xff]
+    );
+    assert_eq!(
+        Ok(vec![0x01, 0xc0]),
+        encode(Mnemonic::RJMP, &[Operand::Constant(Some(0x12))], 0x20)
+    );
+}
+
+#[test]
+fn should_encode_branches_as_7_bit_word_offsets() {
+    gen_encode_test!(
+        Mnemonic::BRNE,
+        [Operand::Constant(Some(0))],
+        vec![0xf9, 0xf7]
+    );
+    gen_encode_test!(
+        Mnemonic::BREQ,
+        [Operand::Constant(Some(64))],
+        vec![0xf9, 0xf1]
+    );
+    gen_encode_test!(
+        Mnemonic::BRBS,
+        [Operand::Constant(Some(6)), Operand::Constant(Some(1))],
+        vec![0x06, 0xf0]
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(64)),
+        encode(Mnemonic::BRNE, &[Operand::Constant(Some(65))], 0)
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(2048)),
+        encode(Mnemonic::RJMP, &[Operand::Constant(Some(2049))], 0)
+    );
+}
+
+#[test]
+fn should_leave_unresolved_targets_unvalidated() {
+    gen_encode_test!(Mnemonic::RJMP, [Operand::Constant(None)], vec![0x00, 0xc0]);
+    gen_encode_test!(
+        Mnemonic::CALL,
+        [Operand::Constant(None)],
+        vec![0x0e, 0x94, 0x00, 0x00]
+    );
+}
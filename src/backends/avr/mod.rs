@@ -0,0 +1,107 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::avr::instruction_set::{encode, EncodeErr, Instruction, Operand, Value};
+use crate::backends::layout::{
+    self, parse_instruction_with, resolve_value, InstructionSet, SymbolTable,
+};
+use crate::backends::BackendErr;
+
+/// AvrAssembler implements the [InstructionSet] of the classic AVR.
+#[derive(Default)]
+pub struct AvrAssembler;
+
+impl AvrAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Resolves the wrapped value with [resolve_value], applying any selection of
+/// its bytes or byte address.
+fn resolve_selection(symbol_table: &SymbolTable, value: &Value) -> Result<i64, BackendErr> {
+    match value {
+        Value::Plain(v) => resolve_value(symbol_table, v),
+        Value::Lo8(v) => resolve_selection(symbol_table, v).map(|v| v & 0xff),
+        Value::Hi8(v) => resolve_selection(symbol_table, v).map(|v| (v >> 8) & 0xff),
+        Value::Byte(v) => resolve_selection(symbol_table, v).map(|v| v * 2),
+    }
+}
+
+impl InstructionSet for AvrAssembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// The size of an AVR instruction depends only on its mnemonic, allowing
+    /// it to be sized with every value left unresolved.
+    fn size_of(&self, _: &SymbolTable, instruction: &Instruction) -> usize {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|_| Ok::<_, ()>(None)))
+            .collect::<Result<Vec<_>, ()>>()
+            .unwrap_or_default();
+
+        encode(instruction.mnemonic, &operands, 0)
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        if position % 2 != 0 {
+            return Err(BackendErr::Unspecified(format!(
+                "{:?} at {:#06x} is not aligned to a program word",
+                instruction.mnemonic, position
+            )));
+        }
+
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_selection(symbol_table, v).map(Some)))
+            .collect::<Result<Vec<_>, BackendErr>>()?;
+
+        encode(instruction.mnemonic, &operands, position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                BackendErr::BranchOutOfRange(target(instruction), offset)
+            }
+        })
+    }
+
+    /// Program memory is addressed in 16-bit words, so labels take the word
+    /// address of their byte position.
+    fn label_value(&self, position: usize) -> usize {
+        position / 2
+    }
+
+    /// `jmp` and `call` address up to 4M words of program memory.
+    fn address_width(&self) -> u32 {
+        22
+    }
+}
+
+/// Returns the name of a jump or branch target, being its last operand.
+fn target(instruction: &Instruction) -> String {
+    match instruction.operands.last() {
+        Some(Operand::Constant(Value::Plain(layout::Value::Reference(id)))) => id.clone(),
+        operand => format!("{:?}", operand),
+    }
+}
@@ -0,0 +1,162 @@
+use crate::backends::avr::instruction_set::mnemonic::Mnemonic;
+use crate::backends::avr::instruction_set::{Instruction, Operand, Pointer, Value};
+use crate::backends::layout;
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches any operand. Registers and pointers are tried ahead of values so
+/// that a register, such as `r16` or `z`, isn't read as a reference to a
+/// symbol of the same name.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(expect_character('-'), pointer()))
+        .map(Operand::PreDecrement)
+        .or(|| register_pair())
+        .or(|| register().map(Operand::Register))
+        .or(|| {
+            join(
+                pointer(),
+                optional(right(join(expect_character('+'), optional(value())))),
+            )
+            .map(|(pointer, increment)| match increment {
+                None => Operand::Indirect(pointer),
+                Some(None) => Operand::PostIncrement(pointer),
+                Some(Some(q)) => Operand::Displacement(pointer, q),
+            })
+        })
+        .or(|| signed_value().map(Operand::Constant))
+}
+
+/// Matches a register pair, given high register first, such as `r25:r24`.
+fn register_pair<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(register(), right(join(expect_character(':'), register())))
+        .predicate(|(high, low)| *high == low + 1)
+        .map(|(_, low)| Operand::RegisterPair(low))
+}
+
+fn register<'a>() -> impl parcel::Parser<'a, &'a [char], u8> {
+    name()
+        .map(|name| match name.to_lowercase().strip_prefix('r') {
+            Some(n) if !n.starts_with('0') || n == "0" => n.parse::<u8>().ok(),
+            _ => None,
+        })
+        .predicate(|register| matches!(register, Some(0..=31)))
+        .map(|register| register.unwrap())
+}
+
+fn pointer<'a>() -> impl parcel::Parser<'a, &'a [char], Pointer> {
+    name()
+        .map(|name| match name.to_lowercase().as_str() {
+            "x" => Some(Pointer::X),
+            "y" => Some(Pointer::Y),
+            "z" => Some(Pointer::Z),
+            _ => None,
+        })
+        .predicate(|pointer| pointer.is_some())
+        .map(|pointer| pointer.unwrap())
+}
+
+/// Matches a value, allowing a leading `-` on literals for immediates such
+/// as `ldi r16, -1`.
+#[allow(clippy::redundant_closure)]
+fn signed_value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    right(join(expect_character('-'), unsigned32()))
+        .map(|v| Value::Plain(layout::Value::Literal(-i64::from(v))))
+        .or(|| value())
+}
+
+/// Matches a value, optionally selecting its low or high byte with
+/// `lo8(value)` or `hi8(value)`.
+#[allow(clippy::redundant_closure)]
+fn value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    applied("lo8", address())
+        .map(|v| Value::Lo8(Box::new(v)))
+        .or(|| applied("hi8", address()).map(|v| Value::Hi8(Box::new(v))))
+        .or(|| address())
+}
+
+/// Matches a value, optionally taking the byte address of a label with
+/// `byte(value)`.
+#[allow(clippy::redundant_closure)]
+fn address<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    applied("byte", literal_or_reference())
+        .map(|v| Value::Byte(Box::new(v)))
+        .or(|| literal_or_reference())
+}
+
+/// Matches an operator, such as `lo8`, applied to the parenthesized
+/// argument matched by `argument`.
+fn applied<'a, P>(function: &'static str, argument: P) -> impl parcel::Parser<'a, &'a [char], Value>
+where
+    P: parcel::Parser<'a, &'a [char], Value>,
+{
+    right(join(
+        join(expect_str(function), expect_character('(')),
+        left(join(
+            join(zero_or_more(non_newline_whitespace()), argument),
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(')'),
+            ),
+        )),
+    ))
+    .map(|(_, v)| v)
+}
+
+#[allow(clippy::redundant_closure)]
+fn literal_or_reference<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    unsigned32()
+        .map(|v| Value::Plain(layout::Value::Literal(i64::from(v))))
+        .or(|| name().map(|id| Value::Plain(layout::Value::Reference(id))))
+}
+
+/// Matches a name, allowing trailing digits for registers such as `r31`.
+#[allow(clippy::redundant_closure)]
+fn name<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
+    join(alphabetic(), zero_or_more(alphabetic().or(|| digit(10))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
@@ -0,0 +1,142 @@
+use crate::backends::avr::instruction_set::mnemonic::Mnemonic;
+use crate::backends::avr::instruction_set::{Instruction, Operand, Pointer, Value};
+use crate::backends::avr::parser::instruction;
+use crate::backends::layout;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operands:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operands)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_instructions_without_operands() {
+    gen_inst_test!("  nop", Mnemonic::NOP, vec![]);
+    gen_inst_test!("RETI  ", Mnemonic::RETI, vec![]);
+}
+
+#[test]
+fn should_parse_registers_ahead_of_references() {
+    gen_inst_test!(
+        "mov r0, R31",
+        Mnemonic::MOV,
+        vec![Operand::Register(0), Operand::Register(31)]
+    );
+    gen_inst_test!(
+        "ldi r16, r32",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(16),
+            Operand::Constant(Value::Plain(layout::Value::Reference("r32".to_string())))
+        ]
+    );
+    gen_inst_test!(
+        "ldi r16, -1",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(16),
+            Operand::Constant(Value::Plain(layout::Value::Literal(-1)))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_register_pairs() {
+    gen_inst_test!(
+        "adiw r25:r24, 1",
+        Mnemonic::ADIW,
+        vec![
+            Operand::RegisterPair(24),
+            Operand::Constant(Value::Plain(layout::Value::Literal(1)))
+        ]
+    );
+    gen_inst_test!(
+        "movw r31:r30, r25:r24",
+        Mnemonic::MOVW,
+        vec![Operand::RegisterPair(30), Operand::RegisterPair(24)]
+    );
+}
+
+#[test]
+fn should_parse_each_pointer_addressing_mode() {
+    gen_inst_test!(
+        "ld r0, x",
+        Mnemonic::LD,
+        vec![Operand::Register(0), Operand::Indirect(Pointer::X)]
+    );
+    gen_inst_test!(
+        "ld r0, Z+",
+        Mnemonic::LD,
+        vec![Operand::Register(0), Operand::PostIncrement(Pointer::Z)]
+    );
+    gen_inst_test!(
+        "st -y, r1",
+        Mnemonic::ST,
+        vec![Operand::PreDecrement(Pointer::Y), Operand::Register(1)]
+    );
+    gen_inst_test!(
+        "ldd r24, y+0x3f",
+        Mnemonic::LDD,
+        vec![
+            Operand::Register(24),
+            Operand::Displacement(Pointer::Y, Value::Plain(layout::Value::Literal(0x3f)))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_byte_selection_of_values() {
+    gen_inst_test!(
+        "ldi r30, lo8(table)",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(30),
+            Operand::Constant(Value::Lo8(Box::new(Value::Plain(
+                layout::Value::Reference("table".to_string())
+            ))))
+        ]
+    );
+    gen_inst_test!(
+        "ldi r31, hi8( 0x1234 )",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(31),
+            Operand::Constant(Value::Hi8(Box::new(Value::Plain(layout::Value::Literal(
+                0x1234
+            )))))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_byte_addresses_of_labels() {
+    gen_inst_test!(
+        "ldi r30, lo8(byte(table))",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(30),
+            Operand::Constant(Value::Lo8(Box::new(Value::Byte(Box::new(Value::Plain(
+                layout::Value::Reference("table".to_string())
+            ))))))
+        ]
+    );
+    gen_inst_test!(
+        "ldi r31, byte( table )",
+        Mnemonic::LDI,
+        vec![
+            Operand::Register(31),
+            Operand::Constant(Value::Byte(Box::new(Value::Plain(
+                layout::Value::Reference("table".to_string())
+            ))))
+        ]
+    );
+}
@@ -0,0 +1,292 @@
+use std::convert::TryFrom;
+
+use crate::backends::layout::{assemble_source, AssembledOrigins};
+use crate::backends::mos6502::CycleCount;
+use crate::backends::table::description::IsaDescription;
+use crate::backends::{
+    avr, chip8, i8080, mc6800, mc6809, mcs51, mos6502, rv32i, sm83, table, z80, Backend,
+};
+use crate::{preparse, Assembler, AssemblerOptions, AssemblerResult, PreparsedOrigins};
+
+/// Mos6502Family represents a backend for a member of the 6502 family, each
+/// sharing an assembler that defaults to a different processor.
+#[derive(Debug, Clone, Copy)]
+pub struct Mos6502Family {
+    name: &'static str,
+    cpu: mos6502::instruction_set::Cpu,
+}
+
+pub const MOS6502: Mos6502Family = Mos6502Family {
+    name: "mos6502",
+    cpu: mos6502::instruction_set::Cpu::Nmos6502,
+};
+
+pub const MOS65C02: Mos6502Family = Mos6502Family {
+    name: "65c02",
+    cpu: mos6502::instruction_set::Cpu::Cmos65c02,
+};
+
+pub const W65C02: Mos6502Family = Mos6502Family {
+    name: "w65c02",
+    cpu: mos6502::instruction_set::Cpu::Wdc65c02,
+};
+
+pub const W65816: Mos6502Family = Mos6502Family {
+    name: "65816",
+    cpu: mos6502::instruction_set::Cpu::Wdc65816,
+};
+
+impl Mos6502Family {
+    /// Preparses the source and configures an assembler for the processor
+    /// from the provided options.
+    fn prepare(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> Result<(mos6502::Mos6502Assembler, PreparsedOrigins), String> {
+        let origin_tokens = preparse(source, options.definitions, 0)?;
+
+        let mut assembler = mos6502::Mos6502Assembler::new();
+        if let Some(diagnostics) = options.diagnostics {
+            assembler = assembler.with_diagnostics(diagnostics);
+        }
+        if options.relax_branches {
+            assembler = assembler.with_branch_relaxation();
+        }
+
+        let cpu = match options.cpu {
+            Some(cpu) => mos6502::instruction_set::Cpu::try_from(cpu.as_str())?,
+            None => self.cpu,
+        };
+
+        Ok((assembler.with_cpu(cpu), origin_tokens))
+    }
+
+    /// Assembles the source as [Backend::assemble] does, additionally summing
+    /// the cycles taken by the instructions from the start label up to, but
+    /// not including, the end label.
+    pub fn assemble_counting_cycles(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+        start: &str,
+        end: &str,
+    ) -> AssemblerResult<(AssembledOrigins, CycleCount), String> {
+        let (assembler, origin_tokens) = self.prepare(source, options)?;
+
+        assembler
+            .assemble_counting_cycles(origin_tokens, start, end)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Returns the member of the 6502 family selected by name, such as `65c02`.
+pub fn mos6502_family(name: &str) -> Option<Mos6502Family> {
+    [MOS6502, MOS65C02, W65C02, W65816]
+        .into_iter()
+        .find(|family| family.name == name)
+}
+
+impl Backend for Mos6502Family {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        let (assembler, origin_tokens) = self.prepare(source, options)?;
+
+        assembler.assemble(origin_tokens).map_err(|e| e.to_string())
+    }
+}
+
+/// Generates a backend for an instruction set that targets a single
+/// processor with its preparsed origins beginning at zero.
+macro_rules! single_cpu_backend {
+    ($backend:ident, $name:literal, $isa:path) => {
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct $backend;
+
+        impl Backend for $backend {
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn assemble(
+                &self,
+                source: &str,
+                options: AssemblerOptions,
+            ) -> AssemblerResult<AssembledOrigins, String> {
+                if let Some(cpu) = &options.cpu {
+                    return Err(format!("unknown cpu for the {} backend: {}", $name, cpu));
+                }
+
+                assemble_source(&<$isa>::new(), source, options, 0)
+            }
+        }
+    };
+}
+
+single_cpu_backend!(Z80, "z80", z80::Z80Assembler);
+single_cpu_backend!(Mc6809, "6809", mc6809::Mc6809Assembler);
+single_cpu_backend!(Rv32i, "rv32i", rv32i::Rv32iAssembler);
+single_cpu_backend!(Sm83, "sm83", sm83::Sm83Assembler);
+single_cpu_backend!(Avr, "avr", avr::AvrAssembler);
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct I8080;
+
+impl Backend for I8080 {
+    fn name(&self) -> &str {
+        "i8080"
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        let cpu = match &options.cpu {
+            Some(cpu) => i8080::instruction_set::Cpu::try_from(cpu.as_str())?,
+            None => i8080::instruction_set::Cpu::I8080,
+        };
+        let isa = i8080::I8080Assembler::new().with_cpu(cpu);
+
+        assemble_source(&isa, source, options, 0)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Mc6800;
+
+impl Backend for Mc6800 {
+    fn name(&self) -> &str {
+        "6800"
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        let cpu = match &options.cpu {
+            Some(cpu) => mc6800::instruction_set::Cpu::try_from(cpu.as_str())?,
+            None => mc6800::instruction_set::Cpu::M6800,
+        };
+        let isa = mc6800::Mc6800Assembler::new().with_cpu(cpu);
+
+        assemble_source(&isa, source, options, 0)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Chip8;
+
+impl Backend for Chip8 {
+    fn name(&self) -> &str {
+        "chip8"
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        if let Some(cpu) = &options.cpu {
+            return Err(format!("unknown cpu for the chip8 backend: {}", cpu));
+        }
+
+        assemble_source(
+            &chip8::Chip8Assembler::new(),
+            source,
+            options,
+            chip8::DEFAULT_ORIGIN,
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Mcs51;
+
+impl Backend for Mcs51 {
+    fn name(&self) -> &str {
+        "mcs51"
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        if let Some(cpu) = &options.cpu {
+            return Err(format!("unknown cpu for the mcs51 backend: {}", cpu));
+        }
+        // the special function registers and bits are defined ahead of
+        // any definitions, allowing them to be overridden.
+        let definitions = mcs51::symbols::predefined()
+            .into_iter()
+            .chain(options.definitions)
+            .collect();
+        let options = AssemblerOptions {
+            definitions,
+            ..options
+        };
+
+        assemble_source(&mcs51::Mcs51Assembler::new(), source, options, 0)
+    }
+}
+
+/// Table represents an instruction set loaded from a description, such as
+/// one given by `--isa-file`.
+#[derive(Debug, Clone)]
+pub struct Table {
+    description: IsaDescription,
+}
+
+impl Table {
+    pub fn new(description: IsaDescription) -> Self {
+        Self { description }
+    }
+}
+
+impl Backend for Table {
+    fn name(&self) -> &str {
+        "table"
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        if let Some(cpu) = &options.cpu {
+            return Err(format!("unknown cpu for the table backend: {}", cpu));
+        }
+        let isa = table::TableAssembler::new(self.description.clone());
+
+        assemble_source(&isa, source, options, 0)
+    }
+}
+
+/// Returns each backend built into spasm, excluding [Table] as it requires
+/// a description.
+pub fn all() -> Vec<Box<dyn Backend>> {
+    vec![
+        Box::new(MOS6502),
+        Box::new(MOS65C02),
+        Box::new(W65C02),
+        Box::new(W65816),
+        Box::new(Z80),
+        Box::new(I8080),
+        Box::new(Mc6800),
+        Box::new(Mc6809),
+        Box::new(Rv32i),
+        Box::new(Chip8),
+        Box::new(Sm83),
+        Box::new(Mcs51),
+        Box::new(Avr),
+    ]
+}
@@ -0,0 +1,29 @@
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    ($($mnemonic:ident),*) => {
+        /// Mnemonic represents the mnemonics of the CHIP-8 interpreter, as
+        /// named in Cowgod's technical reference.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($mnemonic,)*
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($mnemonic) => Ok(Self::$mnemonic),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    ADD, AND, CALL, CLS, DRW, JP, LD, OR, RET, RND, SE, SHL, SHR, SKNP, SKP, SNE, SUB, SUBN, SYS,
+    XOR
+);
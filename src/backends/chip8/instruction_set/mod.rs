@@ -0,0 +1,146 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+
+/// Operand represents a single operand of a CHIP-8 instruction, generalized
+/// over its value so that operands may be parsed with references and encoded
+/// once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    /// One of the sixteen general purpose registers, `v0` through `vf`.
+    Register(u8),
+    /// The 12-bit address register, `i`.
+    I,
+    /// The memory addressed by `i`, given as `[i]`.
+    IndirectI,
+    /// The delay timer, `dt`.
+    DelayTimer,
+    /// The sound timer, `st`.
+    SoundTimer,
+    /// A key press, `k`, awaited by `ld vx, k`.
+    Key,
+    /// The font sprite of a digit, `f`, as loaded by `ld f, vx`.
+    Font,
+    /// The binary-coded decimal representation, `b`, as stored by
+    /// `ld b, vx`.
+    Bcd,
+    /// An address, byte or nibble, depending on the instruction.
+    Immediate(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        match self {
+            Self::Register(x) => Ok(Operand::Register(*x)),
+            Self::I => Ok(Operand::I),
+            Self::IndirectI => Ok(Operand::IndirectI),
+            Self::DelayTimer => Ok(Operand::DelayTimer),
+            Self::SoundTimer => Ok(Operand::SoundTimer),
+            Self::Key => Ok(Operand::Key),
+            Self::Font => Ok(Operand::Font),
+            Self::Bcd => Ok(Operand::Bcd),
+            Self::Immediate(v) => f(v).map(Operand::Immediate),
+        }
+    }
+}
+
+/// Instruction represents a single CHIP-8 mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+}
+
+/// Every CHIP-8 instruction is encoded as a single 16-bit opcode.
+pub const INSTRUCTION_SIZE: usize = 2;
+
+fn bounded(value: i64, max: i64) -> Result<u16, EncodeErr> {
+    match value {
+        v if (0..=max).contains(&v) => Ok(v as u16),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn address(value: i64) -> Result<u16, EncodeErr> {
+    bounded(value, 0xfff)
+}
+
+fn byte(value: i64) -> Result<u16, EncodeErr> {
+    bounded(value, 0xff)
+}
+
+fn nibble(value: i64) -> Result<u16, EncodeErr> {
+    bounded(value, 0xf)
+}
+
+/// Encodes an instruction to its big-endian opcode, validating that each
+/// address fits in 12 bits, and each byte and nibble in their widths.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<i64>]) -> Result<Vec<u8>, EncodeErr> {
+    use Mnemonic as M;
+    use Operand::*;
+
+    let xy = |x: &u8, y: &u8| (u16::from(*x) << 8) | (u16::from(*y) << 4);
+    let x = |x: &u8| u16::from(*x) << 8;
+
+    let opcode: u16 = match (mnemonic, operands) {
+        (M::CLS, []) => 0x00e0,
+        (M::RET, []) => 0x00ee,
+        (M::SYS, [Immediate(nnn)]) => address(*nnn)?,
+        (M::JP, [Immediate(nnn)]) => 0x1000 | address(*nnn)?,
+        (M::JP, [Register(0), Immediate(nnn)]) => 0xb000 | address(*nnn)?,
+        (M::CALL, [Immediate(nnn)]) => 0x2000 | address(*nnn)?,
+        (M::SE, [Register(vx), Immediate(kk)]) => 0x3000 | x(vx) | byte(*kk)?,
+        (M::SNE, [Register(vx), Immediate(kk)]) => 0x4000 | x(vx) | byte(*kk)?,
+        (M::SE, [Register(vx), Register(vy)]) => 0x5000 | xy(vx, vy),
+        (M::LD, [Register(vx), Immediate(kk)]) => 0x6000 | x(vx) | byte(*kk)?,
+        (M::ADD, [Register(vx), Immediate(kk)]) => 0x7000 | x(vx) | byte(*kk)?,
+        (M::LD, [Register(vx), Register(vy)]) => 0x8000 | xy(vx, vy),
+        (M::OR, [Register(vx), Register(vy)]) => 0x8001 | xy(vx, vy),
+        (M::AND, [Register(vx), Register(vy)]) => 0x8002 | xy(vx, vy),
+        (M::XOR, [Register(vx), Register(vy)]) => 0x8003 | xy(vx, vy),
+        (M::ADD, [Register(vx), Register(vy)]) => 0x8004 | xy(vx, vy),
+        (M::SUB, [Register(vx), Register(vy)]) => 0x8005 | xy(vx, vy),
+        (M::SHR, [Register(vx)]) => 0x8006 | x(vx),
+        (M::SHR, [Register(vx), Register(vy)]) => 0x8006 | xy(vx, vy),
+        (M::SUBN, [Register(vx), Register(vy)]) => 0x8007 | xy(vx, vy),
+        (M::SHL, [Register(vx)]) => 0x800e | x(vx),
+        (M::SHL, [Register(vx), Register(vy)]) => 0x800e | xy(vx, vy),
+        (M::SNE, [Register(vx), Register(vy)]) => 0x9000 | xy(vx, vy),
+        (M::LD, [I, Immediate(nnn)]) => 0xa000 | address(*nnn)?,
+        (M::RND, [Register(vx), Immediate(kk)]) => 0xc000 | x(vx) | byte(*kk)?,
+        (M::DRW, [Register(vx), Register(vy), Immediate(n)]) => 0xd000 | xy(vx, vy) | nibble(*n)?,
+        (M::SKP, [Register(vx)]) => 0xe09e | x(vx),
+        (M::SKNP, [Register(vx)]) => 0xe0a1 | x(vx),
+        (M::LD, [Register(vx), DelayTimer]) => 0xf007 | x(vx),
+        (M::LD, [Register(vx), Key]) => 0xf00a | x(vx),
+        (M::LD, [DelayTimer, Register(vx)]) => 0xf015 | x(vx),
+        (M::LD, [SoundTimer, Register(vx)]) => 0xf018 | x(vx),
+        (M::ADD, [I, Register(vx)]) => 0xf01e | x(vx),
+        (M::LD, [Font, Register(vx)]) => 0xf029 | x(vx),
+        (M::LD, [Bcd, Register(vx)]) => 0xf033 | x(vx),
+        (M::LD, [IndirectI, Register(vx)]) => 0xf055 | x(vx),
+        (M::LD, [Register(vx), IndirectI]) => 0xf065 | x(vx),
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(opcode.to_be_bytes().to_vec())
+}
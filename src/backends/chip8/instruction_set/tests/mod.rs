@@ -0,0 +1,126 @@
+use crate::backends::chip8::instruction_set::mnemonic::Mnemonic;
+use crate::backends::chip8::instruction_set::{encode, EncodeErr, Operand};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, $operands:expr, $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &$operands));
+    };
+}
+
+#[test]
+fn should_encode_instructions_without_operands() {
+    gen_encode_test!(Mnemonic::CLS, [], vec![0x00, 0xe0]);
+    gen_encode_test!(Mnemonic::RET, [], vec![0x00, 0xee]);
+}
+
+#[test]
+fn should_encode_address_operands() {
+    gen_encode_test!(Mnemonic::JP, [Operand::Immediate(0x234)], vec![0x12, 0x34]);
+    gen_encode_test!(
+        Mnemonic::JP,
+        [Operand::Register(0), Operand::Immediate(0x300)],
+        vec![0xb3, 0x00]
+    );
+    gen_encode_test!(
+        Mnemonic::CALL,
+        [Operand::Immediate(0x2a0)],
+        vec![0x22, 0xa0]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::I, Operand::Immediate(0x456)],
+        vec![0xa4, 0x56]
+    );
+}
+
+#[test]
+fn should_encode_register_operands() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(0xa), Operand::Immediate(0x12)],
+        vec![0x6a, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::SUB,
+        [Operand::Register(1), Operand::Register(2)],
+        vec![0x81, 0x25]
+    );
+    gen_encode_test!(Mnemonic::SHR, [Operand::Register(3)], vec![0x83, 0x06]);
+    gen_encode_test!(
+        Mnemonic::DRW,
+        [
+            Operand::Register(1),
+            Operand::Register(2),
+            Operand::Immediate(5)
+        ],
+        vec![0xd1, 0x25]
+    );
+    gen_encode_test!(Mnemonic::SKNP, [Operand::Register(4)], vec![0xe4, 0xa1]);
+}
+
+#[test]
+fn should_encode_special_register_operands() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(5), Operand::DelayTimer],
+        vec![0xf5, 0x07]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::SoundTimer, Operand::Register(5)],
+        vec![0xf5, 0x18]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Bcd, Operand::Register(6)],
+        vec![0xf6, 0x33]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::IndirectI, Operand::Register(7)],
+        vec![0xf7, 0x55]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(7), Operand::IndirectI],
+        vec![0xf7, 0x65]
+    );
+}
+
+#[test]
+fn should_error_on_values_exceeding_their_width() {
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x1000)),
+        encode(Mnemonic::JP, &[Operand::Immediate(0x1000)])
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x100)),
+        encode(
+            Mnemonic::LD,
+            &[Operand::Register(0), Operand::Immediate(0x100)]
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x10)),
+        encode(
+            Mnemonic::DRW,
+            &[
+                Operand::Register(0),
+                Operand::Register(1),
+                Operand::Immediate(0x10)
+            ]
+        )
+    );
+}
+
+#[test]
+fn should_error_on_invalid_operands() {
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(Mnemonic::CLS, &[Operand::Register(0)])
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(Mnemonic::JP, &[Operand::Register(1), Operand::Immediate(0)])
+    );
+}
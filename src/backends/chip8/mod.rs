@@ -0,0 +1,69 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::chip8::instruction_set::{encode, EncodeErr, Instruction, INSTRUCTION_SIZE};
+use crate::backends::layout::{parse_instruction_with, resolve_value, InstructionSet, SymbolTable};
+use crate::backends::BackendErr;
+use crate::preparser::types::Endianness;
+
+/// The address CHIP-8 interpreters load programs at, as the interpreter
+/// itself historically occupied the memory below it.
+pub const DEFAULT_ORIGIN: usize = 0x200;
+
+/// Chip8Assembler implements the [InstructionSet] of the CHIP-8 virtual
+/// machine.
+#[derive(Default)]
+pub struct Chip8Assembler;
+
+impl Chip8Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl InstructionSet for Chip8Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    fn size_of(&self, _: &SymbolTable, _: &Instruction) -> usize {
+        INSTRUCTION_SIZE
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        _: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_value(symbol_table, v)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        encode(instruction.mnemonic, &operands).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+        })
+    }
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Big
+    }
+
+    /// Instructions address the 4K of memory with 12 bits.
+    fn address_width(&self) -> u32 {
+        12
+    }
+}
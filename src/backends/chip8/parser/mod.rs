@@ -0,0 +1,106 @@
+use crate::backends::chip8::instruction_set::mnemonic::Mnemonic;
+use crate::backends::chip8::instruction_set::{Instruction, Operand, Value};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches any operand. Registers are tried ahead of values so that a
+/// register, such as `dt` or `b`, isn't read as a reference to a symbol of
+/// the same name.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    indirect()
+        .or(|| register())
+        .or(|| value().map(Operand::Immediate))
+}
+
+/// Matches the memory addressed by `i`, given as `[i]`.
+#[allow(clippy::redundant_closure)]
+fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(
+        join(
+            expect_character('['),
+            zero_or_more(non_newline_whitespace()),
+        ),
+        join(
+            expect_character('i').or(|| expect_character('I')),
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(']'),
+            ),
+        ),
+    )
+    .map(|_| Operand::IndirectI)
+}
+
+#[allow(clippy::redundant_closure)]
+fn register<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(alphabetic(), zero_or_more(alphabetic().or(|| digit(10))))
+        .map(|(head, tail)| keyword(&std::iter::once(head).chain(tail).collect::<String>()))
+        .predicate(|register| register.is_some())
+        .map(|register| register.unwrap())
+}
+
+fn keyword(name: &str) -> Option<Operand<Value>> {
+    let name = name.to_lowercase();
+    let register = match name.as_str() {
+        "i" => Operand::I,
+        "dt" => Operand::DelayTimer,
+        "st" => Operand::SoundTimer,
+        "k" => Operand::Key,
+        "f" => Operand::Font,
+        "b" => Operand::Bcd,
+        _ => {
+            let index = name.strip_prefix('v')?;
+            match index.len() {
+                1 => Operand::Register(u8::from_str_radix(index, 16).ok()?),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(register)
+}
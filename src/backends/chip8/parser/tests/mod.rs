@@ -0,0 +1,77 @@
+use crate::backends::chip8::instruction_set::mnemonic::Mnemonic;
+use crate::backends::chip8::instruction_set::{Instruction, Operand, Value};
+use crate::backends::chip8::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operands:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operands)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_instructions_without_operands() {
+    gen_inst_test!("  cls", Mnemonic::CLS, vec![]);
+    gen_inst_test!("RET  ", Mnemonic::RET, vec![]);
+}
+
+#[test]
+fn should_parse_register_and_immediate_operands() {
+    gen_inst_test!(
+        "ld v0, $2a",
+        Mnemonic::LD,
+        vec![
+            Operand::Register(0),
+            Operand::Immediate(Value::Literal(0x2a))
+        ]
+    );
+    gen_inst_test!(
+        "DRW VA,VF,5",
+        Mnemonic::DRW,
+        vec![
+            Operand::Register(0xa),
+            Operand::Register(0xf),
+            Operand::Immediate(Value::Literal(5))
+        ]
+    );
+    gen_inst_test!(
+        "ld i, sprite",
+        Mnemonic::LD,
+        vec![
+            Operand::I,
+            Operand::Immediate(Value::Reference("sprite".to_string()))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_special_registers_ahead_of_labels() {
+    gen_inst_test!(
+        "ld dt, v1",
+        Mnemonic::LD,
+        vec![Operand::DelayTimer, Operand::Register(1)]
+    );
+    gen_inst_test!(
+        "ld b, v2",
+        Mnemonic::LD,
+        vec![Operand::Bcd, Operand::Register(2)]
+    );
+    gen_inst_test!(
+        "ld v3, [ i ]",
+        Mnemonic::LD,
+        vec![Operand::Register(3), Operand::IndirectI]
+    );
+    gen_inst_test!(
+        "jp bounce",
+        Mnemonic::JP,
+        vec![Operand::Immediate(Value::Reference("bounce".to_string()))]
+    );
+}
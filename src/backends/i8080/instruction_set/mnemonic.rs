@@ -0,0 +1,68 @@
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    ($($mnemonic:ident),*) => {
+        /// Mnemonic represents the Intel mnemonics of the 8080 and 8085.
+        /// Whether a mnemonic is valid is left to the target cpu.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($mnemonic,)*
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($mnemonic) => Ok(Self::$mnemonic),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    MOV, MVI, LXI, LDA, STA, LHLD, SHLD, LDAX, STAX, XCHG, ADD, ADC, SUB, SBB, ANA, XRA, ORA, CMP,
+    ADI, ACI, SUI, SBI, ANI, XRI, ORI, CPI, INR, DCR, INX, DCX, DAD, DAA, CMA, STC, CMC, RLC, RRC,
+    RAL, RAR, JMP, JNZ, JZ, JNC, JC, JPO, JPE, JP, JM, CALL, CNZ, CZ, CNC, CC, CPO, CPE, CP, CM,
+    RET, RNZ, RZ, RNC, RC, RPO, RPE, RP, RM, RST, PCHL, SPHL, XTHL, PUSH, POP, IN, OUT, EI, DI,
+    HLT, NOP, RIM, SIM
+);
+
+impl Mnemonic {
+    /// Returns the opcode of a conditional jump, call or return, with its
+    /// condition encoded.
+    pub fn conditional_opcode(self) -> Option<u8> {
+        let (base, condition) = match self {
+            Self::JNZ => (0xc2, 0),
+            Self::JZ => (0xc2, 1),
+            Self::JNC => (0xc2, 2),
+            Self::JC => (0xc2, 3),
+            Self::JPO => (0xc2, 4),
+            Self::JPE => (0xc2, 5),
+            Self::JP => (0xc2, 6),
+            Self::JM => (0xc2, 7),
+            Self::CNZ => (0xc4, 0),
+            Self::CZ => (0xc4, 1),
+            Self::CNC => (0xc4, 2),
+            Self::CC => (0xc4, 3),
+            Self::CPO => (0xc4, 4),
+            Self::CPE => (0xc4, 5),
+            Self::CP => (0xc4, 6),
+            Self::CM => (0xc4, 7),
+            Self::RNZ => (0xc0, 0),
+            Self::RZ => (0xc0, 1),
+            Self::RNC => (0xc0, 2),
+            Self::RC => (0xc0, 3),
+            Self::RPO => (0xc0, 4),
+            Self::RPE => (0xc0, 5),
+            Self::RP => (0xc0, 6),
+            Self::RM => (0xc0, 7),
+            _ => return None,
+        };
+
+        Some(base | (condition << 3))
+    }
+}
@@ -0,0 +1,280 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+use std::convert::TryFrom;
+
+/// Cpu represents the members of the 8080 family being targeted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Cpu {
+    /// The original 8080.
+    #[default]
+    I8080,
+    /// The 8085, adding the `rim` and `sim` interrupt mask instructions.
+    I8085,
+}
+
+impl TryFrom<&str> for Cpu {
+    type Error = String;
+
+    fn try_from(src: &str) -> Result<Self, Self::Error> {
+        match src {
+            "8080" | "i8080" => Ok(Self::I8080),
+            "8085" | "i8085" => Ok(Self::I8085),
+            _ => Err(format!("unknown cpu: {}", src)),
+        }
+    }
+}
+
+/// Register represents the operands naming a register, a register pair or
+/// the memory addressed by `hl`. Register pairs are named by their high
+/// register, with `b`, `d` and `h` naming either depending on the mnemonic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    A,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+    /// The memory addressed by the `hl` pair.
+    M,
+    SP,
+    /// The accumulator and flags, as pushed and popped as a pair.
+    PSW,
+}
+
+impl Register {
+    /// Returns the 3-bit encoding of an 8-bit register within an opcode.
+    fn code(self) -> Option<u8> {
+        match self {
+            Self::B => Some(0),
+            Self::C => Some(1),
+            Self::D => Some(2),
+            Self::E => Some(3),
+            Self::H => Some(4),
+            Self::L => Some(5),
+            Self::M => Some(6),
+            Self::A => Some(7),
+            Self::SP | Self::PSW => None,
+        }
+    }
+
+    /// Returns the 2-bit encoding of a register pair, with `sp` as the
+    /// fourth pair.
+    fn pair_code(self) -> Option<u8> {
+        match self {
+            Self::B => Some(0),
+            Self::D => Some(1),
+            Self::H => Some(2),
+            Self::SP => Some(3),
+            _ => None,
+        }
+    }
+
+    /// As pair_code, with `psw` taking the place of `sp` as it does for
+    /// stack operations.
+    fn stack_pair_code(self) -> Option<u8> {
+        match self {
+            Self::PSW => Some(3),
+            Self::SP => None,
+            pair => pair.pair_code(),
+        }
+    }
+}
+
+/// Operand represents either a register or a value, generalized over the
+/// value so that operands may be parsed with references and encoded once
+/// resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Register(Register),
+    Immediate(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        match self {
+            Self::Register(r) => Ok(Operand::Register(*r)),
+            Self::Immediate(v) => f(v).map(Operand::Immediate),
+        }
+    }
+}
+
+/// Instruction represents a single 8080 mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    UnsupportedInstruction(Cpu),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+fn byte(value: i64) -> Result<u8, EncodeErr> {
+    match value {
+        -0x80..=0xff => Ok(value as u8),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn word(opcode: u8, value: i64) -> Encoding {
+    match value {
+        -0x8000..=0xffff => {
+            let [lo, hi] = (value as u16).to_le_bytes();
+            Ok(vec![opcode, lo, hi])
+        }
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn register(r: &Register) -> Result<u8, EncodeErr> {
+    r.code().ok_or(EncodeErr::InvalidOperands)
+}
+
+fn pair(r: &Register) -> Result<u8, EncodeErr> {
+    r.pair_code().ok_or(EncodeErr::InvalidOperands)
+}
+
+/// Encodes an instruction for the provided cpu against its resolved
+/// operands.
+pub fn encode(cpu: Cpu, mnemonic: Mnemonic, operands: &[Operand<i64>]) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    if let Some(opcode) = mnemonic.conditional_opcode() {
+        return match (mnemonic, operands) {
+            (M::RNZ | M::RZ | M::RNC | M::RC | M::RPO | M::RPE | M::RP | M::RM, []) => {
+                Ok(vec![opcode])
+            }
+            (_, [O::Immediate(address)]) => word(opcode, *address),
+            _ => Err(EncodeErr::InvalidOperands),
+        };
+    }
+
+    match (mnemonic, operands) {
+        (M::RIM | M::SIM, []) if cpu != Cpu::I8085 => Err(EncodeErr::UnsupportedInstruction(cpu)),
+        (m, []) => implied(m),
+        (M::MOV, [O::Register(Register::M), O::Register(Register::M)]) => {
+            Err(EncodeErr::InvalidOperands)
+        }
+        (M::MOV, [O::Register(dst), O::Register(src)]) => {
+            Ok(vec![0x40 | (register(dst)? << 3) | register(src)?])
+        }
+        (M::MVI, [O::Register(dst), O::Immediate(value)]) => {
+            Ok(vec![0x06 | (register(dst)? << 3), byte(*value)?])
+        }
+        (M::LXI, [O::Register(rp), O::Immediate(value)]) => word(0x01 | (pair(rp)? << 4), *value),
+        (M::LDAX | M::STAX, [O::Register(rp)]) => {
+            let base = if mnemonic == M::LDAX { 0x0a } else { 0x02 };
+            match rp {
+                Register::B | Register::D => Ok(vec![base | (pair(rp)? << 4)]),
+                _ => Err(EncodeErr::InvalidOperands),
+            }
+        }
+        (M::LDA, [O::Immediate(address)]) => word(0x3a, *address),
+        (M::STA, [O::Immediate(address)]) => word(0x32, *address),
+        (M::LHLD, [O::Immediate(address)]) => word(0x2a, *address),
+        (M::SHLD, [O::Immediate(address)]) => word(0x22, *address),
+        (M::JMP, [O::Immediate(address)]) => word(0xc3, *address),
+        (M::CALL, [O::Immediate(address)]) => word(0xcd, *address),
+        (
+            M::ADD | M::ADC | M::SUB | M::SBB | M::ANA | M::XRA | M::ORA | M::CMP,
+            [O::Register(src)],
+        ) => {
+            let base = match mnemonic {
+                M::ADD => 0x80,
+                M::ADC => 0x88,
+                M::SUB => 0x90,
+                M::SBB => 0x98,
+                M::ANA => 0xa0,
+                M::XRA => 0xa8,
+                M::ORA => 0xb0,
+                _ => 0xb8,
+            };
+            Ok(vec![base | register(src)?])
+        }
+        (
+            M::ADI | M::ACI | M::SUI | M::SBI | M::ANI | M::XRI | M::ORI | M::CPI,
+            [O::Immediate(value)],
+        ) => {
+            let opcode = match mnemonic {
+                M::ADI => 0xc6,
+                M::ACI => 0xce,
+                M::SUI => 0xd6,
+                M::SBI => 0xde,
+                M::ANI => 0xe6,
+                M::XRI => 0xee,
+                M::ORI => 0xf6,
+                _ => 0xfe,
+            };
+            Ok(vec![opcode, byte(*value)?])
+        }
+        (M::INR, [O::Register(r)]) => Ok(vec![0x04 | (register(r)? << 3)]),
+        (M::DCR, [O::Register(r)]) => Ok(vec![0x05 | (register(r)? << 3)]),
+        (M::INX, [O::Register(rp)]) => Ok(vec![0x03 | (pair(rp)? << 4)]),
+        (M::DCX, [O::Register(rp)]) => Ok(vec![0x0b | (pair(rp)? << 4)]),
+        (M::DAD, [O::Register(rp)]) => Ok(vec![0x09 | (pair(rp)? << 4)]),
+        (M::PUSH | M::POP, [O::Register(rp)]) => {
+            let base = if mnemonic == M::PUSH { 0xc5 } else { 0xc1 };
+            let code = rp.stack_pair_code().ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![base | (code << 4)])
+        }
+        (M::RST, [O::Immediate(vector)]) => match vector {
+            0..=7 => Ok(vec![0xc7 | ((*vector as u8) << 3)]),
+            _ => Err(EncodeErr::OutOfRange(*vector)),
+        },
+        (M::IN, [O::Immediate(port)]) => Ok(vec![0xdb, byte(*port)?]),
+        (M::OUT, [O::Immediate(port)]) => Ok(vec![0xd3, byte(*port)?]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes the instructions that take no operands.
+fn implied(mnemonic: Mnemonic) -> Encoding {
+    use Mnemonic as M;
+
+    let opcode = match mnemonic {
+        M::XCHG => 0xeb,
+        M::DAA => 0x27,
+        M::CMA => 0x2f,
+        M::STC => 0x37,
+        M::CMC => 0x3f,
+        M::RLC => 0x07,
+        M::RRC => 0x0f,
+        M::RAL => 0x17,
+        M::RAR => 0x1f,
+        M::RET => 0xc9,
+        M::PCHL => 0xe9,
+        M::SPHL => 0xf9,
+        M::XTHL => 0xe3,
+        M::EI => 0xfb,
+        M::DI => 0xf3,
+        M::HLT => 0x76,
+        M::NOP => 0x00,
+        M::RIM => 0x20,
+        M::SIM => 0x30,
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(vec![opcode])
+}
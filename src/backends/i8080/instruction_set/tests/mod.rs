@@ -0,0 +1,90 @@
+use crate::backends::i8080::instruction_set::mnemonic::Mnemonic;
+use crate::backends::i8080::instruction_set::{encode, Cpu, EncodeErr, Operand, Register};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], $expected:expr) => {
+        assert_eq!(Ok($expected), encode(Cpu::I8080, $mnemonic, &[$($operand),*]));
+    };
+}
+
+#[test]
+fn should_encode_register_operands() {
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [
+            Operand::Register(Register::A),
+            Operand::Register(Register::M)
+        ],
+        vec![0x7e]
+    );
+    gen_encode_test!(Mnemonic::INR, [Operand::Register(Register::L)], vec![0x2c]);
+    gen_encode_test!(Mnemonic::CMP, [Operand::Register(Register::B)], vec![0xb8]);
+}
+
+#[test]
+fn should_encode_register_pair_operands() {
+    gen_encode_test!(
+        Mnemonic::LXI,
+        [Operand::Register(Register::SP), Operand::Immediate(0x1234)],
+        vec![0x31, 0x34, 0x12]
+    );
+    gen_encode_test!(Mnemonic::DAD, [Operand::Register(Register::D)], vec![0x19]);
+    gen_encode_test!(
+        Mnemonic::PUSH,
+        [Operand::Register(Register::PSW)],
+        vec![0xf5]
+    );
+    gen_encode_test!(Mnemonic::LDAX, [Operand::Register(Register::D)], vec![0x1a]);
+}
+
+#[test]
+fn should_encode_conditional_jumps_calls_and_returns() {
+    gen_encode_test!(
+        Mnemonic::JNZ,
+        [Operand::Immediate(0x0100)],
+        vec![0xc2, 0x00, 0x01]
+    );
+    gen_encode_test!(
+        Mnemonic::CM,
+        [Operand::Immediate(0x0100)],
+        vec![0xfc, 0x00, 0x01]
+    );
+    gen_encode_test!(Mnemonic::RPE, [], vec![0xe8]);
+}
+
+#[test]
+fn should_reject_invalid_operands() {
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Cpu::I8080,
+            Mnemonic::MOV,
+            &[
+                Operand::Register(Register::M),
+                Operand::Register(Register::M)
+            ]
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Cpu::I8080,
+            Mnemonic::STAX,
+            &[Operand::Register(Register::H)]
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(8)),
+        encode(Cpu::I8080, Mnemonic::RST, &[Operand::Immediate(8)])
+    );
+}
+
+#[test]
+fn should_only_encode_rim_and_sim_for_the_8085() {
+    assert_eq!(
+        Err(EncodeErr::UnsupportedInstruction(Cpu::I8080)),
+        encode(Cpu::I8080, Mnemonic::RIM, &[])
+    );
+    assert_eq!(Ok(vec![0x20]), encode(Cpu::I8085, Mnemonic::RIM, &[]));
+    assert_eq!(Ok(vec![0x30]), encode(Cpu::I8085, Mnemonic::SIM, &[]));
+}
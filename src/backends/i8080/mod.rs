@@ -0,0 +1,78 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::i8080::instruction_set::{encode, Cpu, EncodeErr, Instruction, Operand};
+use crate::backends::layout::{parse_instruction_with, resolve_value, InstructionSet, SymbolTable};
+use crate::backends::BackendErr;
+
+/// I8080Assembler implements the [InstructionSet] of the 8080 family.
+#[derive(Default)]
+pub struct I8080Assembler {
+    cpu: Cpu,
+}
+
+impl I8080Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// with_cpu sets the member of the 8080 family that instructions are
+    /// assembled for, defaulting to the 8080.
+    pub fn with_cpu(mut self, cpu: Cpu) -> Self {
+        self.cpu = cpu;
+        self
+    }
+}
+
+impl InstructionSet for I8080Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// The size of an 8080 instruction depends only on its mnemonic and the
+    /// form of its operands, allowing it to be sized with every value zeroed.
+    fn size_of(&self, _: &SymbolTable, instruction: &Instruction) -> usize {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|_| Ok::<i64, ()>(0)))
+            .collect::<Result<Vec<Operand<i64>>, ()>>()
+            .unwrap_or_default();
+
+        encode(self.cpu, instruction.mnemonic, &operands)
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        _: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_value(symbol_table, v)))
+            .collect::<Result<Vec<Operand<i64>>, BackendErr>>()?;
+
+        encode(self.cpu, instruction.mnemonic, &operands).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::UnsupportedInstruction(cpu) => BackendErr::UndefinedInstruction(format!(
+                "{:?} is unsupported by the {:?}",
+                instruction.mnemonic, cpu
+            )),
+        })
+    }
+}
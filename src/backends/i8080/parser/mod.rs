@@ -0,0 +1,96 @@
+use crate::backends::i8080::instruction_set::mnemonic::Mnemonic;
+use crate::backends::i8080::instruction_set::{Instruction, Operand, Register, Value};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    intel_hex_u16()
+        .or(|| unsigned16())
+        .map(|v| Operand::Immediate(Value::Literal(i64::from(v))))
+        .or(|| named())
+}
+
+/// Matches an Intel-style hexadecimal value, such as `0FFH`, which must begin
+/// with a decimal digit to distinguish it from a label.
+fn intel_hex_u16<'a>() -> impl parcel::Parser<'a, &'a [char], u16> {
+    left(join(
+        join(decimal(), zero_or_more(hex_digit())),
+        expect_character('H').or(|| expect_character('h')),
+    ))
+    .map(|(head, tail)| {
+        let digits = std::iter::once(head).chain(tail).collect::<String>();
+        u16::from_str_radix(&digits, 16)
+    })
+    .predicate(|res| res.is_ok())
+    .map(|res| res.unwrap())
+}
+
+/// Matches a register by name, otherwise treating the name as a reference to
+/// a symbol or label. Register names are case-insensitive.
+fn named<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    name().map(|name| {
+        let register = match name.to_lowercase().as_str() {
+            "a" => Some(Register::A),
+            "b" => Some(Register::B),
+            "c" => Some(Register::C),
+            "d" => Some(Register::D),
+            "e" => Some(Register::E),
+            "h" => Some(Register::H),
+            "l" => Some(Register::L),
+            "m" => Some(Register::M),
+            "sp" => Some(Register::SP),
+            "psw" => Some(Register::PSW),
+            _ => None,
+        };
+
+        match register {
+            Some(r) => Operand::Register(r),
+            None => Operand::Immediate(Value::Reference(name)),
+        }
+    })
+}
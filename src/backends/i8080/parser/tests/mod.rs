@@ -0,0 +1,63 @@
+use crate::backends::i8080::instruction_set::mnemonic::Mnemonic;
+use crate::backends::i8080::instruction_set::{Instruction, Operand, Register, Value};
+use crate::backends::i8080::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, [$($operand:expr),*]) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, vec![$($operand),*])
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_intel_mnemonics() {
+    gen_inst_test!("  xchg", Mnemonic::XCHG, []);
+    gen_inst_test!(
+        "MOV M,A",
+        Mnemonic::MOV,
+        [
+            Operand::Register(Register::M),
+            Operand::Register(Register::A)
+        ]
+    );
+}
+
+#[test]
+fn should_parse_h_suffixed_hex() {
+    gen_inst_test!(
+        "MVI A,05H",
+        Mnemonic::MVI,
+        [
+            Operand::Register(Register::A),
+            Operand::Immediate(Value::Literal(0x05))
+        ]
+    );
+    gen_inst_test!(
+        "lxi sp, 0FFFFh",
+        Mnemonic::LXI,
+        [
+            Operand::Register(Register::SP),
+            Operand::Immediate(Value::Literal(0xffff))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_label_references() {
+    gen_inst_test!(
+        "LXI H,label",
+        Mnemonic::LXI,
+        [
+            Operand::Register(Register::H),
+            Operand::Immediate(Value::Reference("label".to_string()))
+        ]
+    );
+}
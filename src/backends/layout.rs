@@ -0,0 +1,756 @@
+use std::collections::HashMap;
+
+use parcel::prelude::v1::*;
+
+use crate::addressing::Positional;
+use crate::backends::BackendErr;
+use crate::diagnostics::{Diagnostic, DiagnosticSender, Severity, SourceLocation};
+use crate::preparser::expression::Expression;
+use crate::preparser::types::{Endianness, LeByteEncodedValue, Reify};
+use crate::preparser::{
+    Assertion, CycleAssertion, Message, MessageFragment, PrimitiveOrReference, Token,
+};
+use crate::{preparse, AssemblerOptions, AssemblerResult, Origin};
+
+pub type UnparsedTokenStream = Vec<Token<String>>;
+pub type PositionalTokenStream<T> = Vec<Positional<Token<T>>>;
+pub type MemoryAlignedStream<T> = Vec<Positional<InstructionOrConstant<T, PrimitiveOrReference>>>;
+pub type PositionalDirectives = Vec<Positional<Directive>>;
+pub type AssembledOrigins = Vec<Origin<Vec<u8>>>;
+pub type Layout<T> = (
+    SymbolTable,
+    PositionalDirectives,
+    Vec<Origin<MemoryAlignedStream<T>>>,
+);
+
+/// The number of passes layout may take before its sizes are considered to
+/// never settle.
+const MAX_LAYOUT_PASSES: usize = 64;
+
+impl Reify<u8> for LeByteEncodedValue {
+    type Error = crate::preparser::types::TypeError;
+
+    fn reify(&self) -> Result<u8, Self::Error> {
+        match self.bits() {
+            0 => Ok(0),
+            1..=8 => Ok(self.to_vec().first().copied().unwrap_or(0)),
+            _ => Err(Self::Error::IllegalType(format!(
+                "bit-width {}",
+                self.bits()
+            ))),
+        }
+    }
+}
+
+impl Reify<u16> for LeByteEncodedValue {
+    type Error = crate::preparser::types::TypeError;
+
+    fn reify(&self) -> Result<u16, Self::Error> {
+        match self.bits() {
+            0 => Ok(0),
+            1..=8 => Reify::<u8>::reify(self).map(u16::from),
+            9..=16 => {
+                let bytes = self.to_vec();
+                Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+            }
+            _ => Err(Self::Error::IllegalType(format!(
+                "bit-width {}",
+                self.bits()
+            ))),
+        }
+    }
+}
+
+impl Reify<u32> for LeByteEncodedValue {
+    type Error = crate::preparser::types::TypeError;
+
+    fn reify(&self) -> Result<u32, Self::Error> {
+        match self.bits() {
+            0..=16 => Reify::<u16>::reify(self).map(u32::from),
+            17..=32 => {
+                let bytes = self.to_vec();
+                Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            _ => Err(Self::Error::IllegalType(format!(
+                "bit-width {}",
+                self.bits()
+            ))),
+        }
+    }
+}
+
+type SymbolMap = HashMap<String, LeByteEncodedValue>;
+
+/// SymbolTable maps each symbol and label to its resolved value, shared by
+/// every backend.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SymbolTable {
+    symbols: SymbolMap,
+}
+
+impl SymbolTable {
+    fn new(symbols: SymbolMap) -> Self {
+        Self { symbols }
+    }
+
+    pub fn get(&self, k: &str) -> Option<LeByteEncodedValue> {
+        self.symbols.get(k).cloned()
+    }
+
+    pub fn get_as_u8(&self, k: &str) -> Option<u8> {
+        self.get(k)
+            .map(|lebev| lebev.reify())
+            .and_then(|res| res.ok())
+    }
+
+    pub fn get_as_u16(&self, k: &str) -> Option<u16> {
+        self.get(k)
+            .map(|lebev| Reify::<u16>::reify(&lebev))
+            .and_then(|res| res.ok())
+    }
+
+    pub fn get_as_u32(&self, k: &str) -> Option<u32> {
+        self.get(k)
+            .map(|lebev| Reify::<u32>::reify(&lebev))
+            .and_then(|res| res.ok())
+    }
+
+    pub fn insert(&mut self, k: &str, v: LeByteEncodedValue) -> Option<LeByteEncodedValue> {
+        self.symbols.insert(k.to_string(), v)
+    }
+}
+
+impl From<Vec<SymbolTable>> for SymbolTable {
+    fn from(src: Vec<SymbolTable>) -> Self {
+        let symbols = src
+            .into_iter()
+            .map(|st| st.symbols)
+            .fold(SymbolMap::new(), |acc, sm| {
+                acc.into_iter().chain(sm).collect()
+            });
+
+        Self::new(symbols)
+    }
+}
+
+/// Directive represents any statement that is evaluated only once all symbols
+/// have been resolved, and that emits no bytes of its own.
+pub enum Directive {
+    Assertion(Assertion),
+    CycleAssertion(CycleAssertion),
+    Message(Message),
+}
+
+/// Stores either an instruction or a constant with either value being
+/// generalized as these values are commonly transformed through the pipeline.
+#[derive(Clone)]
+pub enum InstructionOrConstant<T, U> {
+    Instruction(T),
+    Constant(U),
+}
+
+/// Parses the text of each instruction in an origin with the provided parser,
+/// passing all other tokens through unchanged.
+pub fn parse_origin<T, E>(
+    source: Origin<UnparsedTokenStream>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Origin<Vec<Token<T>>>, E> {
+    let origin_offset = source.offset;
+    let tokens = source
+        .instructions
+        .into_iter()
+        .map(|tok| match tok {
+            Token::Instruction(inst, location) => {
+                parse(&inst).map(|inst| Token::Instruction(inst, location))
+            }
+            Token::Symbol(id, v) => Ok(Token::Symbol(id, v)),
+            Token::Constant(v) => Ok(Token::Constant(v)),
+            Token::Assertion(a) => Ok(Token::Assertion(a)),
+            Token::CycleAssertion(a) => Ok(Token::CycleAssertion(a)),
+            Token::Message(m) => Ok(Token::Message(m)),
+            Token::Phase(offset) => Ok(Token::Phase(offset)),
+            Token::Dephase => Ok(Token::Dephase),
+            Token::Cpu(name, location) => Ok(Token::Cpu(name, location)),
+            Token::RegisterWidth(register, width, location) => {
+                Ok(Token::RegisterWidth(register, width, location))
+            }
+            Token::Vectors(v) => Ok(Token::Vectors(v)),
+        })
+        .collect::<Result<Vec<Token<T>>, E>>()?;
+
+    Ok(Origin::with_offset(origin_offset, tokens))
+}
+
+/// Annotates a given instruction with it's corresponding address offset
+/// position. Positions track the location counter that labels resolve to,
+/// which only diverges from the emit position within a `.phase` block.
+/// Instructions are sized by the provided function, typically against the
+/// symbols resolved by any prior pass.
+pub fn position_origin<T>(
+    source: Origin<Vec<Token<T>>>,
+    size_of: impl Fn(&T) -> usize,
+) -> Origin<PositionalTokenStream<T>> {
+    let origin_offset = source.offset;
+    let tokens = source.instructions;
+    let positional_instructions = tokens
+        .into_iter()
+        .fold(
+            (origin_offset, origin_offset, Vec::new()),
+            |(emit_offset, offset, mut tokens), token| match token {
+                Token::Instruction(i, location) => {
+                    let size = size_of(&i);
+                    tokens.push(Positional::with_position(
+                        offset,
+                        Token::Instruction(i, location),
+                    ));
+                    (emit_offset + size, offset + size, tokens)
+                }
+                Token::Phase(run_offset) => (emit_offset, run_offset, tokens),
+                Token::Dephase => (emit_offset, emit_offset, tokens),
+                t => {
+                    tokens.push(Positional::with_position(offset, t));
+                    (emit_offset, offset, tokens)
+                }
+            },
+        )
+        .2;
+
+    Origin::with_offset(origin_offset, positional_instructions)
+}
+
+/// Walks the source, collecting all symbols and labels into a symbol table and
+/// setting aside any directives for evaluation once all symbols are known.
+/// Labels resolve to their position, as converted by the provided function.
+pub fn collect_symbols<T>(
+    source: Origin<PositionalTokenStream<T>>,
+    label_value: impl Fn(usize) -> usize,
+) -> (
+    SymbolTable,
+    PositionalDirectives,
+    Origin<MemoryAlignedStream<T>>,
+) {
+    let (origin_offset, instructions) = source.into();
+    let (symbol_table, directives, tokens) = instructions.into_iter().fold(
+        (SymbolTable::default(), Vec::new(), Vec::new()),
+        |(mut st, mut directives, mut insts), positional_token| {
+            let offset = positional_token.position;
+            let token = positional_token.unwrap();
+            match token {
+                Token::Instruction(i, _) => {
+                    insts.push(Positional::with_position(
+                        offset,
+                        InstructionOrConstant::Instruction(i),
+                    ));
+                    (st, directives, insts)
+                }
+                Token::Constant(bvol) => {
+                    insts.push(Positional::with_position(
+                        offset,
+                        InstructionOrConstant::Constant(bvol),
+                    ));
+                    (st, directives, insts)
+                }
+                Token::Symbol(l, None) => {
+                    // labels are encoded at the narrowest of 16 or 32 bits,
+                    // allowing for the 24-bit addresses of the 65816.
+                    let label = label_value(offset);
+                    let value = match u16::try_from(label) {
+                        Ok(label) => LeByteEncodedValue::from(label),
+                        Err(_) => LeByteEncodedValue::from(label as u32),
+                    };
+                    st.insert(&l, value);
+                    (st, directives, insts)
+                }
+                Token::Symbol(id, Some(bv)) => {
+                    st.insert(&id, bv);
+                    (st, directives, insts)
+                }
+                Token::Assertion(a) => {
+                    directives.push(Positional::with_position(offset, Directive::Assertion(a)));
+                    (st, directives, insts)
+                }
+                Token::CycleAssertion(a) => {
+                    directives.push(Positional::with_position(
+                        offset,
+                        Directive::CycleAssertion(a),
+                    ));
+                    (st, directives, insts)
+                }
+                Token::Message(m) => {
+                    directives.push(Positional::with_position(offset, Directive::Message(m)));
+                    (st, directives, insts)
+                }
+                // phase boundaries are fully accounted for in positions,
+                // processor state is applied to each instruction on parse and
+                // vectors are placed in their own origin once assembled.
+                Token::Phase(_)
+                | Token::Dephase
+                | Token::Cpu(..)
+                | Token::RegisterWidth(..)
+                | Token::Vectors(_) => (st, directives, insts),
+            }
+        },
+    );
+    (
+        symbol_table,
+        directives,
+        Origin::with_offset(origin_offset, tokens),
+    )
+}
+
+/// Evaluates an expression against the global symbol table with `*` resolved
+/// to the provided offset.
+pub fn evaluate_expression(
+    symbol_table: &SymbolTable,
+    offset: usize,
+    location: SourceLocation,
+    expression: &Expression,
+) -> Result<i64, BackendErr> {
+    let lookup = |id: &str| symbol_table.get_as_u32(id).map(i64::from);
+
+    expression
+        .evaluate(offset, &lookup)
+        .map_err(|e| BackendErr::Unspecified(format!("{}: {}", location, e)))
+}
+
+/// Evaluates each directive, in source order, against the global symbol
+/// table. Failed error-level assertions and `.error` directives halt assembly
+/// while all other messages are reported to the diagnostics channel, if one
+/// is provided. Cycle assertions are checked against the base cycles counted
+/// between their labels by the provided function.
+pub fn evaluate_directives(
+    symbol_table: &SymbolTable,
+    directives: PositionalDirectives,
+    diagnostics: Option<&DiagnosticSender>,
+    cycles_between: &dyn Fn(&str, &str) -> Result<usize, BackendErr>,
+) -> Result<(), BackendErr> {
+    for positional_directive in directives {
+        let offset = positional_directive.position;
+        let diagnostic = match positional_directive.unwrap() {
+            Directive::Assertion(assertion) => {
+                let holds = evaluate_expression(
+                    symbol_table,
+                    offset,
+                    assertion.location,
+                    &assertion.expression,
+                )? != 0;
+
+                match (holds, assertion.severity) {
+                    (true, _) => None,
+                    (false, Severity::Error) => {
+                        return Err(BackendErr::AssertionFailed(
+                            assertion.location,
+                            assertion.message,
+                        ))
+                    }
+                    (false, severity) => Some(Diagnostic::new(
+                        severity,
+                        assertion.location,
+                        format!("assertion failed: {}", assertion.message),
+                    )),
+                }
+            }
+            Directive::CycleAssertion(assertion) => {
+                let cycles = cycles_between(&assertion.start, &assertion.end)?;
+
+                if cycles != assertion.cycles {
+                    return Err(BackendErr::AssertionFailed(
+                        assertion.location,
+                        format!(
+                            "expected {} cycles from {} to {}, found {}",
+                            assertion.cycles, assertion.start, assertion.end, cycles
+                        ),
+                    ));
+                }
+                None
+            }
+            Directive::Message(message) => {
+                let text = message
+                    .fragments
+                    .iter()
+                    .map(|fragment| match fragment {
+                        MessageFragment::Text(text) => Ok(text.clone()),
+                        MessageFragment::Expression(expr) => {
+                            evaluate_expression(symbol_table, offset, message.location, expr)
+                                .map(|v| v.to_string())
+                        }
+                    })
+                    .collect::<Result<String, BackendErr>>()?;
+
+                match message.severity {
+                    Severity::Error => return Err(BackendErr::UserError(message.location, text)),
+                    severity => Some(Diagnostic::new(severity, message.location, text)),
+                }
+            }
+        };
+
+        match (diagnostic, diagnostics) {
+            // a closed receiver signals the caller has no interest in
+            // diagnostics, so failing to send is safe to ignore.
+            (Some(diagnostic), Some(sender)) => {
+                let _ = sender.send(diagnostic);
+            }
+            _ => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves a constant to its value, dereferencing any symbol it references.
+pub fn resolve_constant(
+    symbol_table: &SymbolTable,
+    constant: PrimitiveOrReference,
+) -> Result<LeByteEncodedValue, BackendErr> {
+    match constant {
+        PrimitiveOrReference::Primitive(bv) => Ok(bv),
+        PrimitiveOrReference::Reference(id) => symbol_table
+            .get(&id)
+            .ok_or(BackendErr::UndefinedReference(id)),
+    }
+}
+
+/// Value represents a numeric operand that is either given literally or
+/// references a symbol to be resolved once all symbols are known.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Literal(i64),
+    Reference(String),
+}
+
+/// Resolves a value to its literal, dereferencing any symbol it references.
+pub fn resolve_value(symbol_table: &SymbolTable, value: &Value) -> Result<i64, BackendErr> {
+    match value {
+        Value::Literal(v) => Ok(*v),
+        Value::Reference(id) => symbol_table
+            .get_as_u32(id)
+            .map(i64::from)
+            .ok_or_else(|| BackendErr::UndefinedReference(id.clone())),
+    }
+}
+
+/// Parses the text of a single instruction with the provided parser,
+/// returning an error naming any text that is left unparsed.
+pub fn parse_instruction_with<T>(
+    source: &str,
+    parse: impl for<'a> Fn(&'a [char]) -> ParseResult<'a, &'a [char], T>,
+) -> Result<T, String> {
+    let input = source.chars().collect::<Vec<char>>();
+    match parse(&input) {
+        Ok(MatchStatus::Match((rem, inst))) if rem.is_empty() => Ok(inst),
+        Ok(MatchStatus::Match((rem, _))) | Ok(MatchStatus::NoMatch(rem)) => Err(format!(
+            "unspecified parse error: {}",
+            rem.iter().collect::<String>()
+        )),
+        Err(e) => Err(format!("unspecified parse error: {}", e)),
+    }
+}
+
+/// InstructionSet provides the parsing, sizing and encoding of a backend's
+/// instructions, leaving layout and symbol resolution to the shared pipeline.
+pub trait InstructionSet {
+    type Instruction: Clone;
+
+    /// Parses the text of a single instruction.
+    fn parse_instruction(&self, source: &str) -> Result<Self::Instruction, String>;
+
+    /// Returns the size, in bytes, of an instruction against the symbols
+    /// resolved by the prior layout pass. Instructions referencing symbols
+    /// that are yet to be resolved must be sized at their widest, with sizes
+    /// only ever shrinking from pass to pass, so that layout settles.
+    /// Instructions that can't be encoded may be sized at zero, as they are
+    /// reported once encoded.
+    fn size_of(&self, symbol_table: &SymbolTable, instruction: &Self::Instruction) -> usize;
+
+    /// Encodes an instruction positioned at the provided offset.
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Self::Instruction,
+    ) -> Result<Vec<u8>, BackendErr>;
+
+    /// Returns the byte order that constants are emitted in.
+    fn endianness(&self) -> Endianness {
+        Endianness::Little
+    }
+
+    /// Returns the value of a label at the provided byte position, for
+    /// instruction sets that address code in units other than bytes, such
+    /// as the 16-bit words of the AVR.
+    fn label_value(&self, position: usize) -> usize {
+        position
+    }
+
+    /// Returns the width, in bits, of the addresses that labels take, with
+    /// anything assembled beyond it raising an error.
+    fn address_width(&self) -> u32 {
+        16
+    }
+
+    /// Applies any state carried from one instruction to the next, such as an
+    /// assumed direct page, prior to layout. Instructions are provided in
+    /// source order, across origins.
+    fn apply_processor_state(
+        &self,
+        _instructions: &mut [&mut Self::Instruction],
+    ) -> Result<(), BackendErr> {
+        Ok(())
+    }
+
+    /// Rewrites an instruction, positioned at the provided offset, that can't
+    /// be encoded against the symbols of the latest layout pass, such as an
+    /// out of range branch, returning true if it was rewritten. Rewritten
+    /// instructions must never be rewritten back, so that layout settles.
+    fn relax(
+        &self,
+        _symbol_table: &SymbolTable,
+        _position: usize,
+        _instruction: &mut Self::Instruction,
+    ) -> bool {
+        false
+    }
+}
+
+/// Parses the text of each instruction in the source against an instruction
+/// set.
+pub fn parse_origins<I: InstructionSet>(
+    isa: &I,
+    source: Vec<Origin<UnparsedTokenStream>>,
+) -> Result<Vec<Origin<Vec<Token<I::Instruction>>>>, BackendErr> {
+    source
+        .into_iter()
+        .map(|origin| parse_origin(origin, |inst| isa.parse_instruction(inst)))
+        .collect::<Result<Vec<Origin<Vec<Token<I::Instruction>>>>, String>>()
+        .map_err(BackendErr::Parse)
+}
+
+/// Lays out each instruction, returning the resolved symbols alongside any
+/// directives and the positioned instructions of each origin.
+///
+/// Instructions referencing symbols may shrink once those symbols resolve,
+/// moving any subsequent labels, so layout is repeated against the prior
+/// pass's symbols until no label moves and no instruction is relaxed. An
+/// error is returned if that takes more than `MAX_LAYOUT_PASSES` passes.
+pub fn layout<I: InstructionSet>(
+    isa: &I,
+    token_origins: &mut [Origin<Vec<Token<I::Instruction>>>],
+) -> Result<Layout<I::Instruction>, BackendErr> {
+    let mut symbol_table = SymbolTable::default();
+
+    for _ in 0..MAX_LAYOUT_PASSES {
+        let (symbol_tables, directives, origins) = token_origins
+            .iter()
+            .cloned()
+            .map(|origin| position_origin(origin, |i| isa.size_of(&symbol_table, i)))
+            .map(|origin| collect_symbols(origin, |position| isa.label_value(position)))
+            .fold(
+                (Vec::new(), Vec::new(), Vec::new()),
+                |(mut sts, mut directives, mut origins), (st, d, origin)| {
+                    sts.push(st);
+                    directives.extend(d);
+                    origins.push(origin);
+                    (sts, directives, origins)
+                },
+            );
+
+        let resolved_symbol_table = SymbolTable::from(symbol_tables);
+        let relaxed = relax_origins(isa, &resolved_symbol_table, token_origins, &origins);
+        if !relaxed && resolved_symbol_table == symbol_table {
+            return Ok((resolved_symbol_table, directives, origins));
+        }
+        symbol_table = resolved_symbol_table;
+    }
+
+    Err(BackendErr::Unspecified(format!(
+        "layout didn't settle within {} passes",
+        MAX_LAYOUT_PASSES
+    )))
+}
+
+/// Offers each instruction to the instruction set for relaxation at the
+/// position given by the latest layout pass, returning true if any was
+/// relaxed.
+fn relax_origins<I: InstructionSet>(
+    isa: &I,
+    symbol_table: &SymbolTable,
+    token_origins: &mut [Origin<Vec<Token<I::Instruction>>>],
+    origins: &[Origin<MemoryAlignedStream<I::Instruction>>],
+) -> bool {
+    let mut relaxed = false;
+
+    for (token_origin, origin) in token_origins.iter_mut().zip(origins.iter()) {
+        let instructions = token_origin
+            .instructions
+            .iter_mut()
+            .filter_map(|t| match t {
+                Token::Instruction(i, _) => Some(i),
+                _ => None,
+            });
+        let positions = origin
+            .instructions
+            .iter()
+            .filter_map(|p| match p.contents() {
+                InstructionOrConstant::Instruction(_) => Some(p.position),
+                InstructionOrConstant::Constant(_) => None,
+            });
+
+        for (instruction, position) in instructions.zip(positions) {
+            relaxed |= isa.relax(symbol_table, position, instruction);
+        }
+    }
+
+    relaxed
+}
+
+/// Encodes each non-empty origin against the resolved symbols, returning an
+/// error for anything assembled beyond the instruction set's address space.
+pub fn encode_origins<I: InstructionSet>(
+    isa: &I,
+    symbol_table: &SymbolTable,
+    origins: Vec<Origin<MemoryAlignedStream<I::Instruction>>>,
+) -> Result<AssembledOrigins, BackendErr> {
+    origins
+        .into_iter()
+        // strip empty origins
+        .filter(|origin| !origin.instructions.is_empty())
+        .map(|origin| {
+            let offset = origin.offset;
+            origin
+                .instructions
+                .into_iter()
+                .map(|positional| {
+                    let position = positional.position;
+                    let bytes = match positional.unwrap() {
+                        InstructionOrConstant::Instruction(i) => {
+                            isa.encode(symbol_table, position, &i)
+                        }
+                        InstructionOrConstant::Constant(c) => resolve_constant(symbol_table, c)
+                            .map(|bv| bv.to_vec_with_endianness(isa.endianness())),
+                    }?;
+
+                    let end = isa.label_value(position + bytes.len()) as u64;
+                    if matches!(1u64.checked_shl(isa.address_width()), Some(limit) if end > limit) {
+                        return Err(BackendErr::Unspecified(format!(
+                            "{:#06x} exceeds the {}-bit address space",
+                            position,
+                            isa.address_width()
+                        )));
+                    }
+                    Ok(bytes)
+                })
+                .collect::<Result<Vec<Vec<u8>>, BackendErr>>()
+                .map(|bytes| Origin::with_offset(offset, bytes.concat()))
+        })
+        .collect()
+}
+
+/// Assembles the source against an instruction set, returning an error for
+/// any processor-specific directive that the shared pipeline doesn't
+/// interpret.
+pub fn assemble_instruction_set<I: InstructionSet>(
+    isa: &I,
+    source: Vec<Origin<UnparsedTokenStream>>,
+    diagnostics: Option<&DiagnosticSender>,
+) -> Result<AssembledOrigins, BackendErr> {
+    let mut token_origins = parse_origins(isa, source)?;
+
+    let unsupported = token_origins
+        .iter()
+        .flat_map(|origin| origin.instructions.iter())
+        .find_map(|token| match token {
+            Token::Cpu(name, location) => Some(format!("{}: unsupported cpu: {}", location, name)),
+            Token::RegisterWidth(_, _, location) => {
+                Some(format!("{}: register widths are unsupported", location))
+            }
+            Token::Vectors(v) => Some(format!("{}: vectors are unsupported", v.location)),
+            _ => None,
+        });
+    if let Some(e) = unsupported {
+        return Err(BackendErr::Unspecified(e));
+    }
+    let mut instructions = token_origins
+        .iter_mut()
+        .flat_map(|origin| origin.instructions.iter_mut())
+        .filter_map(|token| match token {
+            Token::Instruction(instruction, _) => Some(instruction),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    isa.apply_processor_state(&mut instructions)?;
+
+    let (symbol_table, directives, origins) = layout(isa, &mut token_origins)?;
+
+    evaluate_directives(&symbol_table, directives, diagnostics, &|_, _| {
+        Err(BackendErr::Unspecified(
+            "cycle counts are unsupported".to_string(),
+        ))
+    })?;
+
+    encode_origins(isa, &symbol_table, origins)
+}
+
+/// Preparses the source, placing any statements that precede the first
+/// `.origin` at the default origin, and assembles it against an instruction
+/// set with the definitions and diagnostics of the provided options.
+pub fn assemble_source<I: InstructionSet>(
+    isa: &I,
+    source: &str,
+    options: AssemblerOptions,
+    default_origin: usize,
+) -> AssemblerResult<AssembledOrigins, String> {
+    let origin_tokens = preparse(source, options.definitions, default_origin)?;
+
+    assemble_instruction_set(isa, origin_tokens, options.diagnostics.as_ref())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A single instruction set, whose instruction grows whenever the label
+    /// following it resolves to 1 and shrinks otherwise.
+    struct Oscillating;
+
+    impl InstructionSet for Oscillating {
+        type Instruction = ();
+
+        fn parse_instruction(&self, _: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn size_of(&self, symbol_table: &SymbolTable, _: &()) -> usize {
+            match symbol_table.get_as_u16("end") {
+                Some(1) => 2,
+                _ => 1,
+            }
+        }
+
+        fn encode(&self, _: &SymbolTable, _: usize, _: &()) -> Result<Vec<u8>, BackendErr> {
+            Ok(vec![0x00])
+        }
+    }
+
+    #[test]
+    fn should_throw_an_error_on_layout_that_never_settles() {
+        let source = vec![Origin::new(vec![
+            Token::Instruction("nop".to_string(), SourceLocation::new(1)),
+            Token::Symbol("end".to_string(), None),
+        ])];
+
+        assert_eq!(
+            Some("layout didn't settle within 64 passes".to_string()),
+            assemble_instruction_set(&Oscillating, source, None)
+                .err()
+                .map(|e| e.to_string())
+        );
+    }
+
+    #[test]
+    fn should_reify_a_16_bit_lebytesvalue() {
+        let lebev = LeByteEncodedValue::new(vec![0, 128]);
+
+        assert_eq!(Ok(0x8000), Reify::<u16>::reify(&lebev))
+    }
+}
@@ -0,0 +1,35 @@
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    ($($mnemonic:ident),*) => {
+        /// Mnemonic represents the Motorola mnemonics of the 6800 and 6801.
+        /// Whether a mnemonic is valid is left to the target cpu.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($mnemonic,)*
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($mnemonic) => Ok(Self::$mnemonic),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    ABA, ABX, ADCA, ADCB, ADDA, ADDB, ADDD, ANDA, ANDB, ASL, ASLA, ASLB, ASLD, ASR, ASRA, ASRB,
+    BCC, BCS, BEQ, BGE, BGT, BHI, BHS, BITA, BITB, BLE, BLO, BLS, BLT, BMI, BNE, BPL, BRA, BRN,
+    BSR, BVC, BVS, CBA, CLC, CLI, CLR, CLRA, CLRB, CLV, CMPA, CMPB, COM, COMA, COMB, CPX, DAA, DEC,
+    DECA, DECB, DES, DEX, EORA, EORB, INC, INCA, INCB, INS, INX, JMP, JSR, LDAA, LDAB, LDD, LDS,
+    LDX, LSL, LSLA, LSLB, LSLD, LSR, LSRA, LSRB, LSRD, MUL, NEG, NEGA, NEGB, NOP, ORAA, ORAB, PSHA,
+    PSHB, PSHX, PULA, PULB, PULX, ROL, ROLA, ROLB, ROR, RORA, RORB, RTI, RTS, SBA, SBCA, SBCB, SEC,
+    SEI, SEV, STAA, STAB, STD, STS, STX, SUBA, SUBB, SUBD, SWI, TAB, TAP, TBA, TPA, TST, TSTA,
+    TSTB, TSX, TXS, WAI
+);
@@ -0,0 +1,346 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+use std::convert::TryFrom;
+
+/// Cpu represents the members of the 6800 family being targeted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Cpu {
+    /// The 6800 and 6802, which share an instruction set.
+    #[default]
+    M6800,
+    /// The 6801 and 6803, adding the 16-bit `d` accumulator instructions,
+    /// `abx`, `mul`, `pshx`, `pulx`, `brn` and a direct page `jsr`.
+    M6801,
+}
+
+impl TryFrom<&str> for Cpu {
+    type Error = String;
+
+    fn try_from(src: &str) -> Result<Self, Self::Error> {
+        match src {
+            "6800" | "6802" => Ok(Self::M6800),
+            "6801" | "6803" => Ok(Self::M6801),
+            _ => Err(format!("unknown cpu: {}", src)),
+        }
+    }
+}
+
+/// Operand represents the single operand of a 6800 instruction, generalized
+/// over its value so that operands may be parsed with references and encoded
+/// once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Inherent,
+    Immediate(V),
+    /// An address, or branch target, assembled with direct addressing when
+    /// it falls below 0x100.
+    Address(V),
+    /// An unsigned offset from the `x` register, such as `4,x`.
+    Indexed(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        match self {
+            Self::Inherent => Ok(Operand::Inherent),
+            Self::Immediate(v) => f(v).map(Operand::Immediate),
+            Self::Address(v) => f(v).map(Operand::Address),
+            Self::Indexed(v) => f(v).map(Operand::Indexed),
+        }
+    }
+}
+
+/// Instruction represents a single 6800 mnemonic and its operand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operand: Operand<Value>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operand: Operand<Value>) -> Self {
+        Self { mnemonic, operand }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+    UnsupportedInstruction(Cpu),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Resolved represents a value that may still reference an unresolved
+/// symbol, in which case the widest encoding is selected and the value is
+/// zeroed.
+pub type Resolved = Option<i64>;
+
+fn byte(value: Resolved) -> Result<u8, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x80..=0xff => Ok(v as u8),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn word(value: Resolved) -> Result<[u8; 2], EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x8000..=0xffff => Ok((v as u16).to_be_bytes()),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+/// Opcodes holds the opcode of each addressing mode an instruction taking
+/// a memory operand supports.
+struct Opcodes {
+    /// The immediate opcode and the width of its operand in bytes.
+    immediate: Option<(u8, usize)>,
+    direct: Option<u8>,
+    indexed: u8,
+    extended: u8,
+}
+
+impl Opcodes {
+    /// The layout of the accumulator and 16-bit register instructions, with
+    /// each mode offset from the immediate opcode.
+    fn register(base: u8, immediate: Option<usize>) -> Self {
+        Self {
+            immediate: immediate.map(|width| (base, width)),
+            direct: Some(base + 0x10),
+            indexed: base + 0x20,
+            extended: base + 0x30,
+        }
+    }
+
+    /// The layout of the read-modify-write instructions, which have no
+    /// direct page form.
+    fn memory(base: u8) -> Self {
+        Self {
+            immediate: None,
+            direct: None,
+            indexed: base + 0x60,
+            extended: base + 0x70,
+        }
+    }
+}
+
+/// Form represents the family of operands an instruction accepts.
+enum Form {
+    Inherent(u8),
+    Memory(Opcodes),
+    Branch(u8),
+}
+
+/// Returns whether a mnemonic was introduced by the 6801.
+fn is_6801_extension(mnemonic: Mnemonic) -> bool {
+    use Mnemonic as M;
+
+    matches!(
+        mnemonic,
+        M::ABX
+            | M::ADDD
+            | M::ASLD
+            | M::LSLD
+            | M::LSRD
+            | M::BRN
+            | M::LDD
+            | M::STD
+            | M::MUL
+            | M::PSHX
+            | M::PULX
+            | M::SUBD
+    )
+}
+
+fn form(cpu: Cpu, mnemonic: Mnemonic) -> Form {
+    use Mnemonic as M;
+
+    let accumulator = |base| Form::Memory(Opcodes::register(base, Some(1)));
+    let wide = |base| Form::Memory(Opcodes::register(base, Some(2)));
+    let store = |base| Form::Memory(Opcodes::register(base, None));
+
+    match mnemonic {
+        M::ABA => Form::Inherent(0x1b),
+        M::ABX => Form::Inherent(0x3a),
+        M::ASLD | M::LSLD => Form::Inherent(0x05),
+        M::CBA => Form::Inherent(0x11),
+        M::CLC => Form::Inherent(0x0c),
+        M::CLI => Form::Inherent(0x0e),
+        M::CLV => Form::Inherent(0x0a),
+        M::DAA => Form::Inherent(0x19),
+        M::DES => Form::Inherent(0x34),
+        M::DEX => Form::Inherent(0x09),
+        M::INS => Form::Inherent(0x31),
+        M::INX => Form::Inherent(0x08),
+        M::LSRD => Form::Inherent(0x04),
+        M::MUL => Form::Inherent(0x3d),
+        M::NOP => Form::Inherent(0x01),
+        M::PSHA => Form::Inherent(0x36),
+        M::PSHB => Form::Inherent(0x37),
+        M::PSHX => Form::Inherent(0x3c),
+        M::PULA => Form::Inherent(0x32),
+        M::PULB => Form::Inherent(0x33),
+        M::PULX => Form::Inherent(0x38),
+        M::RTI => Form::Inherent(0x3b),
+        M::RTS => Form::Inherent(0x39),
+        M::SBA => Form::Inherent(0x10),
+        M::SEC => Form::Inherent(0x0d),
+        M::SEI => Form::Inherent(0x0f),
+        M::SEV => Form::Inherent(0x0b),
+        M::SWI => Form::Inherent(0x3f),
+        M::TAB => Form::Inherent(0x16),
+        M::TAP => Form::Inherent(0x06),
+        M::TBA => Form::Inherent(0x17),
+        M::TPA => Form::Inherent(0x07),
+        M::TSX => Form::Inherent(0x30),
+        M::TXS => Form::Inherent(0x35),
+        M::WAI => Form::Inherent(0x3e),
+        M::NEGA => Form::Inherent(0x40),
+        M::COMA => Form::Inherent(0x43),
+        M::LSRA => Form::Inherent(0x44),
+        M::RORA => Form::Inherent(0x46),
+        M::ASRA => Form::Inherent(0x47),
+        M::ASLA | M::LSLA => Form::Inherent(0x48),
+        M::ROLA => Form::Inherent(0x49),
+        M::DECA => Form::Inherent(0x4a),
+        M::INCA => Form::Inherent(0x4c),
+        M::TSTA => Form::Inherent(0x4d),
+        M::CLRA => Form::Inherent(0x4f),
+        M::NEGB => Form::Inherent(0x50),
+        M::COMB => Form::Inherent(0x53),
+        M::LSRB => Form::Inherent(0x54),
+        M::RORB => Form::Inherent(0x56),
+        M::ASRB => Form::Inherent(0x57),
+        M::ASLB | M::LSLB => Form::Inherent(0x58),
+        M::ROLB => Form::Inherent(0x59),
+        M::DECB => Form::Inherent(0x5a),
+        M::INCB => Form::Inherent(0x5c),
+        M::TSTB => Form::Inherent(0x5d),
+        M::CLRB => Form::Inherent(0x5f),
+
+        M::NEG => Form::Memory(Opcodes::memory(0x00)),
+        M::COM => Form::Memory(Opcodes::memory(0x03)),
+        M::LSR => Form::Memory(Opcodes::memory(0x04)),
+        M::ROR => Form::Memory(Opcodes::memory(0x06)),
+        M::ASR => Form::Memory(Opcodes::memory(0x07)),
+        M::ASL | M::LSL => Form::Memory(Opcodes::memory(0x08)),
+        M::ROL => Form::Memory(Opcodes::memory(0x09)),
+        M::DEC => Form::Memory(Opcodes::memory(0x0a)),
+        M::INC => Form::Memory(Opcodes::memory(0x0c)),
+        M::TST => Form::Memory(Opcodes::memory(0x0d)),
+        M::JMP => Form::Memory(Opcodes::memory(0x0e)),
+        M::CLR => Form::Memory(Opcodes::memory(0x0f)),
+
+        M::SUBA => accumulator(0x80),
+        M::CMPA => accumulator(0x81),
+        M::SBCA => accumulator(0x82),
+        M::SUBD => wide(0x83),
+        M::ANDA => accumulator(0x84),
+        M::BITA => accumulator(0x85),
+        M::LDAA => accumulator(0x86),
+        M::STAA => store(0x87),
+        M::EORA => accumulator(0x88),
+        M::ADCA => accumulator(0x89),
+        M::ORAA => accumulator(0x8a),
+        M::ADDA => accumulator(0x8b),
+        M::CPX => wide(0x8c),
+        M::LDS => wide(0x8e),
+        M::STS => store(0x8f),
+        M::SUBB => accumulator(0xc0),
+        M::CMPB => accumulator(0xc1),
+        M::SBCB => accumulator(0xc2),
+        M::ADDD => wide(0xc3),
+        M::ANDB => accumulator(0xc4),
+        M::BITB => accumulator(0xc5),
+        M::LDAB => accumulator(0xc6),
+        M::STAB => store(0xc7),
+        M::EORB => accumulator(0xc8),
+        M::ADCB => accumulator(0xc9),
+        M::ORAB => accumulator(0xca),
+        M::ADDB => accumulator(0xcb),
+        M::LDD => wide(0xcc),
+        M::STD => store(0xcd),
+        M::LDX => wide(0xce),
+        M::STX => store(0xcf),
+        // the direct page jsr was introduced by the 6801.
+        M::JSR => Form::Memory(Opcodes {
+            immediate: None,
+            direct: (cpu == Cpu::M6801).then_some(0x9d),
+            indexed: 0xad,
+            extended: 0xbd,
+        }),
+
+        M::BRA => Form::Branch(0x20),
+        M::BRN => Form::Branch(0x21),
+        M::BHI => Form::Branch(0x22),
+        M::BLS => Form::Branch(0x23),
+        M::BCC | M::BHS => Form::Branch(0x24),
+        M::BCS | M::BLO => Form::Branch(0x25),
+        M::BNE => Form::Branch(0x26),
+        M::BEQ => Form::Branch(0x27),
+        M::BVC => Form::Branch(0x28),
+        M::BVS => Form::Branch(0x29),
+        M::BPL => Form::Branch(0x2a),
+        M::BMI => Form::Branch(0x2b),
+        M::BGE => Form::Branch(0x2c),
+        M::BLT => Form::Branch(0x2d),
+        M::BGT => Form::Branch(0x2e),
+        M::BLE => Form::Branch(0x2f),
+        M::BSR => Form::Branch(0x8d),
+    }
+}
+
+/// Encodes an instruction for the provided cpu, positioned at the provided
+/// offset, against its resolved operand. Unresolved addresses select
+/// extended addressing.
+pub fn encode(
+    cpu: Cpu,
+    mnemonic: Mnemonic,
+    operand: &Operand<Resolved>,
+    position: usize,
+) -> Encoding {
+    if cpu == Cpu::M6800 && is_6801_extension(mnemonic) {
+        return Err(EncodeErr::UnsupportedInstruction(cpu));
+    }
+
+    match (form(cpu, mnemonic), operand) {
+        (Form::Inherent(opcode), Operand::Inherent) => Ok(vec![opcode]),
+        (Form::Memory(opcodes), Operand::Immediate(value)) => match opcodes.immediate {
+            Some((opcode, 1)) => Ok(vec![opcode, byte(*value)?]),
+            Some((opcode, _)) => {
+                let [hi, lo] = word(*value)?;
+                Ok(vec![opcode, hi, lo])
+            }
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        (Form::Memory(opcodes), Operand::Address(address)) => match (opcodes.direct, address) {
+            (Some(opcode), Some(address @ 0..=0xff)) => Ok(vec![opcode, *address as u8]),
+            _ => {
+                let [hi, lo] = word(*address)?;
+                Ok(vec![opcodes.extended, hi, lo])
+            }
+        },
+        (Form::Memory(opcodes), Operand::Indexed(offset)) => match offset.unwrap_or(0) {
+            offset @ 0..=0xff => Ok(vec![opcodes.indexed, offset as u8]),
+            offset => Err(EncodeErr::OutOfRange(offset)),
+        },
+        (Form::Branch(opcode), Operand::Address(target)) => {
+            let offset = target.map_or(0, |t| t - (position as i64 + 2));
+            match offset {
+                -128..=127 => Ok(vec![opcode, offset as u8]),
+                _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+            }
+        }
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
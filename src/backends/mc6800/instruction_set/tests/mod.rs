@@ -0,0 +1,134 @@
+use crate::backends::mc6800::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6800::instruction_set::{encode, Cpu, EncodeErr, Operand};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, $operand:expr, $expected:expr) => {
+        assert_eq!(Ok($expected), encode(Cpu::M6800, $mnemonic, &$operand, 0));
+    };
+}
+
+#[test]
+fn should_encode_inherent_instructions() {
+    gen_encode_test!(Mnemonic::NOP, Operand::Inherent, vec![0x01]);
+    gen_encode_test!(Mnemonic::TAB, Operand::Inherent, vec![0x16]);
+    gen_encode_test!(Mnemonic::CLRB, Operand::Inherent, vec![0x5f]);
+}
+
+#[test]
+fn should_encode_immediate_operands_by_register_width() {
+    gen_encode_test!(
+        Mnemonic::LDAA,
+        Operand::Immediate(Some(0x12)),
+        vec![0x86, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::LDX,
+        Operand::Immediate(Some(0x1234)),
+        vec![0xce, 0x12, 0x34]
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Cpu::M6800,
+            Mnemonic::STAA,
+            &Operand::Immediate(Some(0x12)),
+            0
+        )
+    );
+}
+
+#[test]
+fn should_select_direct_addressing_below_0x100() {
+    gen_encode_test!(
+        Mnemonic::LDAA,
+        Operand::Address(Some(0x20)),
+        vec![0x96, 0x20]
+    );
+    gen_encode_test!(
+        Mnemonic::LDAA,
+        Operand::Address(Some(0x1234)),
+        vec![0xb6, 0x12, 0x34]
+    );
+    gen_encode_test!(
+        Mnemonic::LDAA,
+        Operand::Address(None),
+        vec![0xb6, 0x00, 0x00]
+    );
+    gen_encode_test!(
+        Mnemonic::STX,
+        Operand::Address(Some(0x80)),
+        vec![0xdf, 0x80]
+    );
+    // read-modify-write instructions have no direct page form.
+    gen_encode_test!(
+        Mnemonic::INC,
+        Operand::Address(Some(0x20)),
+        vec![0x7c, 0x00, 0x20]
+    );
+}
+
+#[test]
+fn should_encode_indexed_operands() {
+    gen_encode_test!(Mnemonic::LDAA, Operand::Indexed(Some(4)), vec![0xa6, 0x04]);
+    gen_encode_test!(Mnemonic::CLR, Operand::Indexed(Some(0)), vec![0x6f, 0x00]);
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x100)),
+        encode(
+            Cpu::M6800,
+            Mnemonic::LDAA,
+            &Operand::Indexed(Some(0x100)),
+            0
+        )
+    );
+}
+
+#[test]
+fn should_encode_relative_branches() {
+    assert_eq!(
+        Ok(vec![0x26, 0xfe]),
+        encode(
+            Cpu::M6800,
+            Mnemonic::BNE,
+            &Operand::Address(Some(0x100)),
+            0x100
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(-131)),
+        encode(
+            Cpu::M6800,
+            Mnemonic::BRA,
+            &Operand::Address(Some(0x7f)),
+            0x100
+        )
+    );
+}
+
+#[test]
+fn should_only_encode_6801_extensions_when_targeting_the_6801() {
+    assert_eq!(
+        Err(EncodeErr::UnsupportedInstruction(Cpu::M6800)),
+        encode(Cpu::M6800, Mnemonic::MUL, &Operand::Inherent, 0)
+    );
+    assert_eq!(
+        Ok(vec![0x3d]),
+        encode(Cpu::M6801, Mnemonic::MUL, &Operand::Inherent, 0)
+    );
+    assert_eq!(
+        Ok(vec![0xcc, 0x12, 0x34]),
+        encode(
+            Cpu::M6801,
+            Mnemonic::LDD,
+            &Operand::Immediate(Some(0x1234)),
+            0
+        )
+    );
+    assert_eq!(
+        Ok(vec![0xbd, 0x00, 0x20]),
+        encode(Cpu::M6800, Mnemonic::JSR, &Operand::Address(Some(0x20)), 0)
+    );
+    assert_eq!(
+        Ok(vec![0x9d, 0x20]),
+        encode(Cpu::M6801, Mnemonic::JSR, &Operand::Address(Some(0x20)), 0)
+    );
+}
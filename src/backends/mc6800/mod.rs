@@ -0,0 +1,90 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+use crate::backends::mc6800::instruction_set::{encode, Cpu, EncodeErr, Instruction, Operand};
+use crate::backends::BackendErr;
+use crate::preparser::types::Endianness;
+
+/// Mc6800Assembler implements the [InstructionSet] of the 6800 family.
+#[derive(Default)]
+pub struct Mc6800Assembler {
+    cpu: Cpu,
+}
+
+impl Mc6800Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// with_cpu sets the member of the 6800 family that instructions are
+    /// assembled for, defaulting to the 6800.
+    pub fn with_cpu(mut self, cpu: Cpu) -> Self {
+        self.cpu = cpu;
+        self
+    }
+}
+
+impl InstructionSet for Mc6800Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// Addresses that are yet to be resolved are sized with extended
+    /// addressing, shrinking to direct addressing once they resolve below
+    /// 0x100.
+    fn size_of(&self, symbol_table: &SymbolTable, instruction: &Instruction) -> usize {
+        let operand = instruction
+            .operand
+            .try_map(|v| Ok::<_, ()>(resolve_value(symbol_table, v).ok()));
+
+        operand
+            .ok()
+            .and_then(|operand| encode(self.cpu, instruction.mnemonic, &operand, 0).ok())
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operand = instruction
+            .operand
+            .try_map(|v| resolve_value(symbol_table, v).map(Some))?;
+
+        encode(self.cpu, instruction.mnemonic, &operand, position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operand for {:?}: {:?}",
+                instruction.mnemonic, instruction.operand
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                let target = match &instruction.operand {
+                    Operand::Address(Value::Reference(id)) => id.clone(),
+                    operand => format!("{:?}", operand),
+                };
+                BackendErr::BranchOutOfRange(target, offset)
+            }
+            EncodeErr::UnsupportedInstruction(cpu) => BackendErr::UndefinedInstruction(format!(
+                "{:?} is unsupported by the {:?}",
+                instruction.mnemonic, cpu
+            )),
+        })
+    }
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Big
+    }
+}
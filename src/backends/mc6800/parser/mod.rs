@@ -0,0 +1,59 @@
+use crate::backends::mc6800::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6800::instruction_set::{Instruction, Operand, Value};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operand(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operand)| Instruction::new(m, operand.unwrap_or(Operand::Inherent)))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(expect_character('#'), value()))
+        .map(Operand::Immediate)
+        .or(|| indexed())
+        .or(|| value().map(Operand::Address))
+}
+
+/// Matches an offset from the `x` register, with `,x` taking an offset of
+/// zero.
+fn indexed<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(
+        optional(value()),
+        right(join(
+            join(
+                zero_or_more(non_newline_whitespace()),
+                join(
+                    expect_character(','),
+                    zero_or_more(non_newline_whitespace()),
+                ),
+            ),
+            name().predicate(|register| register.to_lowercase() == "x"),
+        )),
+    )
+    .map(|(offset, _)| Operand::Indexed(offset.unwrap_or(Value::Literal(0))))
+}
@@ -0,0 +1,58 @@
+use crate::backends::mc6800::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6800::instruction_set::{Instruction, Operand, Value};
+use crate::backends::mc6800::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operand:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operand)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_inherent_instructions() {
+    gen_inst_test!("  tab", Mnemonic::TAB, Operand::Inherent);
+    gen_inst_test!("INX  ", Mnemonic::INX, Operand::Inherent);
+}
+
+#[test]
+fn should_parse_immediate_and_address_operands() {
+    gen_inst_test!(
+        "ldaa #$12",
+        Mnemonic::LDAA,
+        Operand::Immediate(Value::Literal(0x12))
+    );
+    gen_inst_test!(
+        "staa $2000",
+        Mnemonic::STAA,
+        Operand::Address(Value::Literal(0x2000))
+    );
+    gen_inst_test!(
+        "bne loop",
+        Mnemonic::BNE,
+        Operand::Address(Value::Reference("loop".to_string()))
+    );
+}
+
+#[test]
+fn should_parse_indexed_operands() {
+    gen_inst_test!(
+        "ldaa 4,x",
+        Mnemonic::LDAA,
+        Operand::Indexed(Value::Literal(4))
+    );
+    gen_inst_test!("jmp ,X", Mnemonic::JMP, Operand::Indexed(Value::Literal(0)));
+    gen_inst_test!(
+        "ldab offset, x",
+        Mnemonic::LDAB,
+        Operand::Indexed(Value::Reference("offset".to_string()))
+    );
+}
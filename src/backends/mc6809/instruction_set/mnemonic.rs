@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    ($($mnemonic:ident),*) => {
+        /// Mnemonic represents the mnemonics of the 6809, alongside the
+        /// `setdp` pseudo-instruction. Whether a mnemonic is valid for its
+        /// operand is left to encoding.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($mnemonic,)*
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($mnemonic) => Ok(Self::$mnemonic),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    ABX, ADCA, ADCB, ADDA, ADDB, ADDD, ANDA, ANDB, ANDCC, ASL, ASLA, ASLB, ASR, ASRA, ASRB, BCC,
+    BCS, BEQ, BGE, BGT, BHI, BHS, BITA, BITB, BLE, BLO, BLS, BLT, BMI, BNE, BPL, BRA, BRN, BSR,
+    BVC, BVS, CLR, CLRA, CLRB, CMPA, CMPB, CMPD, CMPS, CMPU, CMPX, CMPY, COM, COMA, COMB, CWAI,
+    DAA, DEC, DECA, DECB, EORA, EORB, EXG, INC, INCA, INCB, JMP, JSR, LBCC, LBCS, LBEQ, LBGE, LBGT,
+    LBHI, LBHS, LBLE, LBLO, LBLS, LBLT, LBMI, LBNE, LBPL, LBRA, LBRN, LBSR, LBVC, LBVS, LDA, LDB,
+    LDD, LDS, LDU, LDX, LDY, LEAS, LEAU, LEAX, LEAY, LSL, LSLA, LSLB, LSR, LSRA, LSRB, MUL, NEG,
+    NEGA, NEGB, NOP, ORA, ORB, ORCC, PSHS, PSHU, PULS, PULU, ROL, ROLA, ROLB, ROR, RORA, RORB, RTI,
+    RTS, SBCA, SBCB, SETDP, SEX, STA, STB, STD, STS, STU, STX, STY, SUBA, SUBB, SUBD, SWI, SWI2,
+    SWI3, SYNC, TFR, TST, TSTA, TSTB
+);
@@ -0,0 +1,622 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+
+/// Register represents the registers that may be named by an operand, be
+/// it in a register list, a transfer or as an accumulator offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    A,
+    B,
+    /// The `a` and `b` accumulators as a 16-bit pair.
+    D,
+    X,
+    Y,
+    U,
+    S,
+    PC,
+    CC,
+    DP,
+}
+
+impl Register {
+    /// Returns the 4-bit encoding of a register within a `tfr` or `exg`
+    /// postbyte.
+    fn transfer_code(self) -> u8 {
+        match self {
+            Self::D => 0x0,
+            Self::X => 0x1,
+            Self::Y => 0x2,
+            Self::U => 0x3,
+            Self::S => 0x4,
+            Self::PC => 0x5,
+            Self::A => 0x8,
+            Self::B => 0x9,
+            Self::CC => 0xa,
+            Self::DP => 0xb,
+        }
+    }
+
+    fn is_wide(self) -> bool {
+        matches!(
+            self,
+            Self::D | Self::X | Self::Y | Self::U | Self::S | Self::PC
+        )
+    }
+
+    /// Returns the bit representing a register within a push or pull
+    /// postbyte, with the `s` and `u` stack pointers sharing a bit as each
+    /// names the other stack.
+    fn stack_bits(self) -> u8 {
+        match self {
+            Self::CC => 0x01,
+            Self::A => 0x02,
+            Self::B => 0x04,
+            Self::D => 0x06,
+            Self::DP => 0x08,
+            Self::X => 0x10,
+            Self::Y => 0x20,
+            Self::U | Self::S => 0x40,
+            Self::PC => 0x80,
+        }
+    }
+
+    /// Returns the index register a register names, if any.
+    pub fn as_index(self) -> Option<IndexRegister> {
+        match self {
+            Self::X => Some(IndexRegister::X),
+            Self::Y => Some(IndexRegister::Y),
+            Self::U => Some(IndexRegister::U),
+            Self::S => Some(IndexRegister::S),
+            _ => None,
+        }
+    }
+}
+
+/// IndexRegister represents the registers that may be used as the base of
+/// an indexed operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexRegister {
+    X,
+    Y,
+    U,
+    S,
+}
+
+impl IndexRegister {
+    /// Returns the register's bits within an indexed postbyte.
+    fn code(self) -> u8 {
+        match self {
+            Self::X => 0x00,
+            Self::Y => 0x20,
+            Self::U => 0x40,
+            Self::S => 0x60,
+        }
+    }
+}
+
+/// Offset represents the signed constant offset of an indexed operand, such
+/// as the `-4` of `-4,x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Offset<V> {
+    pub negative: bool,
+    pub value: V,
+}
+
+impl<V> Offset<V> {
+    pub fn new(negative: bool, value: V) -> Self {
+        Self { negative, value }
+    }
+}
+
+/// IndexMode represents each form an indexed operand's effective address
+/// may be calculated by.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexMode<V> {
+    /// A constant offset from an index register, with `,x` having none.
+    Offset(IndexRegister, Option<Offset<V>>),
+    /// An offset held in the `a`, `b` or `d` accumulator, such as `b,x`.
+    Accumulator(Register, IndexRegister),
+    /// `,x+`
+    PostIncrement(IndexRegister),
+    /// `,x++`
+    PostIncrementTwice(IndexRegister),
+    /// `,-x`
+    PreDecrement(IndexRegister),
+    /// `,--x`
+    PreDecrementTwice(IndexRegister),
+    /// An address relative to the program counter, such as `label,pcr`.
+    ProgramCounterRelative(V),
+    /// An absolute address, only valid when indirect, such as `[$1000]`.
+    Extended(V),
+}
+
+/// Indexed represents an indexed operand and whether the effective address
+/// it calculates is dereferenced a second time, as in `[,x]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Indexed<V> {
+    pub indirect: bool,
+    pub mode: IndexMode<V>,
+}
+
+impl<V> Indexed<V> {
+    pub fn new(indirect: bool, mode: IndexMode<V>) -> Self {
+        Self { indirect, mode }
+    }
+}
+
+/// Operand represents the single operand of a 6809 instruction, generalized
+/// over its values so that operands may be parsed with references and
+/// encoded once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Inherent,
+    Immediate(V),
+    /// An address forced to direct page addressing with `<`.
+    Direct(V),
+    /// An address forced to extended addressing with `>`.
+    Extended(V),
+    /// An address, or branch target, whose addressing mode is selected
+    /// against the direct page.
+    Address(V),
+    Indexed(Indexed<V>),
+    Registers(Vec<Register>),
+}
+
+impl<V> Operand<V> {
+    /// Converts the values of an operand, returning the first error
+    /// encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        let offset = |o: &Offset<V>| f(&o.value).map(|value| Offset::new(o.negative, value));
+
+        let operand = match self {
+            Self::Inherent => Operand::Inherent,
+            Self::Immediate(v) => Operand::Immediate(f(v)?),
+            Self::Direct(v) => Operand::Direct(f(v)?),
+            Self::Extended(v) => Operand::Extended(f(v)?),
+            Self::Address(v) => Operand::Address(f(v)?),
+            Self::Registers(registers) => Operand::Registers(registers.clone()),
+            Self::Indexed(Indexed { indirect, mode }) => {
+                let mode = match mode {
+                    IndexMode::Offset(r, o) => {
+                        IndexMode::Offset(*r, o.as_ref().map(offset).transpose()?)
+                    }
+                    IndexMode::Accumulator(a, r) => IndexMode::Accumulator(*a, *r),
+                    IndexMode::PostIncrement(r) => IndexMode::PostIncrement(*r),
+                    IndexMode::PostIncrementTwice(r) => IndexMode::PostIncrementTwice(*r),
+                    IndexMode::PreDecrement(r) => IndexMode::PreDecrement(*r),
+                    IndexMode::PreDecrementTwice(r) => IndexMode::PreDecrementTwice(*r),
+                    IndexMode::ProgramCounterRelative(v) => {
+                        IndexMode::ProgramCounterRelative(f(v)?)
+                    }
+                    IndexMode::Extended(v) => IndexMode::Extended(f(v)?),
+                };
+                Operand::Indexed(Indexed::new(*indirect, mode))
+            }
+        };
+
+        Ok(operand)
+    }
+}
+
+/// Instruction represents a single 6809 mnemonic and its operand, alongside
+/// the direct page assumed by the most recent `setdp`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operand: Operand<Value>,
+    pub direct_page: Value,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operand: Operand<Value>) -> Self {
+        Self {
+            mnemonic,
+            operand,
+            direct_page: Value::Literal(0),
+        }
+    }
+
+    pub fn with_direct_page(mut self, direct_page: Value) -> Self {
+        self.direct_page = direct_page;
+        self
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Resolved represents a value that may still reference an unresolved
+/// symbol, in which case the widest encoding is selected and the value is
+/// zeroed.
+pub type Resolved = Option<i64>;
+
+fn byte(value: Resolved) -> Result<u8, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x80..=0xff => Ok(v as u8),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn word(value: Resolved) -> Result<[u8; 2], EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x8000..=0xffff => Ok((v as u16).to_be_bytes()),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+/// Opcodes holds the opcode of each addressing mode an instruction taking
+/// a memory operand supports.
+struct Opcodes {
+    prefix: Option<u8>,
+    /// The immediate opcode and the width of its operand in bytes.
+    immediate: Option<(u8, usize)>,
+    direct: u8,
+    indexed: u8,
+    extended: u8,
+}
+
+impl Opcodes {
+    /// The layout of the accumulator and 16-bit register instructions, with
+    /// each mode offset from the immediate opcode.
+    fn register(prefix: Option<u8>, base: u8, immediate: Option<usize>) -> Self {
+        Self {
+            prefix,
+            immediate: immediate.map(|width| (base, width)),
+            direct: base + 0x10,
+            indexed: base + 0x20,
+            extended: base + 0x30,
+        }
+    }
+
+    /// The layout of the read-modify-write instructions, with each mode
+    /// offset from the direct opcode.
+    fn memory(base: u8) -> Self {
+        Self {
+            prefix: None,
+            immediate: None,
+            direct: base,
+            indexed: base + 0x60,
+            extended: base + 0x70,
+        }
+    }
+
+    fn with(&self, opcode: u8) -> Vec<u8> {
+        self.prefix
+            .into_iter()
+            .chain(std::iter::once(opcode))
+            .collect()
+    }
+}
+
+/// Form represents the family of operands an instruction accepts.
+enum Form {
+    Inherent(&'static [u8]),
+    Memory(Opcodes),
+    LoadEffectiveAddress(u8),
+    /// A push or pull against the named stack pointer.
+    Stack(u8, Register),
+    Transfer(u8),
+    ConditionCode(u8),
+    Branch(u8),
+    LongBranch(&'static [u8]),
+    SetDirectPage,
+}
+
+fn form(mnemonic: Mnemonic) -> Form {
+    use Mnemonic as M;
+
+    let accumulator = |base| Form::Memory(Opcodes::register(None, base, Some(1)));
+    let wide = |prefix, base| Form::Memory(Opcodes::register(prefix, base, Some(2)));
+    let store = |prefix, base| Form::Memory(Opcodes::register(prefix, base, None));
+
+    match mnemonic {
+        M::ABX => Form::Inherent(&[0x3a]),
+        M::DAA => Form::Inherent(&[0x19]),
+        M::MUL => Form::Inherent(&[0x3d]),
+        M::NOP => Form::Inherent(&[0x12]),
+        M::RTI => Form::Inherent(&[0x3b]),
+        M::RTS => Form::Inherent(&[0x39]),
+        M::SEX => Form::Inherent(&[0x1d]),
+        M::SWI => Form::Inherent(&[0x3f]),
+        M::SWI2 => Form::Inherent(&[0x10, 0x3f]),
+        M::SWI3 => Form::Inherent(&[0x11, 0x3f]),
+        M::SYNC => Form::Inherent(&[0x13]),
+        M::NEGA => Form::Inherent(&[0x40]),
+        M::COMA => Form::Inherent(&[0x43]),
+        M::LSRA => Form::Inherent(&[0x44]),
+        M::RORA => Form::Inherent(&[0x46]),
+        M::ASRA => Form::Inherent(&[0x47]),
+        M::ASLA | M::LSLA => Form::Inherent(&[0x48]),
+        M::ROLA => Form::Inherent(&[0x49]),
+        M::DECA => Form::Inherent(&[0x4a]),
+        M::INCA => Form::Inherent(&[0x4c]),
+        M::TSTA => Form::Inherent(&[0x4d]),
+        M::CLRA => Form::Inherent(&[0x4f]),
+        M::NEGB => Form::Inherent(&[0x50]),
+        M::COMB => Form::Inherent(&[0x53]),
+        M::LSRB => Form::Inherent(&[0x54]),
+        M::RORB => Form::Inherent(&[0x56]),
+        M::ASRB => Form::Inherent(&[0x57]),
+        M::ASLB | M::LSLB => Form::Inherent(&[0x58]),
+        M::ROLB => Form::Inherent(&[0x59]),
+        M::DECB => Form::Inherent(&[0x5a]),
+        M::INCB => Form::Inherent(&[0x5c]),
+        M::TSTB => Form::Inherent(&[0x5d]),
+        M::CLRB => Form::Inherent(&[0x5f]),
+
+        M::NEG => Form::Memory(Opcodes::memory(0x00)),
+        M::COM => Form::Memory(Opcodes::memory(0x03)),
+        M::LSR => Form::Memory(Opcodes::memory(0x04)),
+        M::ROR => Form::Memory(Opcodes::memory(0x06)),
+        M::ASR => Form::Memory(Opcodes::memory(0x07)),
+        M::ASL | M::LSL => Form::Memory(Opcodes::memory(0x08)),
+        M::ROL => Form::Memory(Opcodes::memory(0x09)),
+        M::DEC => Form::Memory(Opcodes::memory(0x0a)),
+        M::INC => Form::Memory(Opcodes::memory(0x0c)),
+        M::TST => Form::Memory(Opcodes::memory(0x0d)),
+        M::JMP => Form::Memory(Opcodes::memory(0x0e)),
+        M::CLR => Form::Memory(Opcodes::memory(0x0f)),
+
+        M::SUBA => accumulator(0x80),
+        M::CMPA => accumulator(0x81),
+        M::SBCA => accumulator(0x82),
+        M::SUBD => wide(None, 0x83),
+        M::ANDA => accumulator(0x84),
+        M::BITA => accumulator(0x85),
+        M::LDA => accumulator(0x86),
+        M::STA => store(None, 0x87),
+        M::EORA => accumulator(0x88),
+        M::ADCA => accumulator(0x89),
+        M::ORA => accumulator(0x8a),
+        M::ADDA => accumulator(0x8b),
+        M::CMPX => wide(None, 0x8c),
+        M::JSR => store(None, 0x8d),
+        M::LDX => wide(None, 0x8e),
+        M::STX => store(None, 0x8f),
+        M::SUBB => accumulator(0xc0),
+        M::CMPB => accumulator(0xc1),
+        M::SBCB => accumulator(0xc2),
+        M::ADDD => wide(None, 0xc3),
+        M::ANDB => accumulator(0xc4),
+        M::BITB => accumulator(0xc5),
+        M::LDB => accumulator(0xc6),
+        M::STB => store(None, 0xc7),
+        M::EORB => accumulator(0xc8),
+        M::ADCB => accumulator(0xc9),
+        M::ORB => accumulator(0xca),
+        M::ADDB => accumulator(0xcb),
+        M::LDD => wide(None, 0xcc),
+        M::STD => store(None, 0xcd),
+        M::LDU => wide(None, 0xce),
+        M::STU => store(None, 0xcf),
+        M::CMPD => wide(Some(0x10), 0x83),
+        M::CMPY => wide(Some(0x10), 0x8c),
+        M::LDY => wide(Some(0x10), 0x8e),
+        M::STY => store(Some(0x10), 0x8f),
+        M::LDS => wide(Some(0x10), 0xce),
+        M::STS => store(Some(0x10), 0xcf),
+        M::CMPU => wide(Some(0x11), 0x83),
+        M::CMPS => wide(Some(0x11), 0x8c),
+
+        M::LEAX => Form::LoadEffectiveAddress(0x30),
+        M::LEAY => Form::LoadEffectiveAddress(0x31),
+        M::LEAS => Form::LoadEffectiveAddress(0x32),
+        M::LEAU => Form::LoadEffectiveAddress(0x33),
+        M::PSHS => Form::Stack(0x34, Register::S),
+        M::PULS => Form::Stack(0x35, Register::S),
+        M::PSHU => Form::Stack(0x36, Register::U),
+        M::PULU => Form::Stack(0x37, Register::U),
+        M::EXG => Form::Transfer(0x1e),
+        M::TFR => Form::Transfer(0x1f),
+        M::ORCC => Form::ConditionCode(0x1a),
+        M::ANDCC => Form::ConditionCode(0x1c),
+        M::CWAI => Form::ConditionCode(0x3c),
+
+        M::BRA => Form::Branch(0x20),
+        M::BRN => Form::Branch(0x21),
+        M::BHI => Form::Branch(0x22),
+        M::BLS => Form::Branch(0x23),
+        M::BCC | M::BHS => Form::Branch(0x24),
+        M::BCS | M::BLO => Form::Branch(0x25),
+        M::BNE => Form::Branch(0x26),
+        M::BEQ => Form::Branch(0x27),
+        M::BVC => Form::Branch(0x28),
+        M::BVS => Form::Branch(0x29),
+        M::BPL => Form::Branch(0x2a),
+        M::BMI => Form::Branch(0x2b),
+        M::BGE => Form::Branch(0x2c),
+        M::BLT => Form::Branch(0x2d),
+        M::BGT => Form::Branch(0x2e),
+        M::BLE => Form::Branch(0x2f),
+        M::BSR => Form::Branch(0x8d),
+        M::LBRA => Form::LongBranch(&[0x16]),
+        M::LBSR => Form::LongBranch(&[0x17]),
+        M::LBRN => Form::LongBranch(&[0x10, 0x21]),
+        M::LBHI => Form::LongBranch(&[0x10, 0x22]),
+        M::LBLS => Form::LongBranch(&[0x10, 0x23]),
+        M::LBCC | M::LBHS => Form::LongBranch(&[0x10, 0x24]),
+        M::LBCS | M::LBLO => Form::LongBranch(&[0x10, 0x25]),
+        M::LBNE => Form::LongBranch(&[0x10, 0x26]),
+        M::LBEQ => Form::LongBranch(&[0x10, 0x27]),
+        M::LBVC => Form::LongBranch(&[0x10, 0x28]),
+        M::LBVS => Form::LongBranch(&[0x10, 0x29]),
+        M::LBPL => Form::LongBranch(&[0x10, 0x2a]),
+        M::LBMI => Form::LongBranch(&[0x10, 0x2b]),
+        M::LBGE => Form::LongBranch(&[0x10, 0x2c]),
+        M::LBLT => Form::LongBranch(&[0x10, 0x2d]),
+        M::LBGT => Form::LongBranch(&[0x10, 0x2e]),
+        M::LBLE => Form::LongBranch(&[0x10, 0x2f]),
+
+        M::SETDP => Form::SetDirectPage,
+    }
+}
+
+/// Encodes an instruction positioned at the provided offset against its
+/// resolved operand and the assumed direct page. Unresolved values select
+/// the widest encoding available to them.
+pub fn encode(
+    mnemonic: Mnemonic,
+    operand: &Operand<Resolved>,
+    direct_page: Resolved,
+    position: usize,
+) -> Encoding {
+    use Operand as O;
+
+    match (form(mnemonic), operand) {
+        (Form::Inherent(opcodes), O::Inherent) => Ok(opcodes.to_vec()),
+        (Form::Memory(opcodes), operand) => memory(&opcodes, operand, direct_page, position),
+        (Form::LoadEffectiveAddress(opcode), operand) => {
+            let indexed = as_indexed(operand).ok_or(EncodeErr::InvalidOperands)?;
+            let postbyte = postbyte(&indexed, position + 1)?;
+            Ok(std::iter::once(opcode).chain(postbyte).collect())
+        }
+        (Form::Stack(opcode, stack), O::Registers(registers)) => {
+            let mut postbyte = 0u8;
+            for register in registers {
+                if *register == stack {
+                    return Err(EncodeErr::InvalidOperands);
+                }
+                postbyte |= register.stack_bits();
+            }
+            Ok(vec![opcode, postbyte])
+        }
+        (Form::Transfer(opcode), O::Registers(registers)) => match registers.as_slice() {
+            [src, dst] if src.is_wide() == dst.is_wide() => Ok(vec![
+                opcode,
+                (src.transfer_code() << 4) | dst.transfer_code(),
+            ]),
+            _ => Err(EncodeErr::InvalidOperands),
+        },
+        (Form::ConditionCode(opcode), O::Immediate(value)) => Ok(vec![opcode, byte(*value)?]),
+        (Form::Branch(opcode), O::Address(target)) => {
+            let offset = target.map_or(0, |t| t - (position as i64 + 2));
+            match offset {
+                -128..=127 => Ok(vec![opcode, offset as u8]),
+                _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+            }
+        }
+        (Form::LongBranch(opcodes), O::Address(target)) => {
+            let next = position + opcodes.len() + 2;
+            let offset = target.map_or(0, |t| t - next as i64);
+            let [hi, lo] = (offset as u16).to_be_bytes();
+            Ok(opcodes.iter().copied().chain([hi, lo]).collect())
+        }
+        (Form::SetDirectPage, O::Address(_) | O::Immediate(_)) => Ok(vec![]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes an instruction taking a memory operand, selecting direct page
+/// addressing for any address within the assumed direct page.
+fn memory(
+    opcodes: &Opcodes,
+    operand: &Operand<Resolved>,
+    direct_page: Resolved,
+    position: usize,
+) -> Encoding {
+    let direct = |address: i64| -> Encoding {
+        Ok([opcodes.with(opcodes.direct), vec![address as u8]].concat())
+    };
+    let extended = |address: Resolved| -> Encoding {
+        Ok([opcodes.with(opcodes.extended), word(address)?.to_vec()].concat())
+    };
+
+    match operand {
+        Operand::Immediate(value) => match opcodes.immediate {
+            Some((opcode, 1)) => Ok([opcodes.with(opcode), vec![byte(*value)?]].concat()),
+            Some((opcode, _)) => Ok([opcodes.with(opcode), word(*value)?.to_vec()].concat()),
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        Operand::Direct(address) => match (address.unwrap_or(0), direct_page) {
+            (address @ 0..=0xff, _) => direct(address),
+            (address, Some(page)) if address >> 8 == page => direct(address),
+            (address, _) => Err(EncodeErr::OutOfRange(address)),
+        },
+        Operand::Extended(address) => extended(*address),
+        Operand::Address(address) => match (address, direct_page) {
+            (Some(address), Some(page)) if address >> 8 == page => direct(*address),
+            _ => extended(*address),
+        },
+        operand => {
+            let indexed = as_indexed(operand).ok_or(EncodeErr::InvalidOperands)?;
+            let opcode = opcodes.with(opcodes.indexed);
+            let postbyte = postbyte(&indexed, position + opcode.len())?;
+            Ok([opcode, postbyte].concat())
+        }
+    }
+}
+
+/// Interprets an operand as indexed, treating a pair of registers such as
+/// `b,x` as an accumulator offset.
+fn as_indexed(operand: &Operand<Resolved>) -> Option<Indexed<Resolved>> {
+    match operand {
+        Operand::Indexed(indexed) => Some(indexed.clone()),
+        Operand::Registers(registers) => match registers.as_slice() {
+            [accumulator @ (Register::A | Register::B | Register::D), index] => Some(Indexed::new(
+                false,
+                IndexMode::Accumulator(*accumulator, index.as_index()?),
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Encodes the postbyte of an indexed operand and any offset following it,
+/// selecting the narrowest constant offset that fits. The postbyte begins at
+/// the provided position, against which program counter relative offsets
+/// are calculated.
+fn postbyte(indexed: &Indexed<Resolved>, position: usize) -> Encoding {
+    let indirect = if indexed.indirect { 0x10 } else { 0x00 };
+
+    let bytes = match &indexed.mode {
+        IndexMode::Offset(r, None) => vec![0x84 | r.code() | indirect],
+        IndexMode::Offset(r, Some(offset)) => {
+            let value = offset.value.map(|v| if offset.negative { -v } else { v });
+            match value {
+                Some(0) => vec![0x84 | r.code() | indirect],
+                Some(v @ -16..=15) if !indexed.indirect => vec![r.code() | (v as u8 & 0x1f)],
+                Some(v @ -128..=127) => vec![0x88 | r.code() | indirect, v as u8],
+                _ => [vec![0x89 | r.code() | indirect], word(value)?.to_vec()].concat(),
+            }
+        }
+        IndexMode::Accumulator(accumulator, r) => {
+            let mode = match accumulator {
+                Register::A => 0x86,
+                Register::B => 0x85,
+                Register::D => 0x8b,
+                _ => return Err(EncodeErr::InvalidOperands),
+            };
+            vec![mode | r.code() | indirect]
+        }
+        IndexMode::PostIncrement(r) if !indexed.indirect => vec![0x80 | r.code()],
+        IndexMode::PostIncrementTwice(r) => vec![0x81 | r.code() | indirect],
+        IndexMode::PreDecrement(r) if !indexed.indirect => vec![0x82 | r.code()],
+        IndexMode::PreDecrementTwice(r) => vec![0x83 | r.code() | indirect],
+        // program counter relative offsets are always 16-bit, leaving their
+        // size independent of their position.
+        IndexMode::ProgramCounterRelative(target) => {
+            let offset = target.map(|t| t - (position as i64 + 3));
+            let [hi, lo] = (offset.unwrap_or(0) as u16).to_be_bytes();
+            vec![0x8d | indirect, hi, lo]
+        }
+        IndexMode::Extended(address) if indexed.indirect => {
+            [vec![0x9f], word(*address)?.to_vec()].concat()
+        }
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(bytes)
+}
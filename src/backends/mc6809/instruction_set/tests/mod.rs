@@ -0,0 +1,266 @@
+use crate::backends::mc6809::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6809::instruction_set::{
+    encode, EncodeErr, IndexMode, IndexRegister, Indexed, Offset, Operand, Register, Resolved,
+};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, $operand:expr, $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &$operand, Some(0), 0));
+    };
+}
+
+fn indexed(mode: IndexMode<Resolved>) -> Operand<Resolved> {
+    Operand::Indexed(Indexed::new(false, mode))
+}
+
+fn indirect(mode: IndexMode<Resolved>) -> Operand<Resolved> {
+    Operand::Indexed(Indexed::new(true, mode))
+}
+
+fn offset(r: IndexRegister, value: i64) -> IndexMode<Resolved> {
+    IndexMode::Offset(r, Some(Offset::new(value < 0, Some(value.abs()))))
+}
+
+#[test]
+fn should_encode_inherent_instructions() {
+    gen_encode_test!(Mnemonic::NOP, Operand::Inherent, vec![0x12]);
+    gen_encode_test!(Mnemonic::CLRA, Operand::Inherent, vec![0x4f]);
+    gen_encode_test!(Mnemonic::LSLB, Operand::Inherent, vec![0x58]);
+    gen_encode_test!(Mnemonic::SWI3, Operand::Inherent, vec![0x11, 0x3f]);
+}
+
+#[test]
+fn should_encode_immediate_operands_by_register_width() {
+    gen_encode_test!(
+        Mnemonic::LDA,
+        Operand::Immediate(Some(0x12)),
+        vec![0x86, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::LDX,
+        Operand::Immediate(Some(0x1234)),
+        vec![0x8e, 0x12, 0x34]
+    );
+    gen_encode_test!(
+        Mnemonic::CMPD,
+        Operand::Immediate(Some(0x1234)),
+        vec![0x10, 0x83, 0x12, 0x34]
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(Mnemonic::STA, &Operand::Immediate(Some(0x12)), Some(0), 0)
+    );
+}
+
+#[test]
+fn should_select_direct_addressing_within_the_direct_page() {
+    gen_encode_test!(
+        Mnemonic::LDA,
+        Operand::Address(Some(0x20)),
+        vec![0x96, 0x20]
+    );
+    gen_encode_test!(
+        Mnemonic::LDA,
+        Operand::Address(Some(0x2000)),
+        vec![0xb6, 0x20, 0x00]
+    );
+    gen_encode_test!(
+        Mnemonic::STY,
+        Operand::Address(Some(0x20)),
+        vec![0x10, 0x9f, 0x20]
+    );
+    gen_encode_test!(
+        Mnemonic::INC,
+        Operand::Address(Some(0x20)),
+        vec![0x0c, 0x20]
+    );
+    gen_encode_test!(
+        Mnemonic::INC,
+        Operand::Extended(Some(0x20)),
+        vec![0x7c, 0x00, 0x20]
+    );
+
+    assert_eq!(
+        Ok(vec![0x96, 0x10]),
+        encode(
+            Mnemonic::LDA,
+            &Operand::Address(Some(0x2010)),
+            Some(0x20),
+            0
+        )
+    );
+    assert_eq!(
+        Ok(vec![0xb6, 0x20, 0x10]),
+        encode(Mnemonic::LDA, &Operand::Address(Some(0x2010)), None, 0)
+    );
+}
+
+#[test]
+fn should_encode_indexed_postbytes() {
+    use IndexRegister::*;
+
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indexed(IndexMode::Offset(X, None)),
+        vec![0xa6, 0x84]
+    );
+    gen_encode_test!(Mnemonic::LDA, indexed(offset(Y, -4)), vec![0xa6, 0x3c]);
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indexed(offset(U, 0x40)),
+        vec![0xa6, 0xc8, 0x40]
+    );
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indexed(offset(S, 0x1234)),
+        vec![0xa6, 0xe9, 0x12, 0x34]
+    );
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indexed(IndexMode::Accumulator(Register::B, X)),
+        vec![0xa6, 0x85]
+    );
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indexed(IndexMode::PostIncrement(X)),
+        vec![0xa6, 0x80]
+    );
+    gen_encode_test!(
+        Mnemonic::LDD,
+        indexed(IndexMode::PostIncrementTwice(Y)),
+        vec![0xec, 0xa1]
+    );
+    gen_encode_test!(
+        Mnemonic::STA,
+        indexed(IndexMode::PreDecrement(U)),
+        vec![0xa7, 0xc2]
+    );
+    gen_encode_test!(
+        Mnemonic::LEAS,
+        indexed(IndexMode::PreDecrementTwice(S)),
+        vec![0x32, 0xe3]
+    );
+    gen_encode_test!(
+        Mnemonic::CLR,
+        indexed(IndexMode::Offset(X, None)),
+        vec![0x6f, 0x84]
+    );
+}
+
+#[test]
+fn should_encode_indirect_indexed_postbytes() {
+    use IndexRegister::*;
+
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indirect(IndexMode::Offset(X, None)),
+        vec![0xa6, 0x94]
+    );
+    gen_encode_test!(
+        Mnemonic::LDA,
+        indirect(offset(X, 4)),
+        vec![0xa6, 0x98, 0x04]
+    );
+    gen_encode_test!(
+        Mnemonic::JMP,
+        indirect(IndexMode::Extended(Some(0xfffe))),
+        vec![0x6e, 0x9f, 0xff, 0xfe]
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Mnemonic::LDA,
+            &indirect(IndexMode::PostIncrement(X)),
+            Some(0),
+            0
+        )
+    );
+}
+
+#[test]
+fn should_encode_program_counter_relative_offsets_from_the_next_instruction() {
+    assert_eq!(
+        Ok(vec![0x30, 0x8d, 0x00, 0x0c]),
+        encode(
+            Mnemonic::LEAX,
+            &indexed(IndexMode::ProgramCounterRelative(Some(0x110))),
+            Some(0),
+            0x100
+        )
+    );
+}
+
+#[test]
+fn should_encode_short_and_long_branches() {
+    assert_eq!(
+        Ok(vec![0x26, 0xfe]),
+        encode(
+            Mnemonic::BNE,
+            &Operand::Address(Some(0x100)),
+            Some(0),
+            0x100
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(254)),
+        encode(
+            Mnemonic::BRA,
+            &Operand::Address(Some(0x200)),
+            Some(0),
+            0x100
+        )
+    );
+    assert_eq!(
+        Ok(vec![0x16, 0x00, 0xfd]),
+        encode(
+            Mnemonic::LBRA,
+            &Operand::Address(Some(0x200)),
+            Some(0),
+            0x100
+        )
+    );
+    assert_eq!(
+        Ok(vec![0x10, 0x27, 0xff, 0xfc]),
+        encode(
+            Mnemonic::LBEQ,
+            &Operand::Address(Some(0x100)),
+            Some(0),
+            0x100
+        )
+    );
+}
+
+#[test]
+fn should_encode_register_lists() {
+    use Register::*;
+
+    gen_encode_test!(
+        Mnemonic::PSHS,
+        Operand::Registers(vec![A, B, X, PC]),
+        vec![0x34, 0x96]
+    );
+    gen_encode_test!(
+        Mnemonic::PULU,
+        Operand::Registers(vec![D, S]),
+        vec![0x37, 0x46]
+    );
+    gen_encode_test!(
+        Mnemonic::TFR,
+        Operand::Registers(vec![X, Y]),
+        vec![0x1f, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::EXG,
+        Operand::Registers(vec![A, B]),
+        vec![0x1e, 0x89]
+    );
+
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(Mnemonic::PSHS, &Operand::Registers(vec![S]), Some(0), 0)
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(Mnemonic::TFR, &Operand::Registers(vec![A, X]), Some(0), 0)
+    );
+}
@@ -0,0 +1,104 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+use crate::backends::mc6809::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6809::instruction_set::{encode, EncodeErr, Instruction, Operand};
+use crate::backends::BackendErr;
+use crate::preparser::types::Endianness;
+
+/// Mc6809Assembler implements the [InstructionSet] of the 6809.
+#[derive(Default)]
+pub struct Mc6809Assembler;
+
+impl Mc6809Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl InstructionSet for Mc6809Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// Values that are yet to be resolved are sized at their widest, falling
+    /// back to extended addressing and 16-bit offsets.
+    fn size_of(&self, symbol_table: &SymbolTable, instruction: &Instruction) -> usize {
+        let resolve = |v: &Value| Ok::<_, ()>(resolve_value(symbol_table, v).ok());
+        let operand = instruction.operand.try_map(resolve);
+        let direct_page = resolve_value(symbol_table, &instruction.direct_page).ok();
+
+        operand
+            .ok()
+            .and_then(|operand| encode(instruction.mnemonic, &operand, direct_page, 0).ok())
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operand = instruction
+            .operand
+            .try_map(|v| resolve_value(symbol_table, v).map(Some))?;
+        let direct_page = resolve_value(symbol_table, &instruction.direct_page)?;
+
+        encode(instruction.mnemonic, &operand, Some(direct_page), position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operand for {:?}: {:?}",
+                instruction.mnemonic, instruction.operand
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                let target = match &instruction.operand {
+                    Operand::Address(Value::Reference(id)) => id.clone(),
+                    operand => format!("{:?}", operand),
+                };
+                BackendErr::BranchOutOfRange(target, offset)
+            }
+        })
+    }
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Big
+    }
+
+    /// Carries the page set by each `setdp` to the instructions following it
+    /// in source order, across origins.
+    fn apply_processor_state(
+        &self,
+        instructions: &mut [&mut Instruction],
+    ) -> Result<(), BackendErr> {
+        let mut direct_page = Value::Literal(0);
+
+        for instruction in instructions.iter_mut() {
+            match (&instruction.mnemonic, &instruction.operand) {
+                (Mnemonic::SETDP, Operand::Address(page) | Operand::Immediate(page)) => {
+                    direct_page = page.clone()
+                }
+                (Mnemonic::SETDP, operand) => {
+                    return Err(BackendErr::UndefinedInstruction(format!(
+                        "invalid operand for SETDP: {:?}",
+                        operand
+                    )))
+                }
+                _ => instruction.direct_page = direct_page.clone(),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use crate::backends::mc6809::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6809::instruction_set::{
+    IndexMode, Indexed, Instruction, Offset, Operand, Register, Value,
+};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operand(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operand)| Instruction::new(m, operand.unwrap_or(Operand::Inherent)))
+}
+
+#[allow(clippy::redundant_closure)]
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic().or(|| digit(10)))
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+/// Matches any operand. Register lists are tried ahead of indexed operands
+/// so that an accumulator offset such as `b,x` isn't read as a reference to
+/// a symbol named `b`.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(expect_character('#'), value()))
+        .map(Operand::Immediate)
+        .or(|| indirect())
+        .or(|| registers())
+        .or(|| indexed().map(|mode| Operand::Indexed(Indexed::new(false, mode))))
+        .or(|| {
+            join(expect_character('<').or(|| expect_character('>')), value()).map(|(force, v)| {
+                match force {
+                    '<' => Operand::Direct(v),
+                    _ => Operand::Extended(v),
+                }
+            })
+        })
+        .or(|| value().map(Operand::Address))
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches a comma-separated list of registers, as taken by the push, pull
+/// and transfer instructions.
+fn registers<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(
+        register(),
+        zero_or_more(right(join(separator(), register()))),
+    )
+    .map(|(head, tail)| Operand::Registers(std::iter::once(head).chain(tail).collect()))
+}
+
+fn register<'a>() -> impl parcel::Parser<'a, &'a [char], Register> {
+    name()
+        .map(|name| keyword(&name))
+        .predicate(|register| register.is_some())
+        .map(|register| register.unwrap())
+}
+
+fn keyword(name: &str) -> Option<Register> {
+    let register = match name.to_lowercase().as_str() {
+        "a" => Register::A,
+        "b" => Register::B,
+        "d" => Register::D,
+        "x" => Register::X,
+        "y" => Register::Y,
+        "u" => Register::U,
+        "s" => Register::S,
+        "pc" => Register::PC,
+        "cc" => Register::CC,
+        "dp" => Register::DP,
+        _ => return None,
+    };
+
+    Some(register)
+}
+
+/// Matches an indexed operand wrapped in brackets, or an extended indirect
+/// address such as `[$fffe]`.
+#[allow(clippy::redundant_closure)]
+fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(
+        join(
+            expect_character('['),
+            zero_or_more(non_newline_whitespace()),
+        ),
+        left(join(
+            accumulator_offset()
+                .or(|| indexed())
+                .or(|| value().map(IndexMode::Extended)),
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(']'),
+            ),
+        )),
+    ))
+    .map(|mode| Operand::Indexed(Indexed::new(true, mode)))
+}
+
+fn accumulator_offset<'a>() -> impl parcel::Parser<'a, &'a [char], IndexMode<Value>> {
+    join(register(), right(join(separator(), register())))
+        .map(
+            |(accumulator, index)| match (accumulator, index.as_index()) {
+                (Register::A | Register::B | Register::D, Some(index)) => {
+                    Some(IndexMode::Accumulator(accumulator, index))
+                }
+                _ => None,
+            },
+        )
+        .predicate(|mode| mode.is_some())
+        .map(|mode| mode.unwrap())
+}
+
+/// Matches an index register with an optional constant offset, such as
+/// `-4,x` or `label,pcr`, or its auto-increment and decrement forms, such as
+/// `,x++` and `,-y`.
+fn indexed<'a>() -> impl parcel::Parser<'a, &'a [char], IndexMode<Value>> {
+    join(
+        optional(offset()),
+        right(join(
+            separator(),
+            join(
+                join(zero_or_more(expect_character('-')), name()),
+                zero_or_more(expect_character('+')),
+            ),
+        )),
+    )
+    .map(|(offset, ((decrements, name), increments))| {
+        index_mode(offset, decrements.len(), &name, increments.len())
+    })
+    .predicate(|mode| mode.is_some())
+    .map(|mode| mode.unwrap())
+}
+
+fn index_mode(
+    offset: Option<Offset<Value>>,
+    decrements: usize,
+    name: &str,
+    increments: usize,
+) -> Option<IndexMode<Value>> {
+    if name.to_lowercase() == "pcr" {
+        return match (offset, decrements, increments) {
+            (Some(offset), 0, 0) if !offset.negative => {
+                Some(IndexMode::ProgramCounterRelative(offset.value))
+            }
+            _ => None,
+        };
+    }
+
+    let index = keyword(name)?.as_index()?;
+    match (offset, decrements, increments) {
+        (offset, 0, 0) => Some(IndexMode::Offset(index, offset)),
+        (None, 1, 0) => Some(IndexMode::PreDecrement(index)),
+        (None, 2, 0) => Some(IndexMode::PreDecrementTwice(index)),
+        (None, 0, 1) => Some(IndexMode::PostIncrement(index)),
+        (None, 0, 2) => Some(IndexMode::PostIncrementTwice(index)),
+        _ => None,
+    }
+}
+
+fn offset<'a>() -> impl parcel::Parser<'a, &'a [char], Offset<Value>> {
+    join(optional(expect_character('-')), value())
+        .map(|(sign, value)| Offset::new(sign.is_some(), value))
+}
@@ -0,0 +1,124 @@
+use crate::backends::mc6809::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mc6809::instruction_set::{
+    IndexMode, IndexRegister, Indexed, Instruction, Offset, Operand, Register, Value,
+};
+use crate::backends::mc6809::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operand:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operand)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+fn indexed(indirect: bool, mode: IndexMode<Value>) -> Operand<Value> {
+    Operand::Indexed(Indexed::new(indirect, mode))
+}
+
+#[test]
+fn should_parse_inherent_instructions() {
+    gen_inst_test!("  nop", Mnemonic::NOP, Operand::Inherent);
+    gen_inst_test!("SWI2", Mnemonic::SWI2, Operand::Inherent);
+}
+
+#[test]
+fn should_parse_immediate_and_address_operands() {
+    gen_inst_test!(
+        "lda #$12",
+        Mnemonic::LDA,
+        Operand::Immediate(Value::Literal(0x12))
+    );
+    gen_inst_test!(
+        "jmp start",
+        Mnemonic::JMP,
+        Operand::Address(Value::Reference("start".to_string()))
+    );
+    gen_inst_test!(
+        "lda <$20",
+        Mnemonic::LDA,
+        Operand::Direct(Value::Literal(0x20))
+    );
+    gen_inst_test!(
+        "lda >$20",
+        Mnemonic::LDA,
+        Operand::Extended(Value::Literal(0x20))
+    );
+    gen_inst_test!(
+        "setdp $20",
+        Mnemonic::SETDP,
+        Operand::Address(Value::Literal(0x20))
+    );
+}
+
+#[test]
+fn should_parse_indexed_operands() {
+    use IndexRegister::*;
+
+    gen_inst_test!(
+        "lda ,x",
+        Mnemonic::LDA,
+        indexed(false, IndexMode::Offset(X, None))
+    );
+    gen_inst_test!(
+        "lda -4,y",
+        Mnemonic::LDA,
+        indexed(
+            false,
+            IndexMode::Offset(Y, Some(Offset::new(true, Value::Literal(4))))
+        )
+    );
+    gen_inst_test!(
+        "ldd ,u++",
+        Mnemonic::LDD,
+        indexed(false, IndexMode::PostIncrementTwice(U))
+    );
+    gen_inst_test!(
+        "sta ,-s",
+        Mnemonic::STA,
+        indexed(false, IndexMode::PreDecrement(S))
+    );
+    gen_inst_test!(
+        "leax table,pcr",
+        Mnemonic::LEAX,
+        indexed(
+            false,
+            IndexMode::ProgramCounterRelative(Value::Reference("table".to_string()))
+        )
+    );
+}
+
+#[test]
+fn should_parse_indirect_operands() {
+    gen_inst_test!(
+        "lda [b,x]",
+        Mnemonic::LDA,
+        indexed(true, IndexMode::Accumulator(Register::B, IndexRegister::X))
+    );
+    gen_inst_test!(
+        "jmp [$fffe]",
+        Mnemonic::JMP,
+        indexed(true, IndexMode::Extended(Value::Literal(0xfffe)))
+    );
+}
+
+#[test]
+fn should_parse_register_lists() {
+    gen_inst_test!(
+        "pshs a, b, x",
+        Mnemonic::PSHS,
+        Operand::Registers(vec![Register::A, Register::B, Register::X])
+    );
+    gen_inst_test!(
+        "lda b,x",
+        Mnemonic::LDA,
+        Operand::Registers(vec![Register::B, Register::X])
+    );
+}
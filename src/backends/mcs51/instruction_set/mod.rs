@@ -0,0 +1,350 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+
+/// Operand represents each form of MCS-51 operand, generalized over its
+/// value so that operands may be parsed with references and encoded once
+/// resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    /// The accumulator, `a`.
+    Accumulator,
+    /// The accumulator and `b` register pair of `mul` and `div`, `ab`.
+    AccumulatorB,
+    /// The carry flag, `c`.
+    Carry,
+    /// The data pointer, `dptr`.
+    DataPointer,
+    /// One of the registers of the selected bank, `r0` through `r7`.
+    Register(u8),
+    /// Internal RAM addressed by `r0` or `r1`, such as `@r0`.
+    Indirect(u8),
+    /// External RAM addressed by the data pointer, `@dptr`.
+    IndirectDataPointer,
+    /// Code memory addressed by the accumulator and data pointer,
+    /// `@a+dptr`.
+    IndexedDataPointer,
+    /// Code memory addressed by the accumulator and program counter,
+    /// `@a+pc`.
+    IndexedProgramCounter,
+    Immediate(V),
+    /// A direct address, bit address or code address, depending on the
+    /// instruction.
+    Direct(V),
+    /// A bit of a bit-addressable byte, such as `acc.7`.
+    BitOf(V, u8),
+    /// The complement of a bit, given either by its address or as a bit of a
+    /// byte, such as `/p1.0`.
+    Complement(V, Option<u8>),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        let operand = match self {
+            Self::Accumulator => Operand::Accumulator,
+            Self::AccumulatorB => Operand::AccumulatorB,
+            Self::Carry => Operand::Carry,
+            Self::DataPointer => Operand::DataPointer,
+            Self::Register(n) => Operand::Register(*n),
+            Self::Indirect(i) => Operand::Indirect(*i),
+            Self::IndirectDataPointer => Operand::IndirectDataPointer,
+            Self::IndexedDataPointer => Operand::IndexedDataPointer,
+            Self::IndexedProgramCounter => Operand::IndexedProgramCounter,
+            Self::Immediate(v) => Operand::Immediate(f(v)?),
+            Self::Direct(v) => Operand::Direct(f(v)?),
+            Self::BitOf(v, bit) => Operand::BitOf(f(v)?, *bit),
+            Self::Complement(v, bit) => Operand::Complement(f(v)?, *bit),
+        };
+
+        Ok(operand)
+    }
+}
+
+/// Instruction represents a single MCS-51 mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+    /// The target of an `ajmp` or `acall` lies outside of the 2K page of
+    /// the instruction following it.
+    OutOfPage(i64),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Resolved represents a value that may still reference an unresolved
+/// symbol, in which case the value is zeroed and left unvalidated. As the
+/// size of an instruction depends only on the form of its operands, this
+/// allows instructions to be sized before all symbols are known.
+pub type Resolved = Option<i64>;
+
+fn direct(value: Resolved) -> Result<u8, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ 0..=0xff => Ok(v as u8),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn immediate(value: Resolved) -> Result<u8, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x80..=0xff => Ok(v as u8),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+/// 16-bit operands are encoded most significant byte first.
+fn word(value: Resolved) -> Result<[u8; 2], EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x8000..=0xffff => Ok((v as u16).to_be_bytes()),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+/// Returns the bit address of a bit, given either by its address or as a
+/// bit of a byte. Only the bytes of `0x20..=0x2f` and the special function
+/// registers at multiples of 8 are bit-addressable.
+fn bit_address(value: Resolved, bit: Option<u8>) -> Result<u8, EncodeErr> {
+    match (value, bit) {
+        (value, None) => direct(value),
+        (_, Some(bit @ 8..)) => Err(EncodeErr::OutOfRange(i64::from(bit))),
+        (None, Some(_)) => Ok(0),
+        (Some(byte @ 0x20..=0x2f), Some(bit)) => Ok((byte as u8 - 0x20) * 8 + bit),
+        (Some(byte @ 0x80..=0xff), Some(bit)) if byte % 8 == 0 => Ok(byte as u8 + bit),
+        (Some(byte), Some(_)) => Err(EncodeErr::OutOfRange(byte)),
+    }
+}
+
+fn bit(operand: &Operand<Resolved>) -> Result<u8, EncodeErr> {
+    match operand {
+        Operand::Direct(value) => bit_address(*value, None),
+        Operand::BitOf(value, bit) => bit_address(*value, Some(*bit)),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Returns the offset of a relative jump, positioned at the provided offset
+/// and of the provided length, to its target.
+fn relative(position: usize, len: usize, target: Resolved) -> Result<u8, EncodeErr> {
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(0),
+    };
+
+    let offset = target - (position + len) as i64;
+    match offset {
+        -0x80..=0x7f => Ok(offset as i8 as u8),
+        _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+    }
+}
+
+/// Encodes an `ajmp` or `acall`, embedding the upper 3 bits of the 11-bit
+/// target in the opcode. The target must share its upper 5 bits with the
+/// instruction following it.
+fn absolute11(opcode: u8, position: usize, target: Resolved) -> Encoding {
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(vec![opcode, 0x00]),
+    };
+
+    let next = (position + 2) as i64;
+    if !(0..=0xffff).contains(&target) || (target & 0xf800) != (next & 0xf800) {
+        return Err(EncodeErr::OutOfPage(target));
+    }
+
+    Ok(vec![opcode | ((target >> 3) as u8 & 0xe0), target as u8])
+}
+
+fn absolute16(opcode: u8, target: Resolved) -> Encoding {
+    let [hi, lo] = word(target)?;
+    Ok(vec![opcode, hi, lo])
+}
+
+/// Encodes an instruction, positioned at the provided offset, against its
+/// resolved operands.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<Resolved>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    let logical_base = |mnemonic| match mnemonic {
+        M::ORL => 0x40,
+        M::ANL => 0x50,
+        _ => 0x60,
+    };
+
+    match (mnemonic, operands) {
+        (M::NOP, []) => Ok(vec![0x00]),
+        (M::RET, []) => Ok(vec![0x22]),
+        (M::RETI, []) => Ok(vec![0x32]),
+        (M::ADD | M::ADDC | M::SUBB | M::ORL | M::ANL | M::XRL, [O::Accumulator, src]) => {
+            let base = match mnemonic {
+                M::ADD => 0x20,
+                M::ADDC => 0x30,
+                M::SUBB => 0x90,
+                m => logical_base(m),
+            };
+            accumulator_source(base, src)
+        }
+        (M::ORL | M::ANL | M::XRL, [O::Direct(address), O::Accumulator]) => {
+            Ok(vec![logical_base(mnemonic) + 2, direct(*address)?])
+        }
+        (M::ORL | M::ANL | M::XRL, [O::Direct(address), O::Immediate(value)]) => Ok(vec![
+            logical_base(mnemonic) + 3,
+            direct(*address)?,
+            immediate(*value)?,
+        ]),
+        (M::ORL, [O::Carry, O::Complement(value, bit)]) => {
+            Ok(vec![0xa0, bit_address(*value, *bit)?])
+        }
+        (M::ANL, [O::Carry, O::Complement(value, bit)]) => {
+            Ok(vec![0xb0, bit_address(*value, *bit)?])
+        }
+        (M::ORL, [O::Carry, src]) => Ok(vec![0x72, bit(src)?]),
+        (M::ANL, [O::Carry, src]) => Ok(vec![0x82, bit(src)?]),
+        (M::INC, [O::DataPointer]) => Ok(vec![0xa3]),
+        (M::INC | M::DEC, [operand]) => {
+            let base = if mnemonic == M::INC { 0x00 } else { 0x10 };
+            match operand {
+                O::Accumulator => Ok(vec![base + 0x04]),
+                O::Direct(address) => Ok(vec![base + 0x05, direct(*address)?]),
+                O::Indirect(i) => Ok(vec![base + 0x06 + i]),
+                O::Register(n) => Ok(vec![base + 0x08 + n]),
+                _ => Err(EncodeErr::InvalidOperands),
+            }
+        }
+        (M::MUL, [O::AccumulatorB]) => Ok(vec![0xa4]),
+        (M::DIV, [O::AccumulatorB]) => Ok(vec![0x84]),
+        (M::DA, [O::Accumulator]) => Ok(vec![0xd4]),
+        (M::CLR, [O::Accumulator]) => Ok(vec![0xe4]),
+        (M::CPL, [O::Accumulator]) => Ok(vec![0xf4]),
+        (M::RL, [O::Accumulator]) => Ok(vec![0x23]),
+        (M::RLC, [O::Accumulator]) => Ok(vec![0x33]),
+        (M::RR, [O::Accumulator]) => Ok(vec![0x03]),
+        (M::RRC, [O::Accumulator]) => Ok(vec![0x13]),
+        (M::SWAP, [O::Accumulator]) => Ok(vec![0xc4]),
+        (M::CLR, [O::Carry]) => Ok(vec![0xc3]),
+        (M::SETB, [O::Carry]) => Ok(vec![0xd3]),
+        (M::CPL, [O::Carry]) => Ok(vec![0xb3]),
+        (M::CLR, [operand]) => Ok(vec![0xc2, bit(operand)?]),
+        (M::SETB, [operand]) => Ok(vec![0xd2, bit(operand)?]),
+        (M::CPL, [operand]) => Ok(vec![0xb2, bit(operand)?]),
+        (M::MOV, [dst, src]) => mov(dst, src),
+        (M::MOVC, [O::Accumulator, O::IndexedDataPointer]) => Ok(vec![0x93]),
+        (M::MOVC, [O::Accumulator, O::IndexedProgramCounter]) => Ok(vec![0x83]),
+        (M::MOVX, [O::Accumulator, O::IndirectDataPointer]) => Ok(vec![0xe0]),
+        (M::MOVX, [O::Accumulator, O::Indirect(i)]) => Ok(vec![0xe2 + i]),
+        (M::MOVX, [O::IndirectDataPointer, O::Accumulator]) => Ok(vec![0xf0]),
+        (M::MOVX, [O::Indirect(i), O::Accumulator]) => Ok(vec![0xf2 + i]),
+        (M::PUSH, [O::Direct(address)]) => Ok(vec![0xc0, direct(*address)?]),
+        (M::POP, [O::Direct(address)]) => Ok(vec![0xd0, direct(*address)?]),
+        (M::XCH, [O::Accumulator, O::Direct(address)]) => Ok(vec![0xc5, direct(*address)?]),
+        (M::XCH, [O::Accumulator, O::Indirect(i)]) => Ok(vec![0xc6 + i]),
+        (M::XCH, [O::Accumulator, O::Register(n)]) => Ok(vec![0xc8 + n]),
+        (M::XCHD, [O::Accumulator, O::Indirect(i)]) => Ok(vec![0xd6 + i]),
+        (M::AJMP, [O::Direct(target)]) => absolute11(0x01, position, *target),
+        (M::ACALL, [O::Direct(target)]) => absolute11(0x11, position, *target),
+        (M::LJMP, [O::Direct(target)]) => absolute16(0x02, *target),
+        (M::LCALL, [O::Direct(target)]) => absolute16(0x12, *target),
+        (M::JMP, [O::IndexedDataPointer]) => Ok(vec![0x73]),
+        (M::SJMP | M::JZ | M::JNZ | M::JC | M::JNC, [O::Direct(target)]) => {
+            let opcode = match mnemonic {
+                M::SJMP => 0x80,
+                M::JZ => 0x60,
+                M::JNZ => 0x70,
+                M::JC => 0x40,
+                _ => 0x50,
+            };
+            Ok(vec![opcode, relative(position, 2, *target)?])
+        }
+        (M::JB | M::JNB | M::JBC, [operand, O::Direct(target)]) => {
+            let opcode = match mnemonic {
+                M::JB => 0x20,
+                M::JNB => 0x30,
+                _ => 0x10,
+            };
+            Ok(vec![opcode, bit(operand)?, relative(position, 3, *target)?])
+        }
+        (M::CJNE, [dst, src, O::Direct(target)]) => {
+            let (opcode, operand) = match (dst, src) {
+                (O::Accumulator, O::Direct(address)) => (0xb5, direct(*address)?),
+                (O::Accumulator, O::Immediate(value)) => (0xb4, immediate(*value)?),
+                (O::Indirect(i), O::Immediate(value)) => (0xb6 + i, immediate(*value)?),
+                (O::Register(n), O::Immediate(value)) => (0xb8 + n, immediate(*value)?),
+                _ => return Err(EncodeErr::InvalidOperands),
+            };
+            Ok(vec![opcode, operand, relative(position, 3, *target)?])
+        }
+        (M::DJNZ, [O::Register(n), O::Direct(target)]) => {
+            Ok(vec![0xd8 + n, relative(position, 2, *target)?])
+        }
+        (M::DJNZ, [O::Direct(address), O::Direct(target)]) => Ok(vec![
+            0xd5,
+            direct(*address)?,
+            relative(position, 3, *target)?,
+        ]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes an arithmetic or logical operation on the accumulator, with each
+/// source addressing mode offset from the base opcode.
+fn accumulator_source(base: u8, src: &Operand<Resolved>) -> Encoding {
+    match src {
+        Operand::Immediate(value) => Ok(vec![base + 0x04, immediate(*value)?]),
+        Operand::Direct(address) => Ok(vec![base + 0x05, direct(*address)?]),
+        Operand::Indirect(i) => Ok(vec![base + 0x06 + i]),
+        Operand::Register(n) => Ok(vec![base + 0x08 + n]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+fn mov(dst: &Operand<Resolved>, src: &Operand<Resolved>) -> Encoding {
+    use Operand as O;
+
+    match (dst, src) {
+        (O::Accumulator, O::Immediate(value)) => Ok(vec![0x74, immediate(*value)?]),
+        (O::Accumulator, O::Direct(address)) => Ok(vec![0xe5, direct(*address)?]),
+        (O::Accumulator, O::Indirect(i)) => Ok(vec![0xe6 + i]),
+        (O::Accumulator, O::Register(n)) => Ok(vec![0xe8 + n]),
+        (O::Register(n), O::Accumulator) => Ok(vec![0xf8 + n]),
+        (O::Register(n), O::Direct(address)) => Ok(vec![0xa8 + n, direct(*address)?]),
+        (O::Register(n), O::Immediate(value)) => Ok(vec![0x78 + n, immediate(*value)?]),
+        (O::Direct(address), O::Accumulator) => Ok(vec![0xf5, direct(*address)?]),
+        (O::Direct(address), O::Register(n)) => Ok(vec![0x88 + n, direct(*address)?]),
+        // the source address precedes the destination.
+        (O::Direct(dst), O::Direct(src)) => Ok(vec![0x85, direct(*src)?, direct(*dst)?]),
+        (O::Direct(address), O::Indirect(i)) => Ok(vec![0x86 + i, direct(*address)?]),
+        (O::Direct(address), O::Immediate(value)) => {
+            Ok(vec![0x75, direct(*address)?, immediate(*value)?])
+        }
+        (O::Indirect(i), O::Accumulator) => Ok(vec![0xf6 + i]),
+        (O::Indirect(i), O::Direct(address)) => Ok(vec![0xa6 + i, direct(*address)?]),
+        (O::Indirect(i), O::Immediate(value)) => Ok(vec![0x76 + i, immediate(*value)?]),
+        (O::DataPointer, O::Immediate(value)) => {
+            let [hi, lo] = word(*value)?;
+            Ok(vec![0x90, hi, lo])
+        }
+        (O::Carry, src) => Ok(vec![0xa2, bit(src)?]),
+        (dst, O::Carry) => Ok(vec![0x92, bit(dst)?]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
@@ -0,0 +1,165 @@
+use crate::backends::mcs51::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mcs51::instruction_set::{encode, EncodeErr, Operand};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &[$($operand),*], 0));
+    };
+}
+
+#[test]
+fn should_encode_each_source_addressing_mode() {
+    gen_encode_test!(
+        Mnemonic::ADD,
+        [Operand::Accumulator, Operand::Immediate(Some(0x12))],
+        vec![0x24, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::ADDC,
+        [Operand::Accumulator, Operand::Direct(Some(0x30))],
+        vec![0x35, 0x30]
+    );
+    gen_encode_test!(
+        Mnemonic::SUBB,
+        [Operand::Accumulator, Operand::Indirect(1)],
+        vec![0x97]
+    );
+    gen_encode_test!(
+        Mnemonic::XRL,
+        [Operand::Accumulator, Operand::Register(5)],
+        vec![0x6d]
+    );
+    gen_encode_test!(
+        Mnemonic::ORL,
+        [Operand::Direct(Some(0x90)), Operand::Immediate(Some(0x01))],
+        vec![0x43, 0x90, 0x01]
+    );
+}
+
+#[test]
+fn should_encode_moves() {
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [Operand::Register(7), Operand::Immediate(Some(0xff))],
+        vec![0x7f, 0xff]
+    );
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [Operand::Direct(Some(0x30)), Operand::Direct(Some(0x40))],
+        vec![0x85, 0x40, 0x30]
+    );
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [Operand::DataPointer, Operand::Immediate(Some(0x1234))],
+        vec![0x90, 0x12, 0x34]
+    );
+    gen_encode_test!(
+        Mnemonic::MOVX,
+        [Operand::IndirectDataPointer, Operand::Accumulator],
+        vec![0xf0]
+    );
+    gen_encode_test!(
+        Mnemonic::MOVC,
+        [Operand::Accumulator, Operand::IndexedProgramCounter],
+        vec![0x83]
+    );
+}
+
+#[test]
+fn should_encode_bit_addresses() {
+    gen_encode_test!(
+        Mnemonic::SETB,
+        [Operand::BitOf(Some(0x90), 3)],
+        vec![0xd2, 0x93]
+    );
+    gen_encode_test!(
+        Mnemonic::CLR,
+        [Operand::BitOf(Some(0x21), 2)],
+        vec![0xc2, 0x0a]
+    );
+    gen_encode_test!(
+        Mnemonic::MOV,
+        [Operand::Carry, Operand::Direct(Some(0xd7))],
+        vec![0xa2, 0xd7]
+    );
+    gen_encode_test!(
+        Mnemonic::ANL,
+        [Operand::Carry, Operand::Complement(Some(0xe0), Some(7))],
+        vec![0xb0, 0xe7]
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x30)),
+        encode(Mnemonic::SETB, &[Operand::BitOf(Some(0x30), 0)], 0)
+    );
+}
+
+#[test]
+fn should_encode_relative_targets() {
+    assert_eq!(
+        Ok(vec![0x80, 0xfe]),
+        encode(Mnemonic::SJMP, &[Operand::Direct(Some(0x10))], 0x10)
+    );
+    assert_eq!(
+        Ok(vec![0xda, 0xfe]),
+        encode(
+            Mnemonic::DJNZ,
+            &[Operand::Register(2), Operand::Direct(Some(0x10))],
+            0x10
+        )
+    );
+    assert_eq!(
+        Ok(vec![0xb4, 0x0d, 0xfd]),
+        encode(
+            Mnemonic::CJNE,
+            &[
+                Operand::Accumulator,
+                Operand::Immediate(Some(0x0d)),
+                Operand::Direct(Some(0x10))
+            ],
+            0x10
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(0x100)),
+        encode(Mnemonic::SJMP, &[Operand::Direct(Some(0x102))], 0)
+    );
+}
+
+#[test]
+fn should_encode_page_relative_targets() {
+    assert_eq!(
+        Ok(vec![0xe1, 0x23]),
+        encode(Mnemonic::AJMP, &[Operand::Direct(Some(0x0723))], 0x0100)
+    );
+    assert_eq!(
+        Ok(vec![0x31, 0x00]),
+        encode(Mnemonic::ACALL, &[Operand::Direct(Some(0x0900))], 0x0800)
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfPage(0x0800)),
+        encode(Mnemonic::AJMP, &[Operand::Direct(Some(0x0800))], 0x0100)
+    );
+}
+
+#[test]
+fn should_encode_long_targets_big_endian() {
+    gen_encode_test!(
+        Mnemonic::LJMP,
+        [Operand::Direct(Some(0x1234))],
+        vec![0x02, 0x12, 0x34]
+    );
+    gen_encode_test!(
+        Mnemonic::LCALL,
+        [Operand::Direct(Some(0xabcd))],
+        vec![0x12, 0xab, 0xcd]
+    );
+}
+
+#[test]
+fn should_size_unresolved_operands_without_validating_them() {
+    gen_encode_test!(
+        Mnemonic::JB,
+        [Operand::BitOf(None, 0), Operand::Direct(None)],
+        vec![0x20, 0x00, 0x00]
+    );
+}
@@ -0,0 +1,91 @@
+pub mod instruction_set;
+pub mod parser;
+pub mod symbols;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+use crate::backends::mcs51::instruction_set::{encode, EncodeErr, Instruction, Operand};
+use crate::backends::BackendErr;
+use crate::preparser::types::Endianness;
+
+/// Mcs51Assembler implements the [InstructionSet] of the MCS-51.
+#[derive(Default)]
+pub struct Mcs51Assembler;
+
+impl Mcs51Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl InstructionSet for Mcs51Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// The size of an MCS-51 instruction depends only on the form of its
+    /// operands, allowing it to be sized with every value left unresolved.
+    fn size_of(&self, _: &SymbolTable, instruction: &Instruction) -> usize {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|_| Ok::<_, ()>(None)))
+            .collect::<Result<Vec<_>, ()>>()
+            .unwrap_or_default();
+
+        encode(instruction.mnemonic, &operands, 0)
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_value(symbol_table, v).map(Some)))
+            .collect::<Result<Vec<_>, BackendErr>>()?;
+
+        encode(instruction.mnemonic, &operands, position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                BackendErr::BranchOutOfRange(target(instruction), offset)
+            }
+            EncodeErr::OutOfPage(address) => BackendErr::Unspecified(format!(
+                "{:?} target {} at {:#06x} is outside of the 2K page of {:#06x}",
+                instruction.mnemonic,
+                target(instruction),
+                address,
+                position + 2
+            )),
+        })
+    }
+
+    fn endianness(&self) -> Endianness {
+        Endianness::Big
+    }
+}
+
+/// Returns the name of a jump or branch target, being its last operand.
+fn target(instruction: &Instruction) -> String {
+    match instruction.operands.last() {
+        Some(Operand::Direct(Value::Reference(id))) => id.clone(),
+        operand => format!("{:?}", operand),
+    }
+}
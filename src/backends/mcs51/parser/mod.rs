@@ -0,0 +1,161 @@
+use crate::backends::mcs51::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mcs51::instruction_set::{Instruction, Operand, Value};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches any operand. Registers are tried ahead of addresses so that a
+/// register, such as `a` or `r0`, isn't read as a reference to a symbol of
+/// the same name.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(expect_character('#'), signed_value()))
+        .map(Operand::Immediate)
+        .or(|| right(join(expect_character('@'), indirect())))
+        .or(|| {
+            right(join(expect_character('/'), bit()))
+                .map(|(value, bit)| Operand::Complement(value, bit))
+        })
+        .or(|| register())
+        .or(|| {
+            bit().map(|(value, bit)| match bit {
+                Some(bit) => Operand::BitOf(value, bit),
+                None => Operand::Direct(value),
+            })
+        })
+}
+
+fn register<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    name()
+        .map(|name| keyword(&name))
+        .predicate(|register| register.is_some())
+        .map(|register| register.unwrap())
+}
+
+fn keyword(name: &str) -> Option<Operand<Value>> {
+    let register = match name.to_lowercase().as_str() {
+        "a" => Operand::Accumulator,
+        "ab" => Operand::AccumulatorB,
+        "c" => Operand::Carry,
+        "dptr" => Operand::DataPointer,
+        name => match name.strip_prefix('r')?.parse::<u8>() {
+            Ok(n @ 0..=7) if name.len() == 2 => Operand::Register(n),
+            _ => return None,
+        },
+    };
+
+    Some(register)
+}
+
+/// Matches the operand following an `@`, being `r0`, `r1`, `dptr`,
+/// `a+dptr` or `a+pc`.
+fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(
+        name(),
+        optional(right(join(
+            join(
+                zero_or_more(non_newline_whitespace()),
+                join(
+                    expect_character('+'),
+                    zero_or_more(non_newline_whitespace()),
+                ),
+            ),
+            name(),
+        ))),
+    )
+    .map(|(base, index)| {
+        let index = index.map(|index| index.to_lowercase());
+        match (base.to_lowercase().as_str(), index.as_deref()) {
+            ("r0", None) => Some(Operand::Indirect(0)),
+            ("r1", None) => Some(Operand::Indirect(1)),
+            ("dptr", None) => Some(Operand::IndirectDataPointer),
+            ("a", Some("dptr")) => Some(Operand::IndexedDataPointer),
+            ("a", Some("pc")) => Some(Operand::IndexedProgramCounter),
+            _ => None,
+        }
+    })
+    .predicate(|operand| operand.is_some())
+    .map(|operand| operand.unwrap())
+}
+
+/// Matches an address, optionally followed by the bit of the byte it
+/// addresses, such as `acc.7` or `0x20.3`.
+fn bit<'a>() -> impl parcel::Parser<'a, &'a [char], (Value, Option<u8>)> {
+    join(
+        value(),
+        optional(right(join(expect_character('.'), digit(10)))),
+    )
+    .map(|(value, bit)| {
+        (
+            value,
+            bit.and_then(|bit| bit.to_digit(10)).map(|bit| bit as u8),
+        )
+    })
+}
+
+/// Matches a value, allowing a leading `-` on literals for immediates such
+/// as `#-1`.
+#[allow(clippy::redundant_closure)]
+fn signed_value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    right(join(expect_character('-'), unsigned16()))
+        .map(|v| Value::Literal(-i64::from(v)))
+        .or(|| value())
+}
+
+#[allow(clippy::redundant_closure)]
+fn value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    unsigned16()
+        .map(|v| Value::Literal(i64::from(v)))
+        .or(|| name().map(Value::Reference))
+}
+
+/// Matches a name, allowing trailing digits for registers and special
+/// function registers such as `r7` and `tl0`.
+#[allow(clippy::redundant_closure)]
+fn name<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
+    join(alphabetic(), zero_or_more(alphabetic().or(|| digit(10))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
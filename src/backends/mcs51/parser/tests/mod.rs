@@ -0,0 +1,99 @@
+use crate::backends::mcs51::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mcs51::instruction_set::{Instruction, Operand, Value};
+use crate::backends::mcs51::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operands:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operands)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_instructions_without_operands() {
+    gen_inst_test!("  nop", Mnemonic::NOP, vec![]);
+    gen_inst_test!("RETI  ", Mnemonic::RETI, vec![]);
+}
+
+#[test]
+fn should_parse_registers_ahead_of_references() {
+    gen_inst_test!(
+        "mov a, r7",
+        Mnemonic::MOV,
+        vec![Operand::Accumulator, Operand::Register(7)]
+    );
+    gen_inst_test!("MUL AB", Mnemonic::MUL, vec![Operand::AccumulatorB]);
+    gen_inst_test!(
+        "mov dptr, #table",
+        Mnemonic::MOV,
+        vec![
+            Operand::DataPointer,
+            Operand::Immediate(Value::Reference("table".to_string()))
+        ]
+    );
+    gen_inst_test!(
+        "mov b, #-1",
+        Mnemonic::MOV,
+        vec![
+            Operand::Direct(Value::Reference("b".to_string())),
+            Operand::Immediate(Value::Literal(-1))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_indirect_operands() {
+    gen_inst_test!(
+        "mov @r1, a",
+        Mnemonic::MOV,
+        vec![Operand::Indirect(1), Operand::Accumulator]
+    );
+    gen_inst_test!(
+        "movx a, @dptr",
+        Mnemonic::MOVX,
+        vec![Operand::Accumulator, Operand::IndirectDataPointer]
+    );
+    gen_inst_test!(
+        "movc a, @a + dptr",
+        Mnemonic::MOVC,
+        vec![Operand::Accumulator, Operand::IndexedDataPointer]
+    );
+}
+
+#[test]
+fn should_parse_bit_operands() {
+    gen_inst_test!(
+        "setb p1.0",
+        Mnemonic::SETB,
+        vec![Operand::BitOf(Value::Reference("p1".to_string()), 0)]
+    );
+    gen_inst_test!(
+        "clr 0x20.3",
+        Mnemonic::CLR,
+        vec![Operand::BitOf(Value::Literal(0x20), 3)]
+    );
+    gen_inst_test!(
+        "orl c, /TF0",
+        Mnemonic::ORL,
+        vec![
+            Operand::Carry,
+            Operand::Complement(Value::Reference("TF0".to_string()), None)
+        ]
+    );
+    gen_inst_test!(
+        "jnb acc.7, loop",
+        Mnemonic::JNB,
+        vec![
+            Operand::BitOf(Value::Reference("acc".to_string()), 7),
+            Operand::Direct(Value::Reference("loop".to_string()))
+        ]
+    );
+}
@@ -0,0 +1,89 @@
+/// The special function registers of the 8051, by their address in the
+/// upper half of the direct address space.
+const SPECIAL_FUNCTION_REGISTERS: [(&str, u8); 21] = [
+    ("P0", 0x80),
+    ("SP", 0x81),
+    ("DPL", 0x82),
+    ("DPH", 0x83),
+    ("PCON", 0x87),
+    ("TCON", 0x88),
+    ("TMOD", 0x89),
+    ("TL0", 0x8a),
+    ("TL1", 0x8b),
+    ("TH0", 0x8c),
+    ("TH1", 0x8d),
+    ("P1", 0x90),
+    ("SCON", 0x98),
+    ("SBUF", 0x99),
+    ("P2", 0xa0),
+    ("IE", 0xa8),
+    ("P3", 0xb0),
+    ("IP", 0xb8),
+    ("PSW", 0xd0),
+    ("ACC", 0xe0),
+    ("B", 0xf0),
+];
+
+/// The named bits of the bit-addressable special function registers, by
+/// their bit address.
+const BITS: [(&str, u8); 43] = [
+    ("IT0", 0x88),
+    ("IE0", 0x89),
+    ("IT1", 0x8a),
+    ("IE1", 0x8b),
+    ("TR0", 0x8c),
+    ("TF0", 0x8d),
+    ("TR1", 0x8e),
+    ("TF1", 0x8f),
+    ("RI", 0x98),
+    ("TI", 0x99),
+    ("RB8", 0x9a),
+    ("TB8", 0x9b),
+    ("REN", 0x9c),
+    ("SM2", 0x9d),
+    ("SM1", 0x9e),
+    ("SM0", 0x9f),
+    ("EX0", 0xa8),
+    ("ET0", 0xa9),
+    ("EX1", 0xaa),
+    ("ET1", 0xab),
+    ("ES", 0xac),
+    ("EA", 0xaf),
+    ("RXD", 0xb0),
+    ("TXD", 0xb1),
+    ("INT0", 0xb2),
+    ("INT1", 0xb3),
+    ("T0", 0xb4),
+    ("T1", 0xb5),
+    ("WR", 0xb6),
+    ("RD", 0xb7),
+    ("PX0", 0xb8),
+    ("PT0", 0xb9),
+    ("PX1", 0xba),
+    ("PT1", 0xbb),
+    ("PS", 0xbc),
+    ("P", 0xd0),
+    ("F1", 0xd1),
+    ("OV", 0xd2),
+    ("RS0", 0xd3),
+    ("RS1", 0xd4),
+    ("F0", 0xd5),
+    ("AC", 0xd6),
+    ("CY", 0xd7),
+];
+
+/// Returns the special function register and bit names as symbols, in both
+/// upper and lower case, to be defined ahead of the source so that the
+/// source may redefine them.
+pub fn predefined() -> Vec<(String, u32)> {
+    SPECIAL_FUNCTION_REGISTERS
+        .iter()
+        .chain(BITS.iter())
+        .flat_map(|(name, address)| {
+            [
+                (name.to_string(), u32::from(*address)),
+                (name.to_lowercase(), u32::from(*address)),
+            ]
+        })
+        .collect()
+}
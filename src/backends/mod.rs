@@ -1,7 +1,22 @@
-use std::convert::TryFrom;
+use crate::backends::layout::AssembledOrigins;
+use crate::diagnostics::SourceLocation;
+use crate::{AssemblerOptions, AssemblerResult};
 
+pub mod avr;
+pub mod builtin;
+pub mod chip8;
+pub mod i8080;
+pub mod layout;
+pub mod mc6800;
+pub mod mc6809;
+pub mod mcs51;
 #[macro_use]
 pub mod mos6502;
+pub mod registry;
+pub mod rv32i;
+pub mod sm83;
+pub mod table;
+pub mod z80;
 
 /// Error type returned from backends.
 #[allow(dead_code)]
@@ -9,6 +24,9 @@ pub enum BackendErr {
     Parse(String),
     UndefinedReference(String),
     UndefinedInstruction(String),
+    AssertionFailed(SourceLocation, String),
+    UserError(SourceLocation, String),
+    BranchOutOfRange(String, isize),
     Unspecified(String),
 }
 
@@ -18,6 +36,13 @@ impl std::fmt::Display for BackendErr {
             Self::Parse(input) => input.clone(),
             Self::UndefinedReference(input) => format!("reference undefined: {}", input),
             Self::UndefinedInstruction(input) => input.clone(),
+            Self::AssertionFailed(location, message) => {
+                format!("{}: assertion failed: {}", location, message)
+            }
+            Self::UserError(location, message) => format!("{}: error: {}", location, message),
+            Self::BranchOutOfRange(label, offset) => {
+                format!("branch to {} out of range: {} bytes", label, offset)
+            }
             Self::Unspecified(input) => input.clone(),
         };
 
@@ -25,29 +50,19 @@ impl std::fmt::Display for BackendErr {
     }
 }
 
-/// Backend represents the backend targets currently supported by spasm.
-#[derive(Debug)]
-pub enum Backend {
-    Mos6502,
-}
-
-impl std::fmt::Display for Backend {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let output = match self {
-            Backend::Mos6502 => "mos6502".to_string(),
-        };
+/// Backend represents a target architecture that spasm can assemble against,
+/// identified by name. Backends beyond those built in can be added to a
+/// [Registry](registry::Registry), either directly or from an
+/// [InstructionSet](layout::InstructionSet).
+pub trait Backend {
+    /// Returns the name that the backend is selected by, such as `mos6502`.
+    fn name(&self) -> &str;
 
-        write!(f, "{}", output)
-    }
-}
-
-impl TryFrom<&str> for Backend {
-    type Error = String;
-
-    fn try_from(src: &str) -> Result<Self, Self::Error> {
-        match src {
-            "mos6502" => Ok(Backend::Mos6502),
-            _ => Err(format!("unknown backend: {}", &src)),
-        }
-    }
+    /// Assembles the source, applying any definitions and reporting
+    /// diagnostics as configured by the provided options.
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String>;
 }
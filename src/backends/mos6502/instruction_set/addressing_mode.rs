@@ -1,12 +1,133 @@
 use crate::addressing;
-use isa_mos6502::{
-    addressing_mode::{AddressingMode, AddressingModeType},
-    ByteSized,
-};
+use isa_mos6502::{addressing_mode::AddressingModeType, ByteSized};
 use std::fmt;
 
 pub type Label = String;
 
+/// AddressingMode represents the addressing modes of the 6502 family,
+/// extending the NMOS addressing modes modeled by isa_mos6502 with those
+/// introduced by its successors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressingMode {
+    Accumulator,
+    Implied,
+    Immediate(u8),
+    Absolute(u16),
+    ZeroPage(u8),
+    Relative(i8),
+    Indirect(u16),
+    AbsoluteIndexedWithX(u16),
+    AbsoluteIndexedWithY(u16),
+    ZeroPageIndexedWithX(u8),
+    ZeroPageIndexedWithY(u8),
+    XIndexedIndirect(u8),
+    IndirectYIndexed(u8),
+    /// `(zp)`, introduced by the 65C02.
+    ZeroPageIndirect(u8),
+    /// `(abs,X)`, introduced by the 65C02 for `jmp`.
+    AbsoluteIndexedIndirect(u16),
+    /// `zp,rel`, taken by the BBR and BBS bit branches.
+    ZeroPageRelative(u8, i8),
+    /// A 16-bit immediate, taken by the 65816 when the accumulator or index
+    /// registers are 16 bits wide.
+    ImmediateWord(u16),
+    /// `al`, a 24-bit address introduced by the 65816.
+    AbsoluteLong(u32),
+    /// `al,X`, introduced by the 65816.
+    AbsoluteLongIndexedWithX(u32),
+    /// `[dp]`, introduced by the 65816.
+    DirectPageIndirectLong(u8),
+    /// `[dp],Y`, introduced by the 65816.
+    DirectPageIndirectLongIndexedWithY(u8),
+    /// `[abs]`, introduced by the 65816 for `jml`.
+    AbsoluteIndirectLong(u16),
+    /// `sr,S`, introduced by the 65816.
+    StackRelative(u8),
+    /// `(sr,S),Y`, introduced by the 65816.
+    StackRelativeIndirectIndexedWithY(u8),
+    /// A 16-bit relative offset, taken by the 65816's `brl` and `per`.
+    RelativeLong(i16),
+    /// `srcbank,destbank`, taken by the 65816's `mvn` and `mvp` and encoded
+    /// with the destination bank first.
+    BlockMove(u8, u8),
+}
+
+impl AddressingMode {
+    /// Returns the equivalent isa_mos6502 addressing mode if the addressing
+    /// mode is supported by the NMOS 6502.
+    pub fn to_nmos(self) -> Option<isa_mos6502::addressing_mode::AddressingMode> {
+        use isa_mos6502::addressing_mode::AddressingMode as Nmos;
+
+        match self {
+            Self::Accumulator => Some(Nmos::Accumulator),
+            Self::Implied => Some(Nmos::Implied),
+            Self::Immediate(v) => Some(Nmos::Immediate(v)),
+            Self::Absolute(v) => Some(Nmos::Absolute(v)),
+            Self::ZeroPage(v) => Some(Nmos::ZeroPage(v)),
+            Self::Relative(v) => Some(Nmos::Relative(v)),
+            Self::Indirect(v) => Some(Nmos::Indirect(v)),
+            Self::AbsoluteIndexedWithX(v) => Some(Nmos::AbsoluteIndexedWithX(v)),
+            Self::AbsoluteIndexedWithY(v) => Some(Nmos::AbsoluteIndexedWithY(v)),
+            Self::ZeroPageIndexedWithX(v) => Some(Nmos::ZeroPageIndexedWithX(v)),
+            Self::ZeroPageIndexedWithY(v) => Some(Nmos::ZeroPageIndexedWithY(v)),
+            Self::XIndexedIndirect(v) => Some(Nmos::XIndexedIndirect(v)),
+            Self::IndirectYIndexed(v) => Some(Nmos::IndirectYIndexed(v)),
+            _ => None,
+        }
+    }
+}
+
+impl From<isa_mos6502::addressing_mode::AddressingMode> for AddressingMode {
+    fn from(src: isa_mos6502::addressing_mode::AddressingMode) -> Self {
+        use isa_mos6502::addressing_mode::AddressingMode as Nmos;
+
+        match src {
+            Nmos::Accumulator => Self::Accumulator,
+            Nmos::Implied => Self::Implied,
+            Nmos::Immediate(v) => Self::Immediate(v),
+            Nmos::Absolute(v) => Self::Absolute(v),
+            Nmos::ZeroPage(v) => Self::ZeroPage(v),
+            Nmos::Relative(v) => Self::Relative(v),
+            Nmos::Indirect(v) => Self::Indirect(v),
+            Nmos::AbsoluteIndexedWithX(v) => Self::AbsoluteIndexedWithX(v),
+            Nmos::AbsoluteIndexedWithY(v) => Self::AbsoluteIndexedWithY(v),
+            Nmos::ZeroPageIndexedWithX(v) => Self::ZeroPageIndexedWithX(v),
+            Nmos::ZeroPageIndexedWithY(v) => Self::ZeroPageIndexedWithY(v),
+            Nmos::XIndexedIndirect(v) => Self::XIndexedIndirect(v),
+            Nmos::IndirectYIndexed(v) => Self::IndirectYIndexed(v),
+        }
+    }
+}
+
+impl From<AddressingMode> for Vec<u8> {
+    fn from(src: AddressingMode) -> Self {
+        match src {
+            AddressingMode::ZeroPageIndirect(v) => vec![v],
+            AddressingMode::AbsoluteIndexedIndirect(v) => v.to_le_bytes().to_vec(),
+            AddressingMode::ZeroPageRelative(zp, offset) => vec![zp, offset as u8],
+            AddressingMode::ImmediateWord(v) | AddressingMode::AbsoluteIndirectLong(v) => {
+                v.to_le_bytes().to_vec()
+            }
+            AddressingMode::AbsoluteLong(v) | AddressingMode::AbsoluteLongIndexedWithX(v) => {
+                v.to_le_bytes()[..3].to_vec()
+            }
+            AddressingMode::DirectPageIndirectLong(v)
+            | AddressingMode::DirectPageIndirectLongIndexedWithY(v)
+            | AddressingMode::StackRelative(v)
+            | AddressingMode::StackRelativeIndirectIndexedWithY(v) => vec![v],
+            AddressingMode::RelativeLong(offset) => offset.to_le_bytes().to_vec(),
+            AddressingMode::BlockMove(src, dest) => vec![dest, src],
+            am => am.to_nmos().map(Vec::<u8>::from).unwrap_or_default(),
+        }
+    }
+}
+
+impl ByteSized for AddressingMode {
+    fn byte_size(&self) -> usize {
+        Vec::<u8>::from(*self).len()
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Symbol {
     pub address_mode_type: AddressingModeType,
@@ -30,17 +151,76 @@ impl fmt::Display for Symbol {
 
 impl addressing::SizeOf for Symbol {
     fn size_of(&self) -> usize {
-        1
+        match self.address_mode_type {
+            AddressingModeType::AbsoluteIndexedWithX
+            | AddressingModeType::AbsoluteIndexedWithY
+            | AddressingModeType::Indirect => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// ExtendedAddressingModeType represents the addressing modes introduced by
+/// the 65816 that may take a symbolic operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtendedAddressingModeType {
+    DirectPageIndirectLong,
+    DirectPageIndirectLongIndexedWithY,
+    StackRelative,
+    StackRelativeIndirectIndexedWithY,
+    /// An immediate of the bank byte, bits 16-23, of a symbol, `#^sym`.
+    BankByte,
+}
+
+/// ExtendedSymbol represents a symbolic operand to one of the addressing
+/// modes introduced by the 65816.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExtendedSymbol {
+    pub address_mode_type: ExtendedAddressingModeType,
+    pub symbol: String,
+}
+
+impl ExtendedSymbol {
+    pub fn new(amt: ExtendedAddressingModeType, symbol: String) -> Self {
+        Self {
+            address_mode_type: amt,
+            symbol,
+        }
+    }
+}
+
+/// ZeroPageOperand represents the zero page operand of a bit branch, which may
+/// be either a literal address or a symbol.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ZeroPageOperand {
+    Address(u8),
+    Symbol(String),
+}
+
+/// BitBranch represents the zero page and branch target operands of a BBR or
+/// BBS instruction.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BitBranch {
+    pub zeropage: ZeroPageOperand,
+    pub target: Label,
+}
+
+impl BitBranch {
+    pub fn new(zeropage: ZeroPageOperand, target: Label) -> Self {
+        Self { zeropage, target }
     }
 }
 
 /// AddressingModeOrReference handles for parsing either an explicit address mode or a
-/// label mapping.
+/// label mapping. References are sized at their widest encoding, as zero page
+/// addressing is only selected once a reference resolves.
 #[derive(Clone, PartialEq, Debug)]
 pub enum AddressingModeOrReference {
     AddressingMode(AddressingMode),
     Label(Label),
     Symbol(Symbol),
+    ExtendedSymbol(ExtendedSymbol),
+    BitBranch(BitBranch),
 }
 
 impl addressing::SizeOf for AddressingModeOrReference {
@@ -48,7 +228,9 @@ impl addressing::SizeOf for AddressingModeOrReference {
         match self {
             Self::AddressingMode(am) => am.byte_size(),
             Self::Label(_) => 2,
-            Self::Symbol(_) => 1,
+            Self::Symbol(s) => s.size_of(),
+            Self::ExtendedSymbol(_) => 1,
+            Self::BitBranch(_) => 2,
         }
     }
 }
@@ -0,0 +1,155 @@
+use super::addressing_mode::AddressingMode;
+use super::mnemonic::Mnemonic;
+use super::Cpu;
+
+/// Cycles represents the number of clock cycles an instruction takes to
+/// execute, along with any conditions that add a further cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycles {
+    /// The cycles taken with no penalties applied.
+    pub base: usize,
+    /// A further cycle is taken when an indexed read, or a taken branch,
+    /// crosses a page boundary.
+    pub page_crossing: bool,
+    /// A further cycle is taken when a branch is taken.
+    pub branch_taken: bool,
+}
+
+impl Cycles {
+    pub fn new(base: usize) -> Self {
+        Self {
+            base,
+            page_crossing: false,
+            branch_taken: false,
+        }
+    }
+
+    fn with_page_crossing(mut self) -> Self {
+        self.page_crossing = true;
+        self
+    }
+
+    fn with_branch_taken(mut self) -> Self {
+        self.branch_taken = true;
+        self
+    }
+
+    /// Returns the cycles taken with every applicable penalty applied.
+    pub fn worst_case(&self) -> usize {
+        self.base + usize::from(self.page_crossing) + usize::from(self.branch_taken)
+    }
+}
+
+/// AccessType groups mnemonics by how they access their operand, which
+/// determines their timing across addressing modes.
+enum AccessType {
+    Read,
+    Write,
+    ReadModifyWrite,
+}
+
+fn access_type(mnemonic: Mnemonic) -> Option<AccessType> {
+    use Mnemonic::*;
+    match mnemonic {
+        ADC | AND | BIT | CMP | CPX | CPY | EOR | LDA | LDX | LDY | ORA | SBC | LAX | LAS | NOP
+        | ANC | ALR | ARR | ANE | SBX => Some(AccessType::Read),
+        STA | STX | STY | STZ | SAX | SHA | SHX | SHY | TAS => Some(AccessType::Write),
+        ASL | LSR | ROL | ROR | INC | DEC | SLO | RLA | SRE | RRA | DCP | ISC | TRB | TSB => {
+            Some(AccessType::ReadModifyWrite)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the cycles taken by a mnemonic and addressing mode pair on the
+/// provided cpu. The timing of the 65816 varies with its register widths and
+/// emulation mode and so isn't tracked.
+pub fn cycles(cpu: Cpu, mnemonic: Mnemonic, am: AddressingMode) -> Option<Cycles> {
+    use AddressingMode as AM;
+    use Mnemonic as M;
+
+    if cpu == Cpu::Wdc65816 {
+        return None;
+    }
+    let cmos = matches!(cpu, Cpu::Cmos65c02 | Cpu::Wdc65c02);
+
+    match (mnemonic, am) {
+        // the 65C02 corrects the page boundary bug of `jmp (abs)` at the cost
+        // of a cycle.
+        (M::JMP, AM::Absolute(_)) => Some(Cycles::new(3)),
+        (M::JMP, AM::Indirect(_)) if cmos => Some(Cycles::new(6)),
+        (M::JMP, AM::Indirect(_)) => Some(Cycles::new(5)),
+        (M::JMP, AM::AbsoluteIndexedIndirect(_)) => Some(Cycles::new(6)),
+        (M::JSR, AM::Absolute(_)) => Some(Cycles::new(6)),
+        (M::RTS | M::RTI, AM::Implied) => Some(Cycles::new(6)),
+        (M::BRK, AM::Implied) => Some(Cycles::new(7)),
+        (M::PHA | M::PHP | M::PHX | M::PHY, AM::Implied) => Some(Cycles::new(3)),
+        (M::PLA | M::PLP | M::PLX | M::PLY, AM::Implied) => Some(Cycles::new(4)),
+        (M::STP | M::WAI, AM::Implied) => Some(Cycles::new(3)),
+        (M::BRA, AM::Relative(_)) => Some(Cycles::new(3).with_page_crossing()),
+        (m, AM::Relative(_)) if m.is_branch() => {
+            Some(Cycles::new(2).with_branch_taken().with_page_crossing())
+        }
+        (m, AM::ZeroPageRelative(_, _)) if m.is_bit_branch() => {
+            Some(Cycles::new(5).with_branch_taken().with_page_crossing())
+        }
+        (m, AM::ZeroPage(_)) if m.bit_opcode().is_some() => Some(Cycles::new(5)),
+        (_, AM::Implied | AM::Accumulator) => Some(Cycles::new(2)),
+        (m, am) => access_type(m).and_then(|access| access_cycles(cmos, m, access, am)),
+    }
+}
+
+/// Returns the cycles taken by an instruction that reads, writes or modifies
+/// its operand in memory.
+fn access_cycles(
+    cmos: bool,
+    mnemonic: Mnemonic,
+    access: AccessType,
+    am: AddressingMode,
+) -> Option<Cycles> {
+    use AddressingMode as AM;
+
+    let cycles = match (access, am) {
+        (AccessType::Read, AM::Immediate(_)) => Cycles::new(2),
+        (AccessType::Read | AccessType::Write, AM::ZeroPage(_)) => Cycles::new(3),
+        (
+            AccessType::Read | AccessType::Write,
+            AM::ZeroPageIndexedWithX(_) | AM::ZeroPageIndexedWithY(_) | AM::Absolute(_),
+        ) => Cycles::new(4),
+        (AccessType::Read, AM::AbsoluteIndexedWithX(_) | AM::AbsoluteIndexedWithY(_)) => {
+            Cycles::new(4).with_page_crossing()
+        }
+        (AccessType::Write, AM::AbsoluteIndexedWithX(_) | AM::AbsoluteIndexedWithY(_)) => {
+            Cycles::new(5)
+        }
+        (AccessType::Read | AccessType::Write, AM::XIndexedIndirect(_)) => Cycles::new(6),
+        (AccessType::Read, AM::IndirectYIndexed(_)) => Cycles::new(5).with_page_crossing(),
+        (AccessType::Write, AM::IndirectYIndexed(_)) => Cycles::new(6),
+        (AccessType::Read | AccessType::Write, AM::ZeroPageIndirect(_)) => Cycles::new(5),
+        (AccessType::ReadModifyWrite, AM::ZeroPage(_)) => Cycles::new(5),
+        (AccessType::ReadModifyWrite, AM::ZeroPageIndexedWithX(_) | AM::Absolute(_)) => {
+            Cycles::new(6)
+        }
+        // the 65C02 only takes the extra cycle of an indexed shift or rotate
+        // when the index crosses a page.
+        (AccessType::ReadModifyWrite, AM::AbsoluteIndexedWithX(_))
+            if cmos
+                && matches!(
+                    mnemonic,
+                    Mnemonic::ASL | Mnemonic::LSR | Mnemonic::ROL | Mnemonic::ROR
+                ) =>
+        {
+            Cycles::new(6).with_page_crossing()
+        }
+        (
+            AccessType::ReadModifyWrite,
+            AM::AbsoluteIndexedWithX(_) | AM::AbsoluteIndexedWithY(_),
+        ) => Cycles::new(7),
+        (AccessType::ReadModifyWrite, AM::XIndexedIndirect(_) | AM::IndirectYIndexed(_)) => {
+            Cycles::new(8)
+        }
+        _ => return None,
+    };
+
+    Some(cycles)
+}
@@ -0,0 +1,141 @@
+use crate::preparser::Register;
+use std::convert::TryFrom;
+
+macro_rules! mnemonics {
+    (nmos: [$($nmos:ident),*], extended: [$($extended:ident),*]) => {
+        /// Mnemonic represents the mnemonics of the 6502 family, extending the
+        /// NMOS mnemonics modeled by isa_mos6502 with those introduced by its
+        /// successors. Whether a mnemonic is valid is left to the target cpu.
+        #[allow(clippy::upper_case_acronyms)]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Mnemonic {
+            $($nmos,)*
+            $($extended,)*
+        }
+
+        impl Mnemonic {
+            /// Returns the equivalent isa_mos6502 mnemonic if the mnemonic is
+            /// part of the NMOS instruction set.
+            pub fn to_nmos(self) -> Option<isa_mos6502::mnemonic::Mnemonic> {
+                match self {
+                    $(Self::$nmos => Some(isa_mos6502::mnemonic::Mnemonic::$nmos),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<isa_mos6502::mnemonic::Mnemonic> for Mnemonic {
+            fn from(src: isa_mos6502::mnemonic::Mnemonic) -> Self {
+                match src {
+                    $(isa_mos6502::mnemonic::Mnemonic::$nmos => Self::$nmos,)*
+                }
+            }
+        }
+
+        impl TryFrom<&str> for Mnemonic {
+            type Error = String;
+
+            fn try_from(src: &str) -> Result<Self, Self::Error> {
+                match src.to_uppercase().as_str() {
+                    $(stringify!($nmos) => Ok(Self::$nmos),)*
+                    $(stringify!($extended) => Ok(Self::$extended),)*
+                    _ => Err(format!("unknown mnemonic: {}", src)),
+                }
+            }
+        }
+    };
+}
+
+mnemonics!(
+    nmos: [
+        ADC, AND, ASL, BCC, BCS, BEQ, BIT, BMI, BNE, BPL, BRK, BVC, BVS, CLC, CLD, CLI, CLV, CMP,
+        CPX, CPY, DEC, DEX, DEY, EOR, INC, INX, INY, JMP, JSR, LDA, LDX, LDY, LSR, NOP, ORA, PHA,
+        PHP, PLA, PLP, ROL, ROR, RTI, RTS, SBC, SEC, SED, SEI, STA, STX, STY, TAX, TAY, TSX, TXA,
+        TXS, TYA
+    ],
+    extended: [
+        BRA, PHX, PHY, PLX, PLY, STZ, TRB, TSB, STP, WAI, RMB0, RMB1, RMB2, RMB3, RMB4, RMB5,
+        RMB6, RMB7, SMB0, SMB1, SMB2, SMB3, SMB4, SMB5, SMB6, SMB7, BBR0, BBR1, BBR2, BBR3, BBR4,
+        BBR5, BBR6, BBR7, BBS0, BBS1, BBS2, BBS3, BBS4, BBS5, BBS6, BBS7, LAX, SAX, DCP, ISC, SLO,
+        RLA, SRE, RRA, ANC, ALR, ARR, SBX, ANE, LAS, SHA, SHX, SHY, TAS, BRL, COP, JML, JSL, MVN,
+        MVP, PEA, PEI, PER, PHB, PHD, PHK, PLB, PLD, REP, RTL, SEP, TCD, TCS, TDC, TSC, TXY, TYX,
+        WDM, XBA, XCE
+    ]
+);
+
+impl Mnemonic {
+    /// Returns true if the mnemonic is a branch taking a relative operand.
+    pub fn is_branch(self) -> bool {
+        matches!(
+            self,
+            Self::BCC
+                | Self::BCS
+                | Self::BEQ
+                | Self::BNE
+                | Self::BMI
+                | Self::BPL
+                | Self::BVC
+                | Self::BVS
+                | Self::BRA
+        )
+    }
+
+    /// Returns true if the mnemonic takes a 16-bit relative operand, as the
+    /// 65816's `brl` and `per` do.
+    pub fn is_long_branch(self) -> bool {
+        matches!(self, Self::BRL | Self::PER)
+    }
+
+    /// Returns the register whose width determines the size of the
+    /// mnemonic's immediate operand on the 65816, if any.
+    pub fn immediate_register(self) -> Option<Register> {
+        use Mnemonic::*;
+        match self {
+            ADC | AND | BIT | CMP | EOR | LDA | ORA | SBC => Some(Register::Accumulator),
+            CPX | CPY | LDX | LDY => Some(Register::Index),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the mnemonic is one of the BBR or BBS bit branches,
+    /// taking both a zero page and relative operand.
+    pub fn is_bit_branch(self) -> bool {
+        matches!(self.bit_base_opcode(), Some(0x0f) | Some(0x8f))
+    }
+
+    /// Returns the bit that a RMB, SMB, BBR or BBS instruction operates on.
+    fn bit_index(self) -> Option<u8> {
+        use Mnemonic::*;
+        match self {
+            RMB0 | SMB0 | BBR0 | BBS0 => Some(0),
+            RMB1 | SMB1 | BBR1 | BBS1 => Some(1),
+            RMB2 | SMB2 | BBR2 | BBS2 => Some(2),
+            RMB3 | SMB3 | BBR3 | BBS3 => Some(3),
+            RMB4 | SMB4 | BBR4 | BBS4 => Some(4),
+            RMB5 | SMB5 | BBR5 | BBS5 => Some(5),
+            RMB6 | SMB6 | BBR6 | BBS6 => Some(6),
+            RMB7 | SMB7 | BBR7 | BBS7 => Some(7),
+            _ => None,
+        }
+    }
+
+    /// Returns the opcode of bit 0 of a RMB, SMB, BBR or BBS instruction.
+    fn bit_base_opcode(self) -> Option<u8> {
+        use Mnemonic::*;
+        match self {
+            RMB0 | RMB1 | RMB2 | RMB3 | RMB4 | RMB5 | RMB6 | RMB7 => Some(0x07),
+            SMB0 | SMB1 | SMB2 | SMB3 | SMB4 | SMB5 | SMB6 | SMB7 => Some(0x87),
+            BBR0 | BBR1 | BBR2 | BBR3 | BBR4 | BBR5 | BBR6 | BBR7 => Some(0x0f),
+            BBS0 | BBS1 | BBS2 | BBS3 | BBS4 | BBS5 | BBS6 | BBS7 => Some(0x8f),
+            _ => None,
+        }
+    }
+
+    /// Returns the opcode of a RMB, SMB, BBR or BBS instruction, with each
+    /// bit's opcode offset by 0x10 from the last.
+    pub fn bit_opcode(self) -> Option<u8> {
+        self.bit_base_opcode()
+            .zip(self.bit_index())
+            .map(|(base, bit)| base + (bit << 4))
+    }
+}
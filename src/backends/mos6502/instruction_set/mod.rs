@@ -1,8 +1,12 @@
 pub mod addressing_mode;
+pub mod cycles;
+pub mod mnemonic;
 use crate::addressing;
 use crate::Emitter;
-use addressing_mode::AddressingModeOrReference;
-use isa_mos6502::{addressing_mode::AddressingMode, mnemonic::Mnemonic};
+use addressing_mode::{AddressingMode, AddressingModeOrReference, ExtendedAddressingModeType};
+use isa_mos6502::addressing_mode::AddressingModeType;
+use mnemonic::Mnemonic;
+use std::convert::TryFrom;
 use std::fmt;
 
 #[cfg(test)]
@@ -13,51 +17,452 @@ pub type OpCode = u8;
 
 impl addressing::SizeOf for Mnemonic {
     fn size_of(&self) -> usize {
-        use isa_mos6502::ByteSized;
-        self.byte_size()
+        1
     }
 }
+
+/// Cpu represents the members of the 6502 family that the backend can target,
+/// each supporting a superset of the documented NMOS instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Cpu {
+    /// The original NMOS 6502.
+    #[default]
+    Nmos6502,
+    /// The NMOS 6502, additionally accepting its undocumented opcodes.
+    Nmos6502Undocumented,
+    /// The CMOS 65C02.
+    Cmos65c02,
+    /// The WDC W65C02S, adding the Rockwell bit instructions alongside `stp`
+    /// and `wai` to the 65C02.
+    Wdc65c02,
+    /// The WDC W65C816S, adding 24-bit addressing, stack relative addressing
+    /// and 16-bit registers to the 65C02.
+    Wdc65816,
+}
+
+impl Cpu {
+    /// Returns the opcode of a mnemonic and addressing mode pair if the cpu
+    /// supports it.
+    pub fn opcode(self, mnemonic: Mnemonic, am: AddressingMode) -> Option<OpCode> {
+        let nmos = mnemonic
+            .to_nmos()
+            .zip(am.to_nmos())
+            .and_then(|(m, am)| isa_mos6502::InstructionVariant::new(m, am).ok())
+            .and_then(|iv| Vec::<u8>::from(iv).first().copied());
+
+        match self {
+            Self::Nmos6502 => nmos,
+            Self::Nmos6502Undocumented => nmos.or_else(|| undocumented_opcode(mnemonic, am)),
+            Self::Cmos65c02 => nmos.or_else(|| cmos_opcode(mnemonic, am)),
+            Self::Wdc65c02 => nmos
+                .or_else(|| cmos_opcode(mnemonic, am))
+                .or_else(|| wdc_opcode(mnemonic, am)),
+            Self::Wdc65816 => match am {
+                // 16-bit immediates share the opcode of their 8-bit form.
+                AddressingMode::ImmediateWord(_) => mnemonic
+                    .immediate_register()
+                    .and_then(|_| self.opcode(mnemonic, AddressingMode::Immediate(0))),
+                _ => nmos
+                    .or_else(|| cmos_opcode(mnemonic, am))
+                    .or_else(|| w65816_opcode(mnemonic, am)),
+            },
+        }
+    }
+}
+
+impl Cpu {
+    /// Returns true if code targeting the other cpu runs on this cpu, bounding
+    /// the processors that a `.cpu` directive may select to the family of the
+    /// backend.
+    pub fn runs(self, other: Cpu) -> bool {
+        match self {
+            Self::Nmos6502 | Self::Nmos6502Undocumented => {
+                matches!(other, Self::Nmos6502 | Self::Nmos6502Undocumented)
+            }
+            Self::Cmos65c02 => matches!(other, Self::Nmos6502 | Self::Cmos65c02),
+            Self::Wdc65c02 => matches!(other, Self::Nmos6502 | Self::Cmos65c02 | Self::Wdc65c02),
+            Self::Wdc65816 => matches!(other, Self::Nmos6502 | Self::Cmos65c02 | Self::Wdc65816),
+        }
+    }
+}
+
+impl TryFrom<&str> for Cpu {
+    type Error = String;
+
+    fn try_from(src: &str) -> Result<Self, Self::Error> {
+        match src.to_lowercase().as_str() {
+            "6502" | "mos6502" => Ok(Self::Nmos6502),
+            "6502x" => Ok(Self::Nmos6502Undocumented),
+            "65c02" => Ok(Self::Cmos65c02),
+            "w65c02" => Ok(Self::Wdc65c02),
+            "65816" => Ok(Self::Wdc65816),
+            _ => Err(format!("unknown cpu: {}", src)),
+        }
+    }
+}
+
+/// Returns the undocumented opcodes of the NMOS 6502. Where an instruction is
+/// available under more than one opcode, the most commonly used is returned.
+fn undocumented_opcode(mnemonic: Mnemonic, am: AddressingMode) -> Option<OpCode> {
+    use AddressingMode as AM;
+    use Mnemonic as M;
+
+    // the read-modify-write combinations share a layout, offset by a base
+    // opcode per mnemonic.
+    let rmw_base = match mnemonic {
+        M::SLO => Some(0x00),
+        M::RLA => Some(0x20),
+        M::SRE => Some(0x40),
+        M::RRA => Some(0x60),
+        M::DCP => Some(0xc0),
+        M::ISC => Some(0xe0),
+        _ => None,
+    };
+
+    if let Some(base) = rmw_base {
+        return match am {
+            AM::XIndexedIndirect(_) => Some(base + 0x03),
+            AM::ZeroPage(_) => Some(base + 0x07),
+            AM::Absolute(_) => Some(base + 0x0f),
+            AM::IndirectYIndexed(_) => Some(base + 0x13),
+            AM::ZeroPageIndexedWithX(_) => Some(base + 0x17),
+            AM::AbsoluteIndexedWithY(_) => Some(base + 0x1b),
+            AM::AbsoluteIndexedWithX(_) => Some(base + 0x1f),
+            _ => None,
+        };
+    }
+
+    match (mnemonic, am) {
+        (M::LAX, AM::XIndexedIndirect(_)) => Some(0xa3),
+        (M::LAX, AM::ZeroPage(_)) => Some(0xa7),
+        (M::LAX, AM::Immediate(_)) => Some(0xab),
+        (M::LAX, AM::Absolute(_)) => Some(0xaf),
+        (M::LAX, AM::IndirectYIndexed(_)) => Some(0xb3),
+        (M::LAX, AM::ZeroPageIndexedWithY(_)) => Some(0xb7),
+        (M::LAX, AM::AbsoluteIndexedWithY(_)) => Some(0xbf),
+        (M::SAX, AM::XIndexedIndirect(_)) => Some(0x83),
+        (M::SAX, AM::ZeroPage(_)) => Some(0x87),
+        (M::SAX, AM::Absolute(_)) => Some(0x8f),
+        (M::SAX, AM::ZeroPageIndexedWithY(_)) => Some(0x97),
+        (M::ANC, AM::Immediate(_)) => Some(0x0b),
+        (M::ALR, AM::Immediate(_)) => Some(0x4b),
+        (M::ARR, AM::Immediate(_)) => Some(0x6b),
+        (M::ANE, AM::Immediate(_)) => Some(0x8b),
+        (M::SBX, AM::Immediate(_)) => Some(0xcb),
+        (M::SHA, AM::IndirectYIndexed(_)) => Some(0x93),
+        (M::SHA, AM::AbsoluteIndexedWithY(_)) => Some(0x9f),
+        (M::TAS, AM::AbsoluteIndexedWithY(_)) => Some(0x9b),
+        (M::SHY, AM::AbsoluteIndexedWithX(_)) => Some(0x9c),
+        (M::SHX, AM::AbsoluteIndexedWithY(_)) => Some(0x9e),
+        (M::LAS, AM::AbsoluteIndexedWithY(_)) => Some(0xbb),
+        (M::NOP, AM::ZeroPage(_)) => Some(0x04),
+        (M::NOP, AM::Absolute(_)) => Some(0x0c),
+        (M::NOP, AM::ZeroPageIndexedWithX(_)) => Some(0x14),
+        (M::NOP, AM::AbsoluteIndexedWithX(_)) => Some(0x1c),
+        (M::NOP, AM::Immediate(_)) => Some(0x80),
+        _ => None,
+    }
+}
+
+/// Returns the opcodes introduced by the 65C02.
+fn cmos_opcode(mnemonic: Mnemonic, am: AddressingMode) -> Option<OpCode> {
+    use AddressingMode as AM;
+    use Mnemonic as M;
+
+    match (mnemonic, am) {
+        (M::BRA, AM::Relative(_)) => Some(0x80),
+        (M::PHX, AM::Implied) => Some(0xda),
+        (M::PHY, AM::Implied) => Some(0x5a),
+        (M::PLX, AM::Implied) => Some(0xfa),
+        (M::PLY, AM::Implied) => Some(0x7a),
+        (M::STZ, AM::ZeroPage(_)) => Some(0x64),
+        (M::STZ, AM::ZeroPageIndexedWithX(_)) => Some(0x74),
+        (M::STZ, AM::Absolute(_)) => Some(0x9c),
+        (M::STZ, AM::AbsoluteIndexedWithX(_)) => Some(0x9e),
+        (M::TRB, AM::ZeroPage(_)) => Some(0x14),
+        (M::TRB, AM::Absolute(_)) => Some(0x1c),
+        (M::TSB, AM::ZeroPage(_)) => Some(0x04),
+        (M::TSB, AM::Absolute(_)) => Some(0x0c),
+        (M::INC, AM::Accumulator) => Some(0x1a),
+        (M::DEC, AM::Accumulator) => Some(0x3a),
+        (M::BIT, AM::Immediate(_)) => Some(0x89),
+        (M::BIT, AM::ZeroPageIndexedWithX(_)) => Some(0x34),
+        (M::BIT, AM::AbsoluteIndexedWithX(_)) => Some(0x3c),
+        (M::ORA, AM::ZeroPageIndirect(_)) => Some(0x12),
+        (M::AND, AM::ZeroPageIndirect(_)) => Some(0x32),
+        (M::EOR, AM::ZeroPageIndirect(_)) => Some(0x52),
+        (M::ADC, AM::ZeroPageIndirect(_)) => Some(0x72),
+        (M::STA, AM::ZeroPageIndirect(_)) => Some(0x92),
+        (M::LDA, AM::ZeroPageIndirect(_)) => Some(0xb2),
+        (M::CMP, AM::ZeroPageIndirect(_)) => Some(0xd2),
+        (M::SBC, AM::ZeroPageIndirect(_)) => Some(0xf2),
+        (M::JMP, AM::AbsoluteIndexedIndirect(_)) => Some(0x7c),
+        _ => None,
+    }
+}
+
+/// Returns the opcodes introduced by the W65C02S, including the Rockwell bit
+/// instructions.
+fn wdc_opcode(mnemonic: Mnemonic, am: AddressingMode) -> Option<OpCode> {
+    use AddressingMode as AM;
+    use Mnemonic as M;
+
+    match (mnemonic, am) {
+        (M::STP, AM::Implied) => Some(0xdb),
+        (M::WAI, AM::Implied) => Some(0xcb),
+        (m, AM::ZeroPageRelative(_, _)) if m.is_bit_branch() => m.bit_opcode(),
+        (m, AM::ZeroPage(_)) if !m.is_bit_branch() => m.bit_opcode(),
+        _ => None,
+    }
+}
+
+/// Returns the opcodes introduced by the W65C816S.
+fn w65816_opcode(mnemonic: Mnemonic, am: AddressingMode) -> Option<OpCode> {
+    use AddressingMode as AM;
+    use Mnemonic as M;
+
+    // the long and stack relative modes of the accumulator instructions share
+    // a layout, offset by a base opcode per mnemonic.
+    let accumulator_base = match mnemonic {
+        M::ORA => Some(0x00),
+        M::AND => Some(0x20),
+        M::EOR => Some(0x40),
+        M::ADC => Some(0x60),
+        M::STA => Some(0x80),
+        M::LDA => Some(0xa0),
+        M::CMP => Some(0xc0),
+        M::SBC => Some(0xe0),
+        _ => None,
+    };
+
+    if let Some(base) = accumulator_base {
+        return match am {
+            AM::StackRelative(_) => Some(base + 0x03),
+            AM::DirectPageIndirectLong(_) => Some(base + 0x07),
+            AM::AbsoluteLong(_) => Some(base + 0x0f),
+            AM::StackRelativeIndirectIndexedWithY(_) => Some(base + 0x13),
+            AM::DirectPageIndirectLongIndexedWithY(_) => Some(base + 0x17),
+            AM::AbsoluteLongIndexedWithX(_) => Some(base + 0x1f),
+            _ => None,
+        };
+    }
+
+    match (mnemonic, am) {
+        (M::BRL, AM::RelativeLong(_)) => Some(0x82),
+        (M::PER, AM::RelativeLong(_)) => Some(0x62),
+        (M::COP, AM::Immediate(_)) => Some(0x02),
+        (M::WDM, AM::Immediate(_)) => Some(0x42),
+        (M::REP, AM::Immediate(_)) => Some(0xc2),
+        (M::SEP, AM::Immediate(_)) => Some(0xe2),
+        (M::JMP | M::JML, AM::AbsoluteLong(_)) => Some(0x5c),
+        (M::JMP | M::JML, AM::AbsoluteIndirectLong(_)) => Some(0xdc),
+        (M::JSR | M::JSL, AM::AbsoluteLong(_)) => Some(0x22),
+        (M::JSR, AM::AbsoluteIndexedIndirect(_)) => Some(0xfc),
+        (M::MVN, AM::BlockMove(_, _)) => Some(0x54),
+        (M::MVP, AM::BlockMove(_, _)) => Some(0x44),
+        (M::PEA, AM::Absolute(_)) => Some(0xf4),
+        (M::PEI, AM::ZeroPageIndirect(_)) => Some(0xd4),
+        (M::PHB, AM::Implied) => Some(0x8b),
+        (M::PHD, AM::Implied) => Some(0x0b),
+        (M::PHK, AM::Implied) => Some(0x4b),
+        (M::PLB, AM::Implied) => Some(0xab),
+        (M::PLD, AM::Implied) => Some(0x2b),
+        (M::RTL, AM::Implied) => Some(0x6b),
+        (M::TCD, AM::Implied) => Some(0x5b),
+        (M::TCS, AM::Implied) => Some(0x1b),
+        (M::TDC, AM::Implied) => Some(0x7b),
+        (M::TSC, AM::Implied) => Some(0x3b),
+        (M::TXY, AM::Implied) => Some(0x9b),
+        (M::TYX, AM::Implied) => Some(0xbb),
+        (M::XBA, AM::Implied) => Some(0xeb),
+        (M::XCE, AM::Implied) => Some(0xfb),
+        (M::STP, AM::Implied) => Some(0xdb),
+        (M::WAI, AM::Implied) => Some(0xcb),
+        _ => None,
+    }
+}
+
+/// StaticInstruction represents an instruction with all references resolved,
+/// encoded for a specific cpu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticInstruction {
+    pub mnemonic: Mnemonic,
+    pub addressing_mode: AddressingMode,
+    opcode: OpCode,
+    cpu: Cpu,
+}
+
+impl StaticInstruction {
+    pub fn new(
+        cpu: Cpu,
+        mnemonic: Mnemonic,
+        addressing_mode: AddressingMode,
+    ) -> Result<Self, UnknownInstructionErr> {
+        cpu.opcode(mnemonic, addressing_mode)
+            .map(|opcode| Self {
+                mnemonic,
+                addressing_mode,
+                opcode,
+                cpu,
+            })
+            .ok_or_else(|| UnknownInstructionErr::new(mnemonic, addressing_mode))
+    }
+
+    /// Returns the cycles the instruction takes to execute on the cpu it was
+    /// encoded for, if they are known.
+    pub fn cycles(&self) -> Option<cycles::Cycles> {
+        cycles::cycles(self.cpu, self.mnemonic, self.addressing_mode)
+    }
+}
+
+impl Emitter<Vec<OpCode>> for StaticInstruction {
+    fn emit(&self) -> Vec<OpCode> {
+        vec![self.opcode]
+            .into_iter()
+            .chain(Vec::<u8>::from(self.addressing_mode))
+            .collect()
+    }
+}
+/// OperandSize represents an explicit override of an operand's width,
+/// selecting between the zero page, absolute and long forms of an
+/// instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperandSize {
+    Byte,
+    Word,
+    /// A 24-bit operand, supported only by the 65816.
+    Long,
+}
+
+/// BranchForm represents how a conditional branch to a label is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BranchForm {
+    /// A relative branch, erroring if the target is out of range.
+    #[default]
+    Short,
+    /// A relative branch that is relaxed to a long branch if the target is out
+    /// of range.
+    Relaxable,
+    /// An inverted relative branch over a `jmp` to the target.
+    Long,
+}
+
 /// Instruction represents a single 6502 instruction containing a mnemonic,
 /// and either a static address_mode or a label.
 #[derive(Clone, PartialEq, Debug)]
 pub struct Instruction {
     pub mnemonic: Mnemonic,
     pub amor: AddressingModeOrReference,
+    pub operand_size: Option<OperandSize>,
+    pub branch_form: BranchForm,
+    pub cpu: Cpu,
+    /// The width of any immediate operand, as set by the width of the register
+    /// the instruction targets.
+    pub immediate_size: OperandSize,
 }
 
 impl Instruction {
-    pub fn new(mnemonic: Mnemonic, amor: AddressingModeOrReference) -> Self {
-        Self { mnemonic, amor }
+    pub fn new<M: Into<Mnemonic>>(mnemonic: M, amor: AddressingModeOrReference) -> Self {
+        Self {
+            mnemonic: mnemonic.into(),
+            amor,
+            operand_size: None,
+            branch_form: BranchForm::default(),
+            cpu: Cpu::default(),
+            immediate_size: OperandSize::Byte,
+        }
+    }
+
+    /// Returns true if the instruction takes an immediate operand.
+    pub fn is_immediate(&self) -> bool {
+        match &self.amor {
+            AddressingModeOrReference::AddressingMode(am) => {
+                matches!(
+                    am,
+                    AddressingMode::Immediate(_) | AddressingMode::ImmediateWord(_)
+                )
+            }
+            AddressingModeOrReference::Symbol(s) => {
+                matches!(s.address_mode_type, AddressingModeType::Immediate)
+            }
+            AddressingModeOrReference::ExtendedSymbol(s) => {
+                s.address_mode_type == ExtendedAddressingModeType::BankByte
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the instruction is one of the undocumented opcodes
+    /// whose behavior varies between parts, such as `ane` or `lax #imm`.
+    pub fn is_unstable(&self) -> bool {
+        match self.mnemonic {
+            Mnemonic::ANE | Mnemonic::SHA | Mnemonic::SHX | Mnemonic::SHY | Mnemonic::TAS => true,
+            Mnemonic::LAX => self.is_immediate(),
+            _ => false,
+        }
+    }
+
+    /// with_branch_form sets how a branch to a label is encoded, having no
+    /// effect on any other instruction.
+    pub fn with_branch_form(mut self, branch_form: BranchForm) -> Self {
+        self.branch_form = branch_form;
+        self
+    }
+
+    /// with_operand_size forces the width of the instruction's operand,
+    /// taking precedence over any automatic selection.
+    pub fn with_operand_size(mut self, operand_size: OperandSize) -> Self {
+        self.operand_size = Some(operand_size);
+        self
     }
 }
 
 impl addressing::SizeOf for Instruction {
     fn size_of(&self) -> usize {
-        self.mnemonic.size_of() + self.amor.size_of()
+        let operand_size = match (self.operand_size, &self.amor) {
+            (
+                Some(OperandSize::Byte),
+                AddressingModeOrReference::Label(_) | AddressingModeOrReference::Symbol(_),
+            ) => 1,
+            (
+                Some(OperandSize::Long),
+                AddressingModeOrReference::Label(_) | AddressingModeOrReference::Symbol(_),
+            ) => 3,
+            _ if self.immediate_size == OperandSize::Word && self.is_immediate() => 2,
+            // `jml [abs]` shares its syntax with the direct page `[dp]`.
+            (_, AddressingModeOrReference::ExtendedSymbol(s))
+                if self.mnemonic == Mnemonic::JML
+                    && s.address_mode_type
+                        == ExtendedAddressingModeType::DirectPageIndirectLong =>
+            {
+                2
+            }
+            _ => self.amor.size_of(),
+        };
+
+        self.mnemonic.size_of() + operand_size
     }
 }
 
-impl From<(Mnemonic, AddressingMode)> for Instruction {
-    fn from((m, am): (Mnemonic, AddressingMode)) -> Self {
-        Self {
-            mnemonic: m,
-            amor: AddressingModeOrReference::AddressingMode(am),
-        }
+impl<M, A> From<(M, A)> for Instruction
+where
+    M: Into<Mnemonic>,
+    A: Into<AddressingMode>,
+{
+    fn from((m, am): (M, A)) -> Self {
+        Self::new(m, AddressingModeOrReference::AddressingMode(am.into()))
     }
 }
 
 /// UnknownInstructionErr represents an Instruction that is unrepresentable or unknown.
 #[derive(Debug, Copy, Clone)]
 pub struct UnknownInstructionErr {
-    mnemonic: isa_mos6502::mnemonic::Mnemonic,
-    addressing_mode: isa_mos6502::addressing_mode::AddressingModeType,
+    mnemonic: Mnemonic,
+    addressing_mode: AddressingMode,
 }
 
 impl UnknownInstructionErr {
-    pub fn new(
-        mnemonic: isa_mos6502::mnemonic::Mnemonic,
-        addressing_mode: isa_mos6502::addressing_mode::AddressingModeType,
-    ) -> Self {
+    pub fn new(mnemonic: Mnemonic, addressing_mode: AddressingMode) -> Self {
         Self {
             mnemonic,
             addressing_mode,
@@ -0,0 +1,62 @@
+use crate::backends::mos6502::instruction_set::addressing_mode::AddressingMode;
+use crate::backends::mos6502::instruction_set::cycles::{cycles, Cycles};
+use crate::backends::mos6502::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mos6502::instruction_set::Cpu;
+
+#[test]
+fn should_return_base_cycles_of_nmos_instructions() {
+    assert_eq!(
+        Some(Cycles::new(2)),
+        cycles(
+            Cpu::Nmos6502,
+            Mnemonic::LDA,
+            AddressingMode::Immediate(0x00)
+        )
+    );
+    assert_eq!(
+        Some(Cycles::new(7)),
+        cycles(
+            Cpu::Nmos6502,
+            Mnemonic::INC,
+            AddressingMode::AbsoluteIndexedWithX(0x1234)
+        )
+    );
+    assert_eq!(
+        Some(Cycles::new(5)),
+        cycles(
+            Cpu::Nmos6502,
+            Mnemonic::JMP,
+            AddressingMode::Indirect(0x1234)
+        )
+    );
+}
+
+#[test]
+fn should_flag_page_crossing_and_branch_penalties() {
+    let indexed_read = cycles(
+        Cpu::Nmos6502,
+        Mnemonic::LDA,
+        AddressingMode::AbsoluteIndexedWithY(0x1234),
+    )
+    .unwrap();
+    let branch = cycles(Cpu::Nmos6502, Mnemonic::BNE, AddressingMode::Relative(-2)).unwrap();
+
+    assert_eq!((4, 5), (indexed_read.base, indexed_read.worst_case()));
+    assert_eq!((2, 4), (branch.base, branch.worst_case()));
+}
+
+#[test]
+fn should_return_cmos_specific_cycles() {
+    assert_eq!(
+        Some(Cycles::new(6)),
+        cycles(
+            Cpu::Cmos65c02,
+            Mnemonic::JMP,
+            AddressingMode::Indirect(0x1234)
+        )
+    );
+    assert_eq!(
+        None,
+        cycles(Cpu::Wdc65816, Mnemonic::NOP, AddressingMode::Implied)
+    );
+}
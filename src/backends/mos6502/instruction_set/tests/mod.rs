@@ -1,2 +1,3 @@
 mod addressing_mode;
+mod cycles;
 mod instructions;
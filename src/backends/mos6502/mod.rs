@@ -3,338 +3,843 @@ pub mod instruction_set;
 pub mod parser;
 
 use parcel::prelude::v1::*;
-use std::collections::HashMap;
 
-use crate::addressing;
 use crate::addressing::{Positional, SizeOf};
-use crate::backends::mos6502::instruction_set::addressing_mode::AddressingModeOrReference;
-use crate::backends::mos6502::instruction_set::Instruction;
+use crate::backends::layout::{
+    encode_origins, evaluate_directives, layout, parse_origins, resolve_constant, AssembledOrigins,
+    InstructionOrConstant, InstructionSet, MemoryAlignedStream, SymbolTable, UnparsedTokenStream,
+};
+use crate::backends::mos6502::instruction_set::addressing_mode::{
+    AddressingMode, AddressingModeOrReference, ExtendedAddressingModeType, ZeroPageOperand,
+};
+use crate::backends::mos6502::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mos6502::instruction_set::{
+    BranchForm, Cpu, Instruction, OperandSize, StaticInstruction,
+};
 use crate::backends::BackendErr;
-use crate::preparser::{types, PrimitiveOrReference, Token};
+use crate::diagnostics::{Diagnostic, DiagnosticSender, Severity};
+use crate::preparser::{types, PrimitiveOrReference, Register, Token, Vectors};
 use crate::{Assembler, AssemblerResult};
 use crate::{Emitter, Origin};
-use isa_mos6502::addressing_mode::AddressingMode;
+use isa_mos6502::addressing_mode::AddressingModeType;
+use isa_mos6502::ByteSized;
 
-type UnparsedTokenStream = Vec<Token<String>>;
 type Token6502InstStream = Vec<Token<Instruction>>;
-type PositionalToken6502Stream = Vec<Positional<Token<Instruction>>>;
-type MemoryAligned6502Stream = Vec<InstructionOrConstant<Instruction, PrimitiveOrReference>>;
-type AssembledOrigins = Vec<Origin<Vec<u8>>>;
-
-use crate::preparser::types::Reify;
-impl Reify<u8> for crate::preparser::types::LeByteEncodedValue {
-    type Error = crate::preparser::types::TypeError;
-
-    fn reify(&self) -> Result<u8, Self::Error> {
-        match self.bits() {
-            0 => Ok(0),
-            1..=8 => Ok(self.to_vec().first().copied().unwrap_or(0)),
-            _ => Err(Self::Error::IllegalType(format!(
-                "bit-width {}",
-                self.bits()
-            ))),
-        }
+type MemoryAligned6502Stream = MemoryAlignedStream<Instruction>;
+
+/// The address of the interrupt vector table, holding the nmi, reset and irq
+/// vectors through to the end of the address space.
+const VECTOR_TABLE_OFFSET: usize = 0xfffa;
+const VECTOR_TABLE_END: usize = 0x10000;
+
+/// Parses the text of a single instruction.
+fn parse_instruction(source: &str) -> Result<Instruction, parser::ParseErr> {
+    let input = source.chars().collect::<Vec<char>>();
+    match parser::instruction().parse(&input) {
+        Ok(MatchStatus::Match((_, inst))) => Ok(inst),
+        Ok(MatchStatus::NoMatch(remainder)) => Err(parser::ParseErr::Unspecified(
+            remainder.iter().collect::<String>(),
+        )),
+        Err(e) => Err(parser::ParseErr::Unspecified(e)),
     }
 }
 
-impl Reify<u16> for crate::preparser::types::LeByteEncodedValue {
-    type Error = crate::preparser::types::TypeError;
+/// CycleCount represents the cycles taken by a run of instructions, both
+/// without penalties and with every page crossing and taken branch penalty
+/// applied.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CycleCount {
+    pub base: usize,
+    pub worst_case: usize,
+}
+
+/// Sums the cycles taken by each instruction, in source order, from the start
+/// label up to, but not including, the end label. Both labels must be
+/// defined in the same origin, so that instructions of another origin or
+/// `.phase` block sharing their addresses are never counted.
+fn count_cycles(
+    symbol_table: &SymbolTable,
+    token_origins: &[Origin<Token6502InstStream>],
+    origins: &[Origin<MemoryAligned6502Stream>],
+    start: &str,
+    end: &str,
+) -> Result<CycleCount, BackendErr> {
+    let index_of = |label: &str| {
+        label_index(token_origins, label)
+            .ok_or_else(|| BackendErr::UndefinedReference(label.to_string()))
+    };
+    let ((start_origin, start_index), (end_origin, end_index)) = (index_of(start)?, index_of(end)?);
+    if start_origin != end_origin {
+        return Err(BackendErr::Unspecified(format!(
+            "{} and {} are defined in different origins",
+            start, end
+        )));
+    }
+    if end_index < start_index {
+        return Err(BackendErr::Unspecified(format!(
+            "{} precedes {}",
+            end, start
+        )));
+    }
 
-    fn reify(&self) -> Result<u16, Self::Error> {
-        match self.bits() {
-            0 => Ok(0),
-            1..=8 => Reify::<u8>::reify(self).map(u16::from),
-            9..=16 => {
-                let bytes = self.to_vec();
-                Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    origins[start_origin].instructions[start_index..end_index]
+        .iter()
+        .filter(|p| matches!(p.contents(), InstructionOrConstant::Instruction(_)))
+        .map(|p| dereference_instructions_to_static_instructions(symbol_table, p.clone()))
+        .collect::<Result<Vec<_>, BackendErr>>()?
+        .into_iter()
+        .flatten()
+        .try_fold(CycleCount::default(), |count, ioc| match ioc {
+            InstructionOrConstant::Instruction(si) => si
+                .cycles()
+                .map(|cycles| CycleCount {
+                    base: count.base + cycles.base,
+                    worst_case: count.worst_case + cycles.worst_case(),
+                })
+                .ok_or_else(|| {
+                    BackendErr::Unspecified(format!("cycles unknown for {:?}", si.mnemonic))
+                }),
+            InstructionOrConstant::Constant(_) => Ok(count),
+        })
+}
+
+/// Sets the cpu of each instruction to that selected by the most recent
+/// `.cpu` directive, falling back to the provided default, and sizes any
+/// immediate operands on the 65816 by the most recently set register widths.
+/// Directives may only select cpus whose code runs on the default. Unstable
+/// undocumented instructions are reported to the diagnostics channel, if one
+/// is provided.
+fn apply_processor_state(
+    default: Cpu,
+    token_origins: &mut [Origin<Token6502InstStream>],
+    diagnostics: Option<&DiagnosticSender>,
+) -> Result<(), BackendErr> {
+    let mut cpu = default;
+    // registers are 8 bits wide out of reset.
+    let mut accumulator_size = OperandSize::Byte;
+    let mut index_size = OperandSize::Byte;
+    let tokens = token_origins
+        .iter_mut()
+        .flat_map(|origin| origin.instructions.iter_mut());
+
+    for token in tokens {
+        match token {
+            Token::Cpu(name, location) => {
+                let selected = Cpu::try_from(name.as_str())
+                    .map_err(|e| BackendErr::Unspecified(format!("{}: {}", location, e)))?;
+                if !default.runs(selected) {
+                    return Err(BackendErr::Unspecified(format!(
+                        "{}: cpu {} is outside the family of the selected backend",
+                        location, name
+                    )));
+                }
+                cpu = selected;
+            }
+            Token::RegisterWidth(register, width, _) => {
+                let size = match width {
+                    16 => OperandSize::Word,
+                    _ => OperandSize::Byte,
+                };
+                match register {
+                    Register::Accumulator => accumulator_size = size,
+                    Register::Index => index_size = size,
+                }
             }
-            _ => Err(Self::Error::IllegalType(format!(
-                "bit-width {}",
-                self.bits()
-            ))),
+            Token::Instruction(i, location) => {
+                i.cpu = cpu;
+                i.immediate_size = match (cpu, i.mnemonic.immediate_register()) {
+                    (Cpu::Wdc65816, Some(Register::Accumulator)) => accumulator_size,
+                    (Cpu::Wdc65816, Some(Register::Index)) => index_size,
+                    _ => OperandSize::Byte,
+                };
+
+                if let (Cpu::Nmos6502Undocumented, true, Some(sender)) =
+                    (cpu, i.is_unstable(), diagnostics)
+                {
+                    let _ = sender.send(Diagnostic::new(
+                        Severity::Warning,
+                        *location,
+                        format!("unstable undocumented instruction: {:?}", i.mnemonic),
+                    ));
+                }
+            }
+            _ => (),
         }
     }
-}
 
-type SymbolMap = HashMap<String, LeByteEncodedValue>;
+    Ok(())
+}
 
-#[derive(Default, Debug)]
-struct SymbolTable {
-    symbols: SymbolMap,
+/// Returns the index of the origin that a label is defined in, along with the
+/// index of the instruction or constant that it precedes within the laid out
+/// origin.
+fn label_index(
+    token_origins: &[Origin<Token6502InstStream>],
+    label: &str,
+) -> Option<(usize, usize)> {
+    token_origins
+        .iter()
+        .enumerate()
+        .find_map(|(origin_index, origin)| {
+            origin
+                .instructions
+                .iter()
+                .scan(0, |index, token| {
+                    let at = *index;
+                    if matches!(token, Token::Instruction(..) | Token::Constant(_)) {
+                        *index += 1;
+                    }
+                    Some((at, token))
+                })
+                .find(|(_, token)| matches!(token, Token::Symbol(id, None) if id == label))
+                .map(|(index, _)| (origin_index, index))
+        })
 }
 
-use crate::preparser::types::LeByteEncodedValue;
-impl SymbolTable {
-    fn new(symbols: SymbolMap) -> Self {
-        Self { symbols }
+/// Selects the narrowest form of an absolute or indirect addressing mode that
+/// the cpu supports for the mnemonic and that the operand fits within. The
+/// zero page form is selected for operands below `0x100` and the long form
+/// for operands beyond 16 bits, falling back to the absolute form. Operands
+/// beyond 16 bits without a supported long form return an error.
+fn zeropage_or_absolute(
+    cpu: Cpu,
+    mnemonic: Mnemonic,
+    amt: AddressingModeType,
+    operand: u32,
+) -> Result<AddressingMode, BackendErr> {
+    let (absolute, zeropage, long) = match amt {
+        AddressingModeType::AbsoluteIndexedWithX => (
+            AddressingMode::AbsoluteIndexedWithX(operand as u16),
+            AddressingMode::ZeroPageIndexedWithX(operand as u8),
+            Some(AddressingMode::AbsoluteLongIndexedWithX(operand)),
+        ),
+        AddressingModeType::AbsoluteIndexedWithY => (
+            AddressingMode::AbsoluteIndexedWithY(operand as u16),
+            AddressingMode::ZeroPageIndexedWithY(operand as u8),
+            None,
+        ),
+        AddressingModeType::Indirect => (
+            AddressingMode::Indirect(operand as u16),
+            AddressingMode::ZeroPageIndirect(operand as u8),
+            None,
+        ),
+        _ => (
+            AddressingMode::Absolute(operand as u16),
+            AddressingMode::ZeroPage(operand as u8),
+            Some(AddressingMode::AbsoluteLong(operand)),
+        ),
+    };
+
+    let supported = |am: AddressingMode| cpu.opcode(mnemonic, am).is_some();
+    let absolute_supported = supported(absolute);
+    // indirect addressing has no absolute form outside of `jmp`, so zero page
+    // indirect is preferred wherever the absolute form is unsupported.
+    if operand <= 0xff
+        && supported(zeropage)
+        && (amt != AddressingModeType::Indirect || !absolute_supported)
+    {
+        return Ok(zeropage);
     }
 
-    fn get(&self, k: &str) -> Option<LeByteEncodedValue> {
-        self.symbols.get(k).cloned()
+    match long.filter(|long| supported(*long)) {
+        Some(long) if operand > 0xffff || !absolute_supported => Ok(long),
+        _ if operand > 0xffff => Err(BackendErr::Unspecified(format!(
+            "operand {:#08x} exceeds 16 bits",
+            operand
+        ))),
+        _ => Ok(absolute),
     }
+}
 
-    fn get_as_u8(&self, k: &str) -> Option<u8> {
-        self.get(k)
-            .map(|lebev| lebev.reify())
-            .and_then(|res| res.ok())
-    }
+/// Converts between the zero page, absolute and long forms of an addressing
+/// mode to match an explicit operand size, returning an error if the operand
+/// doesn't fit within the requested size.
+fn apply_operand_size(
+    operand_size: OperandSize,
+    am: AddressingMode,
+) -> Result<AddressingMode, BackendErr> {
+    let to_zeropage = |operand: u32| {
+        u8::try_from(operand).map_err(|_| {
+            BackendErr::Unspecified(format!("operand {:#06x} exceeds the zero page", operand))
+        })
+    };
+    let to_absolute = |operand: u32| {
+        u16::try_from(operand).map_err(|_| {
+            BackendErr::Unspecified(format!("operand {:#08x} exceeds 16 bits", operand))
+        })
+    };
 
-    fn get_as_u16(&self, k: &str) -> Option<u16> {
-        self.get(k)
-            .map(|lebev| Reify::<u16>::reify(&lebev))
-            .and_then(|res| res.ok())
+    match (operand_size, am) {
+        (OperandSize::Word, AddressingMode::ZeroPage(operand)) => {
+            Ok(AddressingMode::Absolute(u16::from(operand)))
+        }
+        (OperandSize::Word, AddressingMode::ZeroPageIndexedWithX(operand)) => {
+            Ok(AddressingMode::AbsoluteIndexedWithX(u16::from(operand)))
+        }
+        (OperandSize::Word, AddressingMode::ZeroPageIndexedWithY(operand)) => {
+            Ok(AddressingMode::AbsoluteIndexedWithY(u16::from(operand)))
+        }
+        (OperandSize::Word, AddressingMode::AbsoluteLong(operand)) => {
+            to_absolute(operand).map(AddressingMode::Absolute)
+        }
+        (OperandSize::Word, AddressingMode::AbsoluteLongIndexedWithX(operand)) => {
+            to_absolute(operand).map(AddressingMode::AbsoluteIndexedWithX)
+        }
+        (OperandSize::Byte, AddressingMode::Absolute(operand)) => {
+            to_zeropage(u32::from(operand)).map(AddressingMode::ZeroPage)
+        }
+        (OperandSize::Byte, AddressingMode::AbsoluteIndexedWithX(operand)) => {
+            to_zeropage(u32::from(operand)).map(AddressingMode::ZeroPageIndexedWithX)
+        }
+        (OperandSize::Byte, AddressingMode::AbsoluteIndexedWithY(operand)) => {
+            to_zeropage(u32::from(operand)).map(AddressingMode::ZeroPageIndexedWithY)
+        }
+        (OperandSize::Byte, AddressingMode::AbsoluteLong(operand)) => {
+            to_zeropage(operand).map(AddressingMode::ZeroPage)
+        }
+        (OperandSize::Byte, AddressingMode::AbsoluteLongIndexedWithX(operand)) => {
+            to_zeropage(operand).map(AddressingMode::ZeroPageIndexedWithX)
+        }
+        (OperandSize::Long, AddressingMode::ZeroPage(operand)) => {
+            Ok(AddressingMode::AbsoluteLong(u32::from(operand)))
+        }
+        (OperandSize::Long, AddressingMode::Absolute(operand)) => {
+            Ok(AddressingMode::AbsoluteLong(u32::from(operand)))
+        }
+        (OperandSize::Long, AddressingMode::ZeroPageIndexedWithX(operand)) => {
+            Ok(AddressingMode::AbsoluteLongIndexedWithX(u32::from(operand)))
+        }
+        (OperandSize::Long, AddressingMode::AbsoluteIndexedWithX(operand)) => {
+            Ok(AddressingMode::AbsoluteLongIndexedWithX(u32::from(operand)))
+        }
+        (_, am) => Ok(am),
     }
+}
 
-    fn insert(&mut self, k: &str, v: LeByteEncodedValue) -> Option<LeByteEncodedValue> {
-        self.symbols.insert(k.to_string(), v)
+/// Converts between the 8 and 16-bit forms of an immediate to match the width
+/// of the register it targets, returning an error if the operand doesn't fit
+/// within an 8-bit register.
+fn apply_immediate_size(
+    immediate_size: OperandSize,
+    am: AddressingMode,
+) -> Result<AddressingMode, BackendErr> {
+    match (immediate_size, am) {
+        (OperandSize::Word, AddressingMode::Immediate(operand)) => {
+            Ok(AddressingMode::ImmediateWord(u16::from(operand)))
+        }
+        (OperandSize::Byte, AddressingMode::ImmediateWord(operand)) => u8::try_from(operand)
+            .map(AddressingMode::Immediate)
+            .map_err(|_| {
+                BackendErr::Unspecified(format!(
+                    "immediate {:#06x} exceeds an 8-bit register",
+                    operand
+                ))
+            }),
+        (_, am) => Ok(am),
     }
 }
 
-impl From<Vec<SymbolTable>> for SymbolTable {
-    fn from(src: Vec<SymbolTable>) -> Self {
-        let symbols = src
-            .into_iter()
-            .map(|st| st.symbols)
-            .fold(SymbolMap::new(), |acc, sm| {
-                acc.into_iter().chain(sm).collect()
-            });
-
-        Self::new(symbols)
+/// Returns the branch taken on the inverse of a conditional branch's
+/// condition. Unconditional branches have no inverse.
+fn invert_branch(mnemonic: Mnemonic) -> Option<Mnemonic> {
+    match mnemonic {
+        Mnemonic::BCC => Some(Mnemonic::BCS),
+        Mnemonic::BCS => Some(Mnemonic::BCC),
+        Mnemonic::BEQ => Some(Mnemonic::BNE),
+        Mnemonic::BNE => Some(Mnemonic::BEQ),
+        Mnemonic::BMI => Some(Mnemonic::BPL),
+        Mnemonic::BPL => Some(Mnemonic::BMI),
+        Mnemonic::BVC => Some(Mnemonic::BVS),
+        Mnemonic::BVS => Some(Mnemonic::BVC),
+        _ => None,
     }
 }
 
-/// Stores either an instruction or a constant with either value being
-/// generalized as these values are commonly transformed through the pipeline.
-enum InstructionOrConstant<T, U> {
-    Instruction(T),
-    Constant(U),
+/// Resolves a branch target to a relative offset from the end of a branch
+/// instruction of the provided size at the provided position, returning an
+/// error if the offset doesn't fit within the branch's operand.
+fn relative_offset_to<T: TryFrom<isize>>(
+    symbol_table: &SymbolTable,
+    position: usize,
+    size: usize,
+    label: &str,
+) -> Result<T, BackendErr> {
+    let target = symbol_table
+        .get_as_u32(label)
+        .ok_or_else(|| BackendErr::UndefinedReference(label.to_string()))?;
+    // offsets are relative to the instruction following the branch.
+    let offset = target as isize - (position + size) as isize;
+
+    T::try_from(offset).map_err(|_| BackendErr::BranchOutOfRange(label.to_string(), offset))
 }
 
-fn parse_string_instructions_origin_to_token_instructions_origin(
-    source: Origin<UnparsedTokenStream>,
-) -> Result<Origin<Token6502InstStream>, parser::ParseErr> {
-    let origin_offset = source.offset;
-    let tokens = source
-        .instructions
-        .into_iter()
-        .map(|tok| match tok {
-            Token::Symbol(id, v) => Ok(Token::Symbol(id, v)),
-            Token::Constant(v) => Ok(Token::Constant(v)),
-            Token::Instruction(inst) => {
-                let input = inst.chars().collect::<Vec<char>>();
-                let res = match parser::instruction().parse(&input) {
-                    Ok(MatchStatus::Match((_, inst))) => Ok(Token::Instruction(inst)),
-                    Ok(MatchStatus::NoMatch(remainder)) => Err(parser::ParseErr::Unspecified(
-                        remainder.iter().collect::<String>(),
-                    )),
-                    Err(e) => Err(parser::ParseErr::Unspecified(e)),
-                };
-                res
-            }
-        })
-        .collect::<Result<Token6502InstStream, parser::ParseErr>>()?;
-
-    Ok(Origin::with_offset(origin_offset, tokens))
+/// Resolves an instruction's operand, at the provided position, to a static
+/// addressing mode, returning an error if it references an undefined symbol.
+/// Any explicit operand size takes precedence over the automatically selected
+/// addressing mode.
+fn resolve_addressing_mode(
+    symbol_table: &SymbolTable,
+    position: usize,
+    instruction: &Instruction,
+) -> Result<AddressingMode, BackendErr> {
+    let am = resolve_operand(symbol_table, position, instruction)?;
+    let am = apply_immediate_size(instruction.immediate_size, am)?;
+
+    match instruction.operand_size {
+        Some(operand_size) => apply_operand_size(operand_size, am),
+        None => Ok(am),
+    }
 }
 
-/// Annotates a given instruction with it's corresponding address offset position.
-fn convert_token_instructions_origins_to_positional_tokens_origin(
-    source: Origin<Token6502InstStream>,
-) -> Origin<PositionalToken6502Stream> {
-    let origin_offset = source.offset;
-    let tokens = source.instructions;
-    let positional_instructions = tokens
-        .into_iter()
-        .fold(
-            (origin_offset, Vec::new()),
-            |(offset, mut tokens), token| match token {
-                Token::Instruction(i) => {
-                    let size_of = i.size_of();
-                    tokens.push(addressing::Positional::with_position(
-                        offset,
-                        Token::Instruction(i),
-                    ));
-                    (offset + size_of, tokens)
-                }
-                t => {
-                    tokens.push(addressing::Positional::with_position(offset, t));
-                    (offset, tokens)
+fn resolve_operand(
+    symbol_table: &SymbolTable,
+    position: usize,
+    instruction: &Instruction,
+) -> Result<AddressingMode, BackendErr> {
+    let cpu = instruction.cpu;
+    let mnemonic = instruction.mnemonic;
+    // `jmp (abs,X)` and `jsr (abs,X)` share their syntax with the zero page
+    // `(zp,X)`, as `jml [abs]` does with the direct page `[dp]`.
+    let absolute_indexed_indirect = matches!(mnemonic, Mnemonic::JMP | Mnemonic::JSR);
+    let absolute_indirect_long = matches!(mnemonic, Mnemonic::JMP | Mnemonic::JML);
+
+    match &instruction.amor {
+        AddressingModeOrReference::AddressingMode(AddressingMode::XIndexedIndirect(operand))
+            if absolute_indexed_indirect =>
+        {
+            Ok(AddressingMode::AbsoluteIndexedIndirect(u16::from(*operand)))
+        }
+        AddressingModeOrReference::AddressingMode(AddressingMode::DirectPageIndirectLong(
+            operand,
+        )) if absolute_indirect_long => {
+            Ok(AddressingMode::AbsoluteIndirectLong(u16::from(*operand)))
+        }
+        AddressingModeOrReference::AddressingMode(AddressingMode::Indirect(operand)) => {
+            zeropage_or_absolute(
+                cpu,
+                mnemonic,
+                AddressingModeType::Indirect,
+                u32::from(*operand),
+            )
+        }
+        AddressingModeOrReference::AddressingMode(am) => Ok(*am),
+        AddressingModeOrReference::Label(l) if mnemonic.is_long_branch() => {
+            relative_offset_to(symbol_table, position, 3, l).map(AddressingMode::RelativeLong)
+        }
+        AddressingModeOrReference::Label(l) if mnemonic.is_branch() => {
+            relative_offset_to(symbol_table, position, 2, l).map(AddressingMode::Relative)
+        }
+        AddressingModeOrReference::Label(l) => symbol_table
+            .get_as_u32(l)
+            .ok_or_else(|| BackendErr::UndefinedReference(l.clone()))
+            .and_then(|offset| {
+                zeropage_or_absolute(cpu, mnemonic, AddressingModeType::Absolute, offset)
+            }),
+        AddressingModeOrReference::BitBranch(bb) => {
+            let zeropage = match &bb.zeropage {
+                ZeroPageOperand::Address(zp) => Ok(*zp),
+                ZeroPageOperand::Symbol(id) => symbol_table
+                    .get_as_u8(id)
+                    .ok_or_else(|| BackendErr::UndefinedReference(id.clone())),
+            }?;
+
+            relative_offset_to(symbol_table, position, 3, &bb.target)
+                .map(|offset| AddressingMode::ZeroPageRelative(zeropage, offset))
+        }
+        AddressingModeOrReference::Symbol(s) => {
+            let undefined = || BackendErr::UndefinedReference(s.symbol.clone());
+            match s.address_mode_type {
+                AddressingModeType::AbsoluteIndexedWithX
+                | AddressingModeType::AbsoluteIndexedWithY
+                | AddressingModeType::Indirect => symbol_table
+                    .get_as_u32(&s.symbol)
+                    .ok_or_else(undefined)
+                    .and_then(|offset| {
+                        zeropage_or_absolute(cpu, mnemonic, s.address_mode_type, offset)
+                    }),
+                AddressingModeType::XIndexedIndirect if absolute_indexed_indirect => symbol_table
+                    .get_as_u16(&s.symbol)
+                    .map(AddressingMode::AbsoluteIndexedIndirect)
+                    .ok_or_else(undefined),
+                AddressingModeType::XIndexedIndirect => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::XIndexedIndirect)
+                    .ok_or_else(undefined),
+                AddressingModeType::IndirectYIndexed => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::IndirectYIndexed)
+                    .ok_or_else(undefined),
+                _ if instruction.immediate_size == OperandSize::Word => symbol_table
+                    .get_as_u16(&s.symbol)
+                    .map(AddressingMode::ImmediateWord)
+                    .ok_or_else(undefined),
+                _ => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::Immediate)
+                    .ok_or_else(undefined),
+            }
+        }
+        AddressingModeOrReference::ExtendedSymbol(s) => {
+            let undefined = || BackendErr::UndefinedReference(s.symbol.clone());
+            match s.address_mode_type {
+                ExtendedAddressingModeType::DirectPageIndirectLong if absolute_indirect_long => {
+                    symbol_table
+                        .get_as_u16(&s.symbol)
+                        .map(AddressingMode::AbsoluteIndirectLong)
+                        .ok_or_else(undefined)
                 }
-            },
-        )
-        .1;
-
-    Origin::with_offset(origin_offset, positional_instructions)
+                ExtendedAddressingModeType::DirectPageIndirectLong => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::DirectPageIndirectLong)
+                    .ok_or_else(undefined),
+                ExtendedAddressingModeType::DirectPageIndirectLongIndexedWithY => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::DirectPageIndirectLongIndexedWithY)
+                    .ok_or_else(undefined),
+                ExtendedAddressingModeType::StackRelative => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::StackRelative)
+                    .ok_or_else(undefined),
+                ExtendedAddressingModeType::StackRelativeIndirectIndexedWithY => symbol_table
+                    .get_as_u8(&s.symbol)
+                    .map(AddressingMode::StackRelativeIndirectIndexedWithY)
+                    .ok_or_else(undefined),
+                ExtendedAddressingModeType::BankByte => symbol_table
+                    .get_as_u32(&s.symbol)
+                    .map(|address| AddressingMode::Immediate((address >> 16) as u8))
+                    .ok_or_else(undefined),
+            }
+        }
+    }
 }
 
-/// Walks the source, collecting all symbols and labels into a symbol table.
-fn generate_symbol_table_from_instructions_origin(
-    source: Origin<PositionalToken6502Stream>,
-) -> (SymbolTable, Origin<MemoryAligned6502Stream>) {
-    let (origin_offset, instructions) = source.into();
-    let (symbol_table, tokens) = instructions.into_iter().fold(
-        (SymbolTable::default(), Vec::new()),
-        |(mut st, mut insts), positional_token| {
-            let offset = positional_token.position;
-            let token = positional_token.unwrap();
-            match token {
-                Token::Instruction(i) => {
-                    insts.push(InstructionOrConstant::Instruction(i));
-                    (st, insts)
-                }
-                Token::Constant(bvol) => {
-                    insts.push(InstructionOrConstant::Constant(bvol));
-                    (st, insts)
-                }
-                Token::Symbol(l, None) => {
-                    let normalized_offset = offset as u16;
-                    st.insert(&l, LeByteEncodedValue::from(normalized_offset));
-                    (st, insts)
-                }
-                Token::Symbol(id, Some(bv)) => {
-                    st.insert(&id, bv);
-                    (st, insts)
-                }
+/// Sizes an instruction against the currently resolved symbols, falling back
+/// to the widest encoding of any operand that can't yet be resolved. Branches
+/// are always a fixed size, regardless of their target.
+fn size_of_instruction(symbol_table: &SymbolTable, instruction: &Instruction) -> usize {
+    let branch_size = instruction.mnemonic.size_of() + AddressingMode::Relative(0).byte_size();
+    let jmp_size = Mnemonic::JMP.size_of() + AddressingMode::Absolute(0).byte_size();
+    match &instruction.amor {
+        AddressingModeOrReference::Label(_)
+            if instruction.mnemonic.is_branch() && instruction.branch_form == BranchForm::Long =>
+        {
+            // an inverted branch over a `jmp` to the target, or only the `jmp`
+            // for unconditional branches.
+            match invert_branch(instruction.mnemonic) {
+                Some(_) => branch_size + jmp_size,
+                None => jmp_size,
             }
-        },
-    );
-    (symbol_table, Origin::with_offset(origin_offset, tokens))
+        }
+        AddressingModeOrReference::Label(_) if instruction.mnemonic.is_long_branch() => {
+            instruction.mnemonic.size_of() + AddressingMode::RelativeLong(0).byte_size()
+        }
+        AddressingModeOrReference::Label(_) if instruction.mnemonic.is_branch() => branch_size,
+        AddressingModeOrReference::BitBranch(_) => instruction.size_of(),
+        _ => resolve_addressing_mode(symbol_table, 0, instruction)
+            .map(|am| instruction.mnemonic.size_of() + am.byte_size())
+            .unwrap_or_else(|_| widest_size_of(instruction)),
+    }
 }
 
-/// Dereferences all reference types operands to a corresponding value.
+/// Sizes an instruction whose operand can't yet be resolved, assuming the
+/// long form of any reference that the cpu supports one for.
+fn widest_size_of(instruction: &Instruction) -> usize {
+    let long = match &instruction.amor {
+        AddressingModeOrReference::Label(_) => Some(AddressingMode::AbsoluteLong(0)),
+        AddressingModeOrReference::Symbol(s)
+            if s.address_mode_type == AddressingModeType::AbsoluteIndexedWithX =>
+        {
+            Some(AddressingMode::AbsoluteLongIndexedWithX(0))
+        }
+        _ => None,
+    };
+
+    match long.and_then(|am| instruction.cpu.opcode(instruction.mnemonic, am)) {
+        Some(_) if instruction.operand_size.is_none() => {
+            instruction.mnemonic.size_of() + AddressingMode::AbsoluteLong(0).byte_size()
+        }
+        _ => instruction.size_of(),
+    }
+}
+
+/// Dereferences all reference types operands to a corresponding value. Long
+/// branches dereference to an inverted branch over a `jmp` to the target.
 fn dereference_instructions_to_static_instructions(
     symbol_table: &SymbolTable,
-    src_ioc: InstructionOrConstant<Instruction, PrimitiveOrReference>,
-) -> Result<
-    InstructionOrConstant<isa_mos6502::InstructionVariant, types::LeByteEncodedValue>,
-    BackendErr,
-> {
-    match src_ioc {
+    src_ioc: Positional<InstructionOrConstant<Instruction, PrimitiveOrReference>>,
+) -> Result<Vec<InstructionOrConstant<StaticInstruction, types::LeByteEncodedValue>>, BackendErr> {
+    let position = src_ioc.position;
+    let encode = |cpu, mnemonic, am| {
+        StaticInstruction::new(cpu, mnemonic, am)
+            .map_err(|e| BackendErr::UndefinedInstruction(e.to_string()))
+            .map(InstructionOrConstant::Instruction)
+    };
+
+    match src_ioc.unwrap() {
+        InstructionOrConstant::Instruction(Instruction {
+            mnemonic,
+            amor: AddressingModeOrReference::Label(l),
+            branch_form: BranchForm::Long,
+            cpu,
+            ..
+        }) if mnemonic.is_branch() => {
+            let target = symbol_table
+                .get_as_u16(&l)
+                .ok_or_else(|| BackendErr::UndefinedReference(l.clone()))?;
+
+            invert_branch(mnemonic)
+                .map(|inverted| (inverted, AddressingMode::Relative(3)))
+                .into_iter()
+                .chain([(Mnemonic::JMP, AddressingMode::Absolute(target))])
+                .map(|(m, am)| encode(cpu, m, am))
+                .collect()
+        }
         InstructionOrConstant::Instruction(i) => {
-            let mnemonic = i.mnemonic;
-            let amor = i.amor;
-            match amor {
-                AddressingModeOrReference::Label(l) => symbol_table
-                    .get_as_u16(&l)
-                    .map_or(Err(BackendErr::UndefinedReference(l.clone())), |offset| {
-                        Ok((mnemonic, AddressingMode::Absolute(offset)))
-                    }),
-                AddressingModeOrReference::Symbol(s) => symbol_table.get_as_u8(&s.symbol).map_or(
-                    Err(BackendErr::UndefinedReference(s.symbol.clone())),
-                    |byte_value| Ok((mnemonic, AddressingMode::Immediate(byte_value))),
-                ),
-                AddressingModeOrReference::AddressingMode(am) => Ok((mnemonic, am)),
-            }
-            .map(|(m, am)| {
-                isa_mos6502::InstructionVariant::new(m, am)
-                    .map_err(|e| BackendErr::UndefinedInstruction(e.to_string()))
-                    .map(InstructionOrConstant::Instruction)
-            })?
-        }
-        InstructionOrConstant::Constant(bvol) => match bvol {
-            PrimitiveOrReference::Primitive(bv) => Ok(bv),
-            PrimitiveOrReference::Reference(id) => symbol_table
-                .get(&id)
-                .ok_or_else(|| BackendErr::UndefinedReference(id.clone())),
-        }
-        .map(InstructionOrConstant::Constant),
+            let am = resolve_addressing_mode(symbol_table, position, &i)?;
+            encode(i.cpu, i.mnemonic, am).map(|ioc| vec![ioc])
+        }
+        InstructionOrConstant::Constant(bvol) => {
+            resolve_constant(symbol_table, bvol).map(|bv| vec![InstructionOrConstant::Constant(bv)])
+        }
     }
 }
 
-// assembles a given origin into it's corresponding binary representation.
-fn assemble_origin(
+/// Removes any `.vectors` directive from the source, returning an error if the
+/// vector table is defined more than once.
+fn take_vectors(source: &mut [Origin<UnparsedTokenStream>]) -> Result<Option<Vectors>, BackendErr> {
+    let mut vectors = source
+        .iter_mut()
+        .flat_map(|origin| {
+            let (vectors, tokens): (UnparsedTokenStream, UnparsedTokenStream) =
+                std::mem::take(&mut origin.instructions)
+                    .into_iter()
+                    .partition(|token| matches!(token, Token::Vectors(_)));
+            origin.instructions = tokens;
+            vectors
+        })
+        .filter_map(|token| match token {
+            Token::Vectors(v) => Some(v),
+            _ => None,
+        })
+        .collect::<Vec<Vectors>>();
+
+    match vectors.len() {
+        0 | 1 => Ok(vectors.pop()),
+        _ => Err(BackendErr::Unspecified(format!(
+            "{}: vector table defined more than once",
+            vectors[1].location
+        ))),
+    }
+}
+
+/// Assembles the vector table into its own origin, returning an error if any
+/// other origin overlaps it.
+fn assemble_vectors(
     symbol_table: &SymbolTable,
-    origin: Origin<Vec<InstructionOrConstant<Instruction, PrimitiveOrReference>>>,
+    vectors: Vectors,
+    origins: &[Origin<Vec<u8>>],
 ) -> Result<Origin<Vec<u8>>, BackendErr> {
-    let origin_offset = origin.offset;
-    let instructions = origin.instructions;
+    if let Some(origin) = origins.iter().find(|origin| {
+        origin.offset < VECTOR_TABLE_END
+            && origin.offset + origin.instructions.len() > VECTOR_TABLE_OFFSET
+    }) {
+        return Err(BackendErr::Unspecified(format!(
+            "{}: origin {:#06x} overlaps the vector table",
+            vectors.location, origin.offset
+        )));
+    }
 
-    let assembled_instructions = instructions
+    let bytes = [vectors.nmi, vectors.reset, vectors.irq]
         .into_iter()
-        .map(|ioc| (&symbol_table, ioc))
-        .map(|(st, ioc)| dereference_instructions_to_static_instructions(st, ioc))
-        .collect::<Result<
-            Vec<InstructionOrConstant<isa_mos6502::InstructionVariant, types::LeByteEncodedValue>>,
-            BackendErr,
-        >>()?
-        .into_iter()
-        .map(|ioc| match ioc {
-            InstructionOrConstant::Instruction(si) => {
-                let mc: Result<Vec<u8>, _> = si.emit();
-                mc
-            }
-            InstructionOrConstant::Constant(v) => {
-                let mc: Vec<u8> = v.emit();
-                Ok(mc)
-            }
+        .map(|label| {
+            symbol_table
+                .get_as_u16(&label)
+                .map(u16::to_le_bytes)
+                .ok_or(BackendErr::UndefinedReference(label))
         })
-        .collect::<Result<Vec<Vec<u8>>, _>>()
-        .map_err(|e| BackendErr::UndefinedInstruction(e.to_string()))?
-        .into_iter()
-        .flatten()
-        .collect::<Vec<u8>>();
+        .collect::<Result<Vec<[u8; 2]>, BackendErr>>()?
+        .concat();
 
-    Ok(Origin::with_offset(origin_offset, assembled_instructions))
+    Ok(Origin::with_offset(VECTOR_TABLE_OFFSET, bytes))
 }
 
 /// Mos6502Assembler functions as a wrapper struct to facilitate an
 /// implementation of the Assembler trait for the 6502 instruction set.
 #[derive(Default)]
-pub struct Mos6502Assembler {}
+pub struct Mos6502Assembler {
+    cpu: Cpu,
+    diagnostics: Option<DiagnosticSender>,
+    relax_branches: bool,
+}
 
 impl Mos6502Assembler {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// with_cpu sets the member of the 6502 family that instructions are
+    /// assembled for, defaulting to the NMOS 6502.
+    pub fn with_cpu(mut self, cpu: Cpu) -> Self {
+        self.cpu = cpu;
+        self
+    }
+
+    /// with_diagnostics sets the channel that non-fatal diagnostics, like
+    /// warning-level assertions and `.print` messages, are reported over.
+    pub fn with_diagnostics(mut self, diagnostics: DiagnosticSender) -> Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    /// with_branch_relaxation enables rewriting any conditional branch whose
+    /// label target is out of range as an inverted branch over a `jmp`.
+    pub fn with_branch_relaxation(mut self) -> Self {
+        self.relax_branches = true;
+        self
+    }
 }
 
-impl Assembler<Vec<Origin<UnparsedTokenStream>>, AssembledOrigins, BackendErr>
-    for Mos6502Assembler
-{
-    fn assemble(
+impl Mos6502Assembler {
+    /// assemble_counting_cycles assembles the source, additionally summing
+    /// the cycles taken by the instructions from the start label up to the
+    /// end label.
+    pub fn assemble_counting_cycles(
         &self,
         source: Vec<Origin<UnparsedTokenStream>>,
-    ) -> AssemblerResult<AssembledOrigins, BackendErr> {
-        // Parse a stream of text tokens into their corresponding types.
-        let token_instructions: Vec<Origin<Token6502InstStream>> = source
-            .into_iter()
-            .map(parse_string_instructions_origin_to_token_instructions_origin)
-            .collect::<Result<Vec<Origin<Token6502InstStream>>, parser::ParseErr>>()
-            .map_err(|e| BackendErr::Parse(e.to_string()))?;
-
-        // Annotate parsed tokens with their position and offsets. Then collect
-        // the symbols and instructions into a vector of origin-aligned offsets.
-        let (symbol_tables, instructions): (
-            Vec<SymbolTable>,
-            Vec<Origin<MemoryAligned6502Stream>>,
-        ) = token_instructions
-            .into_iter()
-            .map(convert_token_instructions_origins_to_positional_tokens_origin)
-            .map(generate_symbol_table_from_instructions_origin)
-            .unzip();
-
-        // Join all the origin's symbol tables into a global symbol table
-        let symbol_table: SymbolTable = SymbolTable::from(symbol_tables);
-
-        let opcode_origins = instructions
-            .into_iter()
-            // strip empty origins
-            .filter(|origin| !origin.instructions.is_empty())
-            .map(|origin| assemble_origin(&symbol_table, origin))
-            .collect::<Result<Vec<Origin<Vec<u8>>>, BackendErr>>()?;
-
-        Ok(opcode_origins)
+        start: &str,
+        end: &str,
+    ) -> AssemblerResult<(AssembledOrigins, CycleCount), BackendErr> {
+        self.assemble_inspecting(source, |symbol_table, token_origins, instructions| {
+            count_cycles(symbol_table, token_origins, instructions, start, end)
+        })
+    }
+
+    /// Assembles the source, returning the assembled origins alongside the
+    /// result of the provided function, which inspects the parsed and laid
+    /// out instructions against the resolved symbols.
+    fn assemble_inspecting<T>(
+        &self,
+        mut source: Vec<Origin<UnparsedTokenStream>>,
+        inspect: impl FnOnce(
+            &SymbolTable,
+            &[Origin<Token6502InstStream>],
+            &[Origin<MemoryAligned6502Stream>],
+        ) -> Result<T, BackendErr>,
+    ) -> AssemblerResult<(AssembledOrigins, T), BackendErr> {
+        let vectors = take_vectors(&mut source)?;
+        let mut token_instructions = parse_origins(self, source)?;
+        apply_processor_state(self.cpu, &mut token_instructions, self.diagnostics.as_ref())?;
+        let (symbol_table, directives, instructions) = layout(self, &mut token_instructions)?;
+
+        // With all labels resolved, verify any assertions hold and report any
+        // user-emitted messages.
+        evaluate_directives(
+            &symbol_table,
+            directives,
+            self.diagnostics.as_ref(),
+            &|start, end| {
+                count_cycles(
+                    &symbol_table,
+                    &token_instructions,
+                    &instructions,
+                    start,
+                    end,
+                )
+                .map(|count| count.base)
+            },
+        )?;
+        let inspected = inspect(&symbol_table, &token_instructions, &instructions)?;
+
+        let mut opcode_origins = encode_origins(self, &symbol_table, instructions)?;
+        if let Some(vectors) = vectors {
+            let vector_origin = assemble_vectors(&symbol_table, vectors, &opcode_origins)?;
+            opcode_origins.push(vector_origin);
+        }
+
+        Ok((opcode_origins, inspected))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl InstructionSet for Mos6502Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        let mut instruction = parse_instruction(source).map_err(|e| e.to_string())?;
+        if self.relax_branches && instruction.branch_form == BranchForm::Short {
+            instruction.branch_form = BranchForm::Relaxable;
+        }
+
+        Ok(instruction)
+    }
+
+    /// Operands that resolve to the zero page shrink their instruction. The
+    /// first pass sizes all references at their widest and sizes only ever
+    /// shrink from there, with the exception of relaxed branches which only
+    /// ever grow once.
+    fn size_of(&self, symbol_table: &SymbolTable, instruction: &Instruction) -> usize {
+        size_of_instruction(symbol_table, instruction)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let ioc = InstructionOrConstant::Instruction(instruction.clone());
+
+        dereference_instructions_to_static_instructions(
+            symbol_table,
+            Positional::with_position(position, ioc),
+        )
+        .map(|iocs| {
+            iocs.into_iter()
+                .flat_map(|ioc| match ioc {
+                    InstructionOrConstant::Instruction(si) => si.emit(),
+                    InstructionOrConstant::Constant(v) => v.emit(),
+                })
+                .collect()
+        })
+    }
 
-    #[test]
-    fn should_reify_a_16_bit_lebytesvalue() {
-        let lebev = LeByteEncodedValue::new(vec![0, 128]);
+    fn address_width(&self) -> u32 {
+        match self.cpu {
+            Cpu::Wdc65816 => 24,
+            _ => 16,
+        }
+    }
+
+    /// Marks a relaxable branch whose target is out of range as a long
+    /// branch. Long branches are never shortened again.
+    fn relax(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &mut Instruction,
+    ) -> bool {
+        let out_of_range = match (&instruction.amor, instruction.branch_form) {
+            (AddressingModeOrReference::Label(l), BranchForm::Relaxable) => matches!(
+                relative_offset_to::<i8>(symbol_table, position, 2, l),
+                Err(BackendErr::BranchOutOfRange(..))
+            ),
+            _ => false,
+        };
+
+        if out_of_range {
+            instruction.branch_form = BranchForm::Long;
+        }
+        out_of_range
+    }
+}
 
-        assert_eq!(Ok(0x8000), Reify::<u16>::reify(&lebev))
+impl Assembler<Vec<Origin<UnparsedTokenStream>>, AssembledOrigins, BackendErr>
+    for Mos6502Assembler
+{
+    fn assemble(
+        &self,
+        source: Vec<Origin<UnparsedTokenStream>>,
+    ) -> AssemblerResult<AssembledOrigins, BackendErr> {
+        self.assemble_inspecting(source, |_, _, _| Ok(()))
+            .map(|(origins, _)| origins)
     }
 }
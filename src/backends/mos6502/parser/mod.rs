@@ -1,14 +1,13 @@
 use crate::backends::mos6502::instruction_set::addressing_mode::{
-    AddressingModeOrReference, Symbol,
-};
-use crate::backends::mos6502::instruction_set::Instruction;
-use isa_mos6502::{
-    addressing_mode::{AddressingMode, AddressingModeType},
-    mnemonic::Mnemonic,
+    AddressingMode, AddressingModeOrReference, BitBranch, ExtendedAddressingModeType,
+    ExtendedSymbol, Symbol, ZeroPageOperand,
 };
+use crate::backends::mos6502::instruction_set::mnemonic::Mnemonic;
+use crate::backends::mos6502::instruction_set::{BranchForm, Instruction, OperandSize};
+use isa_mos6502::addressing_mode::AddressingModeType;
 use parcel::parsers::character::*;
 use parcel::prelude::v1::*;
-use parcel::{join, left, one_or_more, optional, right, take_n, zero_or_more};
+use parcel::{join, left, one_of, one_or_more, optional, right, take_n, zero_or_more};
 use std::convert::TryFrom;
 
 use crate::parser::*;
@@ -36,48 +35,185 @@ impl std::fmt::Display for ParseErr {
 }
 
 pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    bit_branch_instruction().or(standard_instruction)
+}
+
+/// Matches the BBR and BBS bit branches, which uniquely take both a zero page
+/// and branch target operand.
+fn bit_branch_instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(
+            zero_or_more(non_newline_whitespace()),
+            mnemonic().predicate(|m| m.is_bit_branch()),
+        )),
+        left(join(
+            right(join(one_or_more(non_newline_whitespace()), bit_branch())),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, amor)| Instruction::new(m, amor))
+}
+
+fn standard_instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
     join(
-        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        right(join(
+            zero_or_more(non_newline_whitespace()),
+            join(
+                mnemonic()
+                    .map(|m| (m, BranchForm::Short))
+                    .or(|| pseudo_branch_mnemonic().map(|m| (m, BranchForm::Relaxable))),
+                optional(operand_size_suffix()),
+            ),
+        )),
         left(join(
             optional(right(join(
                 one_or_more(non_newline_whitespace()),
-                address_mode(),
+                join(optional(operand_size_prefix()), address_mode()),
             ))),
             zero_or_more(non_newline_whitespace()),
         )),
     )
-    .map(|(m, a)| match a {
-        Some(amor) => Instruction::new(m, amor),
-        None => Instruction::new(
-            m,
-            AddressingModeOrReference::AddressingMode(AddressingMode::Implied),
-        ),
+    .map(|(((m, branch_form), suffix), a)| {
+        let (prefix, amor) = match a {
+            Some((prefix, amor)) => (prefix, amor),
+            None => (
+                None,
+                AddressingModeOrReference::AddressingMode(AddressingMode::Implied),
+            ),
+        };
+
+        let inst = Instruction::new(m, amor).with_branch_form(branch_form);
+        // an operand prefix takes precedence over a mnemonic suffix.
+        match prefix.or(suffix) {
+            Some(size) => inst.with_operand_size(size),
+            None => inst,
+        }
     })
 }
 
 fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
-    take_n(alphabetic(), 3)
-        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+    // bit instructions, like `rmb0`, are suffixed with the bit they operate on.
+    join(take_n(alphabetic(), 3), optional(digit(8)))
+        .map(|(m, bit)| m.into_iter().chain(bit).collect::<String>())
+        .map(|m| Mnemonic::try_from(m.as_str()))
         .predicate(|res| res.is_ok())
         .map(|res| res.unwrap())
 }
 
+/// Matches the `jxx` pseudo-mnemonics, returning the conditional branch that
+/// each is relaxed from.
+fn pseudo_branch_mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    take_n(alphabetic(), 3)
+        .map(
+            |m| match m.into_iter().collect::<String>().to_lowercase().as_str() {
+                "jcc" => Some(Mnemonic::BCC),
+                "jcs" => Some(Mnemonic::BCS),
+                "jeq" => Some(Mnemonic::BEQ),
+                "jne" => Some(Mnemonic::BNE),
+                "jmi" => Some(Mnemonic::BMI),
+                "jpl" => Some(Mnemonic::BPL),
+                "jvc" => Some(Mnemonic::BVC),
+                "jvs" => Some(Mnemonic::BVS),
+                _ => None,
+            },
+        )
+        .predicate(|m| m.is_some())
+        .map(|m| m.unwrap())
+}
+
+/// Matches a `.b`, `.w` or `.l` mnemonic suffix, forcing a zero page,
+/// absolute or long operand respectively.
+fn operand_size_suffix<'a>() -> impl parcel::Parser<'a, &'a [char], OperandSize> {
+    right(join(
+        expect_character('.'),
+        one_of(vec![
+            expect_character('b'),
+            expect_character('B'),
+            expect_character('w'),
+            expect_character('W'),
+            expect_character('l'),
+            expect_character('L'),
+        ]),
+    ))
+    .map(|c| match c {
+        'b' | 'B' => OperandSize::Byte,
+        'l' | 'L' => OperandSize::Long,
+        _ => OperandSize::Word,
+    })
+}
+
+/// Matches a `z:`, `a:` or `f:` operand prefix, forcing a zero page, absolute
+/// or long operand respectively.
+fn operand_size_prefix<'a>() -> impl parcel::Parser<'a, &'a [char], OperandSize> {
+    left(join(
+        one_of(vec![
+            expect_character('z'),
+            expect_character('Z'),
+            expect_character('a'),
+            expect_character('A'),
+            expect_character('f'),
+            expect_character('F'),
+        ]),
+        expect_character(':'),
+    ))
+    .map(|c| match c {
+        'z' | 'Z' => OperandSize::Byte,
+        'f' | 'F' => OperandSize::Long,
+        _ => OperandSize::Word,
+    })
+}
+
 fn address_mode<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
     accumulator()
         .or(zeropage)
         .or(zeropage_x_indexed)
         .or(zeropage_y_indexed)
+        .or(stack_relative)
+        .or(block_move)
         .or(absolute_x_indexed)
         .or(absolute_y_indexed)
         .or(x_indexed_indirect)
         .or(indirect_y_indexed)
+        .or(stack_relative_indirect_y_indexed)
         .or(absolute)
+        .or(absolute_long_x_indexed)
+        .or(absolute_long)
         .or(immediate)
         .or(indirect)
+        .or(indirect_long)
         .or(relative)
         .or(|| label().map(AddressingModeOrReference::Label))
 }
 
+fn bit_branch<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    let separator = || {
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        )
+    };
+
+    join(
+        left(join(unsigned8(), separator())),
+        right(join(expect_character('*'), signed8())),
+    )
+    .map(|(zp, offset)| {
+        AddressingModeOrReference::AddressingMode(AddressingMode::ZeroPageRelative(zp, offset))
+    })
+    .or(move || {
+        join(
+            left(join(
+                unsigned8()
+                    .map(ZeroPageOperand::Address)
+                    .or(|| symbol().map(ZeroPageOperand::Symbol)),
+                separator(),
+            )),
+            label(),
+        )
+        .map(|(zp, target)| AddressingModeOrReference::BitBranch(BitBranch::new(zp, target)))
+    })
+}
+
 fn label<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
     one_or_more(alphabetic()).map(|l| l.into_iter().collect())
 }
@@ -87,8 +223,12 @@ fn symbol<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
 }
 
 fn accumulator<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
-    expect_character('A')
-        .map(|_| AddressingModeOrReference::AddressingMode(AddressingMode::Accumulator))
+    // guard against matching the leading character of a label such as `Again`.
+    left(join(
+        expect_character('A'),
+        non_newline_whitespace().or(eof),
+    ))
+    .map(|_| AddressingModeOrReference::AddressingMode(AddressingMode::Accumulator))
 }
 
 fn absolute<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
@@ -101,6 +241,18 @@ fn absolute_x_indexed<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingMod
         join(expect_character(','), expect_character('X')),
     ))
     .map(|h| AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteIndexedWithX(h)))
+    .or(|| {
+        left(join(
+            symbol(),
+            join(expect_character(','), expect_character('X')),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::Symbol(Symbol::new(
+                AddressingModeType::AbsoluteIndexedWithX,
+                sym,
+            ))
+        })
+    })
 }
 
 fn absolute_y_indexed<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
@@ -109,11 +261,40 @@ fn absolute_y_indexed<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingMod
         join(expect_character(','), expect_character('Y')),
     ))
     .map(|h| AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteIndexedWithY(h)))
+    .or(|| {
+        left(join(
+            symbol(),
+            join(expect_character(','), expect_character('Y')),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::Symbol(Symbol::new(
+                AddressingModeType::AbsoluteIndexedWithY,
+                sym,
+            ))
+        })
+    })
 }
 
 fn immediate<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
     right(join(expect_character('#'), unsigned8()))
         .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::Immediate(u)))
+        .or(|| {
+            right(join(expect_character('#'), unsigned16())).map(|u| {
+                AddressingModeOrReference::AddressingMode(AddressingMode::ImmediateWord(u))
+            })
+        })
+        .or(|| {
+            right(join(
+                join(expect_character('#'), expect_character('^')),
+                symbol(),
+            ))
+            .map(|sym| {
+                AddressingModeOrReference::ExtendedSymbol(ExtendedSymbol::new(
+                    ExtendedAddressingModeType::BankByte,
+                    sym,
+                ))
+            })
+        })
         .or(|| {
             right(join(expect_character('#'), symbol())).map(|sym| {
                 AddressingModeOrReference::Symbol(Symbol::new(AddressingModeType::Immediate, sym))
@@ -127,6 +308,15 @@ fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReferen
         left(join(unsigned16(), expect_character(')'))),
     ))
     .map(|bytes| AddressingModeOrReference::AddressingMode(AddressingMode::Indirect(bytes)))
+    .or(|| {
+        right(join(
+            expect_character('('),
+            left(join(symbol(), expect_character(')'))),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::Symbol(Symbol::new(AddressingModeType::Indirect, sym))
+        })
+    })
 }
 
 fn x_indexed_indirect<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
@@ -141,6 +331,21 @@ fn x_indexed_indirect<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingMod
         )),
     ))
     .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::XIndexedIndirect(u)))
+    .or(|| {
+        right(join(
+            expect_character('('),
+            left(join(
+                unsigned16(),
+                join(
+                    join(expect_character(','), expect_character('X')),
+                    expect_character(')'),
+                ),
+            )),
+        ))
+        .map(|u| {
+            AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteIndexedIndirect(u))
+        })
+    })
     .or(|| {
         right(join(
             expect_character('('),
@@ -218,3 +423,142 @@ fn zeropage_y_indexed<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingMod
     ))
     .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::ZeroPageIndexedWithY(u)))
 }
+
+fn absolute_long<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    unsigned24().map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteLong(u)))
+}
+
+fn absolute_long_x_indexed<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    left(join(
+        unsigned24(),
+        join(expect_character(','), expect_character('X')),
+    ))
+    .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteLongIndexedWithX(u)))
+}
+
+fn indirect_long<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    let indexed = || join(expect_character(','), expect_character('Y'));
+
+    right(join(
+        expect_character('['),
+        left(join(unsigned8(), join(expect_character(']'), indexed()))),
+    ))
+    .map(|u| {
+        AddressingModeOrReference::AddressingMode(
+            AddressingMode::DirectPageIndirectLongIndexedWithY(u),
+        )
+    })
+    .or(move || {
+        right(join(
+            expect_character('['),
+            left(join(symbol(), join(expect_character(']'), indexed()))),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::ExtendedSymbol(ExtendedSymbol::new(
+                ExtendedAddressingModeType::DirectPageIndirectLongIndexedWithY,
+                sym,
+            ))
+        })
+    })
+    .or(|| {
+        right(join(
+            expect_character('['),
+            left(join(unsigned8(), expect_character(']'))),
+        ))
+        .map(|u| {
+            AddressingModeOrReference::AddressingMode(AddressingMode::DirectPageIndirectLong(u))
+        })
+    })
+    .or(|| {
+        right(join(
+            expect_character('['),
+            left(join(unsigned16(), expect_character(']'))),
+        ))
+        .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::AbsoluteIndirectLong(u)))
+    })
+    .or(|| {
+        right(join(
+            expect_character('['),
+            left(join(symbol(), expect_character(']'))),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::ExtendedSymbol(ExtendedSymbol::new(
+                ExtendedAddressingModeType::DirectPageIndirectLong,
+                sym,
+            ))
+        })
+    })
+}
+
+fn stack_relative<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    left(join(
+        unsigned8(),
+        join(expect_character(','), expect_character('S')),
+    ))
+    .map(|u| AddressingModeOrReference::AddressingMode(AddressingMode::StackRelative(u)))
+    .or(|| {
+        left(join(
+            symbol(),
+            join(expect_character(','), expect_character('S')),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::ExtendedSymbol(ExtendedSymbol::new(
+                ExtendedAddressingModeType::StackRelative,
+                sym,
+            ))
+        })
+    })
+}
+
+fn stack_relative_indirect_y_indexed<'a>(
+) -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    let stack_indexed = || {
+        join(
+            join(expect_character(','), expect_character('S')),
+            join(
+                expect_character(')'),
+                join(expect_character(','), expect_character('Y')),
+            ),
+        )
+    };
+
+    right(join(
+        expect_character('('),
+        left(join(unsigned8(), stack_indexed())),
+    ))
+    .map(|u| {
+        AddressingModeOrReference::AddressingMode(
+            AddressingMode::StackRelativeIndirectIndexedWithY(u),
+        )
+    })
+    .or(move || {
+        right(join(
+            expect_character('('),
+            left(join(symbol(), stack_indexed())),
+        ))
+        .map(|sym| {
+            AddressingModeOrReference::ExtendedSymbol(ExtendedSymbol::new(
+                ExtendedAddressingModeType::StackRelativeIndirectIndexedWithY,
+                sym,
+            ))
+        })
+    })
+}
+
+/// Matches the source and destination bank operands of the 65816's `mvn` and
+/// `mvp` block moves.
+fn block_move<'a>() -> impl parcel::Parser<'a, &'a [char], AddressingModeOrReference> {
+    join(
+        left(join(
+            unsigned8(),
+            join(
+                expect_character(','),
+                zero_or_more(non_newline_whitespace()),
+            ),
+        )),
+        unsigned8(),
+    )
+    .map(|(src, dest)| {
+        AddressingModeOrReference::AddressingMode(AddressingMode::BlockMove(src, dest))
+    })
+}
@@ -230,3 +230,92 @@ fn zeropage_y_indexed_address_mode_should_match_valid_u8() {
         AddressingMode::ZeroPageIndexedWithY(0x1a)
     );
 }
+
+#[test]
+fn indexed_address_modes_should_match_symbols() {
+    use crate::backends::mos6502::instruction_set::{
+        addressing_mode::{AddressingModeOrReference, Symbol},
+        Instruction,
+    };
+    use isa_mos6502::addressing_mode::AddressingModeType;
+
+    let xinput = chars!("lda table,X");
+    let yinput = chars!("sta buffer,Y");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &xinput[xinput.len()..],
+            Instruction::new(
+                Mnemonic::LDA,
+                AddressingModeOrReference::Symbol(Symbol::new(
+                    AddressingModeType::AbsoluteIndexedWithX,
+                    "table".to_string()
+                ))
+            )
+        ))),
+        instruction().parse(&xinput)
+    );
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &yinput[yinput.len()..],
+            Instruction::new(
+                Mnemonic::STA,
+                AddressingModeOrReference::Symbol(Symbol::new(
+                    AddressingModeType::AbsoluteIndexedWithY,
+                    "buffer".to_string()
+                ))
+            )
+        ))),
+        instruction().parse(&yinput)
+    );
+}
+
+#[test]
+fn indirect_address_mode_should_match_symbols() {
+    use crate::backends::mos6502::instruction_set::{
+        addressing_mode::{AddressingModeOrReference, Symbol},
+        Instruction,
+    };
+    use isa_mos6502::addressing_mode::AddressingModeType;
+
+    let input = chars!("jmp (vector)");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            Instruction::new(
+                Mnemonic::JMP,
+                AddressingModeOrReference::Symbol(Symbol::new(
+                    AddressingModeType::Indirect,
+                    "vector".to_string()
+                ))
+            )
+        ))),
+        instruction().parse(&input)
+    );
+}
+
+#[test]
+fn operand_size_overrides_should_match_prefixes_and_suffixes() {
+    use crate::backends::mos6502::instruction_set::{Instruction, OperandSize};
+
+    let prefixed = chars!("lda a:$0010");
+    let suffixed = chars!("lda.b 0x1234,X");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &prefixed[prefixed.len()..],
+            Instruction::from((Mnemonic::LDA, AddressingMode::Absolute(0x10)))
+                .with_operand_size(OperandSize::Word)
+        ))),
+        instruction().parse(&prefixed)
+    );
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &suffixed[suffixed.len()..],
+            Instruction::from((Mnemonic::LDA, AddressingMode::AbsoluteIndexedWithX(0x1234)))
+                .with_operand_size(OperandSize::Byte)
+        ))),
+        instruction().parse(&suffixed)
+    );
+}
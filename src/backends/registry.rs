@@ -0,0 +1,180 @@
+use crate::backends::builtin;
+use crate::backends::layout::{assemble_source, AssembledOrigins, InstructionSet};
+use crate::backends::Backend;
+use crate::{AssemblerOptions, AssemblerResult};
+
+/// Registry holds the backends available for selection by name, seeded with
+/// those built into spasm.
+pub struct Registry {
+    backends: Vec<Box<dyn Backend>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            backends: builtin::all(),
+        }
+    }
+
+    /// with_backend registers a backend, replacing any already registered
+    /// under the same name.
+    pub fn with_backend<B: Backend + 'static>(mut self, backend: B) -> Self {
+        self.backends.retain(|b| b.name() != backend.name());
+        self.backends.push(Box::new(backend));
+        self
+    }
+
+    /// with_instruction_set registers a backend that assembles against the
+    /// instruction set returned by `new_instruction_set` for the cpu, if
+    /// any, selected by the options.
+    pub fn with_instruction_set<I, F>(self, name: &str, new_instruction_set: F) -> Self
+    where
+        I: InstructionSet,
+        F: Fn(Option<&str>) -> Result<I, String> + 'static,
+    {
+        self.with_backend(InstructionSetBackend::new(name, new_instruction_set))
+    }
+
+    /// Returns the backend registered under the provided name.
+    pub fn get(&self, name: &str) -> Option<&dyn Backend> {
+        self.backends
+            .iter()
+            .find(|backend| backend.name() == name)
+            .map(|backend| backend.as_ref())
+    }
+
+    /// Returns the names of each registered backend in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.backends.iter().map(|backend| backend.name()).collect()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// InstructionSetBackend adapts an [InstructionSet] to a [Backend],
+/// constructing the instruction set for each assembly.
+pub struct InstructionSetBackend<F> {
+    name: String,
+    new_instruction_set: F,
+}
+
+impl<F> InstructionSetBackend<F> {
+    pub fn new(name: &str, new_instruction_set: F) -> Self {
+        Self {
+            name: name.to_string(),
+            new_instruction_set,
+        }
+    }
+}
+
+impl<I, F> Backend for InstructionSetBackend<F>
+where
+    I: InstructionSet,
+    F: Fn(Option<&str>) -> Result<I, String>,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn assemble(
+        &self,
+        source: &str,
+        options: AssemblerOptions,
+    ) -> AssemblerResult<AssembledOrigins, String> {
+        let isa = (self.new_instruction_set)(options.cpu.as_deref())?;
+
+        assemble_source(&isa, source, options, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::layout::SymbolTable;
+    use crate::backends::BackendErr;
+    use crate::Origin;
+
+    /// A single instruction set, emitting the byte given as its operand.
+    struct Byte;
+
+    impl InstructionSet for Byte {
+        type Instruction = u8;
+
+        fn parse_instruction(&self, source: &str) -> Result<u8, String> {
+            source
+                .trim()
+                .strip_prefix("byte ")
+                .and_then(|operand| operand.trim().parse::<u8>().ok())
+                .ok_or_else(|| format!("unspecified parse error: {}", source))
+        }
+
+        fn size_of(&self, _: &SymbolTable, _: &u8) -> usize {
+            1
+        }
+
+        fn encode(
+            &self,
+            _: &SymbolTable,
+            _: usize,
+            instruction: &u8,
+        ) -> Result<Vec<u8>, BackendErr> {
+            Ok(vec![*instruction])
+        }
+    }
+
+    #[test]
+    fn should_list_the_builtin_backends_by_name() {
+        let registry = Registry::new();
+
+        assert_eq!(Some("mos6502"), registry.names().first().copied());
+        assert_eq!(
+            Some("avr"),
+            registry.get("avr").map(|backend| backend.name())
+        );
+        assert!(registry.get("table").is_none());
+    }
+
+    #[test]
+    fn should_assemble_against_a_registered_instruction_set() {
+        let registry = Registry::new().with_instruction_set("byte", |cpu| match cpu {
+            None => Ok(Byte),
+            Some(cpu) => Err(format!("unknown cpu for the byte backend: {}", cpu)),
+        });
+        let backend = registry.get("byte").unwrap();
+
+        assert_eq!(Some(&"byte"), registry.names().last());
+        assert_eq!(
+            Ok(vec![Origin::with_offset(0, vec![0x01, 0x02])]),
+            backend.assemble("byte 1\nbyte 2\n", AssemblerOptions::new())
+        );
+        assert_eq!(
+            Err("unknown cpu for the byte backend: z80".to_string()),
+            backend.assemble("byte 1\n", AssemblerOptions::new().with_cpu("z80"))
+        );
+    }
+
+    #[test]
+    fn should_replace_a_backend_registered_under_the_same_name() {
+        let registry = Registry::new().with_instruction_set("mos6502", |_| Ok(Byte));
+
+        assert_eq!(
+            1,
+            registry
+                .names()
+                .iter()
+                .filter(|name| **name == "mos6502")
+                .count()
+        );
+        assert_eq!(
+            Ok(vec![Origin::with_offset(0, vec![0xea])]),
+            registry
+                .get("mos6502")
+                .unwrap()
+                .assemble("byte 234\n", AssemblerOptions::new())
+        );
+    }
+}
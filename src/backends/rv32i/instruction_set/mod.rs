@@ -0,0 +1,435 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+use std::convert::TryFrom;
+
+/// Register represents one of the 32 integer registers, `x0` through `x31`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Register(u8);
+
+impl Register {
+    pub const ZERO: Self = Self(0);
+    pub const RA: Self = Self(1);
+
+    pub fn new(index: u8) -> Option<Self> {
+        (index < 32).then_some(Self(index))
+    }
+
+    fn code(self) -> u32 {
+        u32::from(self.0)
+    }
+}
+
+impl TryFrom<&str> for Register {
+    type Error = String;
+
+    /// Accepts both the numeric `x` names and the ABI names of a register.
+    fn try_from(src: &str) -> Result<Self, Self::Error> {
+        let name = src.to_lowercase();
+        let index = match name.as_str() {
+            "zero" => Some(0),
+            "ra" => Some(1),
+            "sp" => Some(2),
+            "gp" => Some(3),
+            "tp" => Some(4),
+            "fp" => Some(8),
+            _ => {
+                let (prefix, number) = name.split_at(1.min(name.len()));
+                let number = number.parse::<u8>().ok();
+                match (prefix, number) {
+                    // reject leading zeroes, such as `x01`.
+                    (_, Some(n)) if n.to_string() != name[1..] => None,
+                    ("x", Some(n)) => Some(n),
+                    ("t", Some(n @ 0..=2)) => Some(n + 5),
+                    ("t", Some(n @ 3..=6)) => Some(n + 25),
+                    ("s", Some(n @ 0..=1)) => Some(n + 8),
+                    ("s", Some(n @ 2..=11)) => Some(n + 16),
+                    ("a", Some(n @ 0..=7)) => Some(n + 10),
+                    _ => None,
+                }
+            }
+        };
+
+        index
+            .and_then(Self::new)
+            .ok_or_else(|| format!("unknown register: {}", src))
+    }
+}
+
+/// Immediate represents an immediate value, optionally wrapped in the
+/// `%hi` or `%lo` relocation operators selecting the upper 20 or lower 12
+/// bits of an address.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Immediate<V> {
+    Value(V),
+    Hi(V),
+    Lo(V),
+}
+
+impl<V> Immediate<V> {
+    fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Immediate<U>, E> {
+        match self {
+            Self::Value(v) => f(v).map(Immediate::Value),
+            Self::Hi(v) => f(v).map(Immediate::Hi),
+            Self::Lo(v) => f(v).map(Immediate::Lo),
+        }
+    }
+}
+
+impl Immediate<Resolved> {
+    /// Returns the value with any relocation operator applied.
+    fn evaluate(&self) -> Resolved {
+        match self {
+            Self::Value(v) => *v,
+            Self::Hi(v) => v.map(hi),
+            Self::Lo(v) => v.map(lo),
+        }
+    }
+}
+
+/// Operand represents either a register, an immediate or a memory operand,
+/// generalized over the value so that operands may be parsed with
+/// references and encoded once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Register(Register),
+    Immediate(Immediate<V>),
+    /// An offset from a base register, such as `8(sp)`.
+    Memory(Immediate<V>, Register),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        match self {
+            Self::Register(r) => Ok(Operand::Register(*r)),
+            Self::Immediate(imm) => imm.try_map(f).map(Operand::Immediate),
+            Self::Memory(imm, r) => imm.try_map(f).map(|imm| Operand::Memory(imm, *r)),
+        }
+    }
+}
+
+/// Instruction represents a single RV32I mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+    MisalignedTarget(i64),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Resolved represents a value that may still reference an unresolved
+/// symbol, in which case the longest expansion is selected and the value is
+/// zeroed.
+pub type Resolved = Option<i64>;
+
+/// Returns the upper 20 bits of a value, rounded to account for the sign
+/// extension of the lower 12 bits.
+fn hi(value: i64) -> i64 {
+    ((value + 0x800) >> 12) & 0xfffff
+}
+
+/// Returns the lower 12 bits of a value, sign-extended.
+fn lo(value: i64) -> i64 {
+    ((value & 0xfff) ^ 0x800) - 0x800
+}
+
+fn signed12(value: Resolved) -> Result<u32, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x800..=0x7ff => Ok(v as u32 & 0xfff),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn upper20(value: Resolved) -> Result<u32, EncodeErr> {
+    match value.unwrap_or(0) {
+        v @ -0x80000..=0xfffff => Ok(v as u32 & 0xfffff),
+        v => Err(EncodeErr::OutOfRange(v)),
+    }
+}
+
+fn r_type(funct7: u32, funct3: u32, rd: Register, rs1: Register, rs2: Register) -> u32 {
+    (funct7 << 25)
+        | (rs2.code() << 20)
+        | (rs1.code() << 15)
+        | (funct3 << 12)
+        | (rd.code() << 7)
+        | 0x33
+}
+
+fn i_type(opcode: u32, funct3: u32, rd: Register, rs1: Register, imm: u32) -> u32 {
+    (imm << 20) | (rs1.code() << 15) | (funct3 << 12) | (rd.code() << 7) | opcode
+}
+
+fn s_type(funct3: u32, rs1: Register, rs2: Register, imm: u32) -> u32 {
+    ((imm >> 5) << 25)
+        | (rs2.code() << 20)
+        | (rs1.code() << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1f) << 7)
+        | 0x23
+}
+
+fn u_type(opcode: u32, rd: Register, imm: u32) -> u32 {
+    (imm << 12) | (rd.code() << 7) | opcode
+}
+
+/// Returns the offset of a target from the provided position, zeroing an
+/// unresolved target.
+fn relative(target: Resolved, position: usize) -> i64 {
+    target.map_or(0, |t| t - position as i64)
+}
+
+fn b_type(funct3: u32, rs1: Register, rs2: Register, offset: i64) -> Result<u32, EncodeErr> {
+    match offset {
+        _ if offset % 2 != 0 => Err(EncodeErr::MisalignedTarget(offset)),
+        -0x1000..=0xffe => {
+            let imm = offset as u32;
+            Ok((((imm >> 12) & 1) << 31)
+                | (((imm >> 5) & 0x3f) << 25)
+                | (rs2.code() << 20)
+                | (rs1.code() << 15)
+                | (funct3 << 12)
+                | (((imm >> 1) & 0xf) << 8)
+                | (((imm >> 11) & 1) << 7)
+                | 0x63)
+        }
+        _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+    }
+}
+
+fn j_type(rd: Register, offset: i64) -> Result<u32, EncodeErr> {
+    match offset {
+        _ if offset % 2 != 0 => Err(EncodeErr::MisalignedTarget(offset)),
+        -0x100000..=0xffffe => {
+            let imm = offset as u32;
+            Ok((((imm >> 20) & 1) << 31)
+                | (((imm >> 1) & 0x3ff) << 21)
+                | (((imm >> 11) & 1) << 20)
+                | (((imm >> 12) & 0xff) << 12)
+                | (rd.code() << 7)
+                | 0x6f)
+        }
+        _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+    }
+}
+
+/// Returns the size, in bytes, of an instruction once any
+/// pseudo-instruction is expanded. Only `li` depends on its value, taking a
+/// single instruction when the value fits in 12 signed bits.
+pub fn size_of(mnemonic: Mnemonic, operands: &[Operand<Resolved>]) -> usize {
+    match (mnemonic, operands) {
+        (Mnemonic::LI, [_, Operand::Immediate(imm)]) => match imm.evaluate() {
+            Some(-0x800..=0x7ff) => 4,
+            _ => 8,
+        },
+        (Mnemonic::LA | Mnemonic::CALL, _) => 8,
+        _ => 4,
+    }
+}
+
+/// Encodes an instruction positioned at the provided offset against its
+/// resolved operands, expanding pseudo-instructions to the one or two
+/// instructions they stand for. Each instruction is emitted little-endian.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<Resolved>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    let words = match (mnemonic, operands) {
+        (M::LUI | M::AUIPC, [O::Register(rd), O::Immediate(imm)]) => {
+            let opcode = if mnemonic == M::LUI { 0x37 } else { 0x17 };
+            vec![u_type(opcode, *rd, upper20(imm.evaluate())?)]
+        }
+        (M::JAL, [O::Immediate(target)]) => {
+            vec![j_type(Register::RA, relative(target.evaluate(), position))?]
+        }
+        (M::JAL, [O::Register(rd), O::Immediate(target)]) => {
+            vec![j_type(*rd, relative(target.evaluate(), position))?]
+        }
+        (M::JALR, [O::Register(rs1)]) => vec![i_type(0x67, 0, Register::RA, *rs1, 0)],
+        (M::JALR, [O::Register(rd), O::Memory(offset, rs1)])
+        | (M::JALR, [O::Register(rd), O::Register(rs1), O::Immediate(offset)]) => {
+            vec![i_type(0x67, 0, *rd, *rs1, signed12(offset.evaluate())?)]
+        }
+        (
+            M::BEQ | M::BNE | M::BLT | M::BGE | M::BLTU | M::BGEU,
+            [O::Register(rs1), O::Register(rs2), O::Immediate(target)],
+        ) => {
+            let funct3 = match mnemonic {
+                M::BEQ => 0,
+                M::BNE => 1,
+                M::BLT => 4,
+                M::BGE => 5,
+                M::BLTU => 6,
+                _ => 7,
+            };
+            let offset = relative(target.evaluate(), position);
+            vec![b_type(funct3, *rs1, *rs2, offset)?]
+        }
+        (M::LB | M::LH | M::LW | M::LBU | M::LHU, [O::Register(rd), O::Memory(offset, rs1)]) => {
+            let funct3 = match mnemonic {
+                M::LB => 0,
+                M::LH => 1,
+                M::LW => 2,
+                M::LBU => 4,
+                _ => 5,
+            };
+            vec![i_type(
+                0x03,
+                funct3,
+                *rd,
+                *rs1,
+                signed12(offset.evaluate())?,
+            )]
+        }
+        (M::SB | M::SH | M::SW, [O::Register(rs2), O::Memory(offset, rs1)]) => {
+            let funct3 = match mnemonic {
+                M::SB => 0,
+                M::SH => 1,
+                _ => 2,
+            };
+            vec![s_type(funct3, *rs1, *rs2, signed12(offset.evaluate())?)]
+        }
+        (M::SLLI | M::SRLI | M::SRAI, [O::Register(rd), O::Register(rs1), O::Immediate(shamt)]) => {
+            let shamt = match shamt.evaluate().unwrap_or(0) {
+                shamt @ 0..=31 => shamt as u32,
+                shamt => return Err(EncodeErr::OutOfRange(shamt)),
+            };
+            let (funct3, funct7) = match mnemonic {
+                M::SLLI => (1, 0x00),
+                M::SRLI => (5, 0x00),
+                _ => (5, 0x20),
+            };
+            vec![i_type(0x13, funct3, *rd, *rs1, (funct7 << 5) | shamt)]
+        }
+        (
+            M::ADDI | M::SLTI | M::SLTIU | M::XORI | M::ORI | M::ANDI,
+            [O::Register(rd), O::Register(rs1), O::Immediate(imm)],
+        ) => {
+            let funct3 = match mnemonic {
+                M::ADDI => 0,
+                M::SLTI => 2,
+                M::SLTIU => 3,
+                M::XORI => 4,
+                M::ORI => 6,
+                _ => 7,
+            };
+            vec![i_type(0x13, funct3, *rd, *rs1, signed12(imm.evaluate())?)]
+        }
+        (
+            M::ADD | M::SUB | M::SLL | M::SLT | M::SLTU | M::XOR | M::SRL | M::SRA | M::OR | M::AND,
+            [O::Register(rd), O::Register(rs1), O::Register(rs2)],
+        ) => {
+            let (funct7, funct3) = match mnemonic {
+                M::ADD => (0x00, 0),
+                M::SUB => (0x20, 0),
+                M::SLL => (0x00, 1),
+                M::SLT => (0x00, 2),
+                M::SLTU => (0x00, 3),
+                M::XOR => (0x00, 4),
+                M::SRL => (0x00, 5),
+                M::SRA => (0x20, 5),
+                M::OR => (0x00, 6),
+                _ => (0x00, 7),
+            };
+            vec![r_type(funct7, funct3, *rd, *rs1, *rs2)]
+        }
+        // fence iorw, iorw
+        (M::FENCE, []) => vec![0x0ff0000f],
+        (M::ECALL, []) => vec![0x00000073],
+        (M::EBREAK, []) => vec![0x00100073],
+        (m, operands) => return pseudo(m, operands, position),
+    };
+
+    Ok(words.into_iter().flat_map(u32::to_le_bytes).collect())
+}
+
+/// Encodes a pseudo-instruction as the instructions it expands to.
+fn pseudo(mnemonic: Mnemonic, operands: &[Operand<Resolved>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    let zero = || O::Register(Register::ZERO);
+    let ra = || O::Register(Register::RA);
+    let value = |v: i64| O::Immediate(Immediate::Value(Some(v)));
+
+    // each expansion is encoded as its real instructions, with the offset of
+    // any following instruction advanced by the instructions preceding it.
+    let expansion: Vec<(Mnemonic, Vec<Operand<Resolved>>)> = match (mnemonic, operands) {
+        (M::NOP, []) => vec![(M::ADDI, vec![zero(), zero(), value(0)])],
+        (M::MV, [rd @ O::Register(_), rs @ O::Register(_)]) => {
+            vec![(M::ADDI, vec![rd.clone(), rs.clone(), value(0)])]
+        }
+        (M::NOT, [rd @ O::Register(_), rs @ O::Register(_)]) => {
+            vec![(M::XORI, vec![rd.clone(), rs.clone(), value(-1)])]
+        }
+        (M::NEG, [rd @ O::Register(_), rs @ O::Register(_)]) => {
+            vec![(M::SUB, vec![rd.clone(), zero(), rs.clone()])]
+        }
+        (M::LI, [rd @ O::Register(_), O::Immediate(imm)]) => match imm.evaluate() {
+            Some(v @ -0x800..=0x7ff) => vec![(M::ADDI, vec![rd.clone(), zero(), value(v)])],
+            Some(v @ -0x8000_0000..=0xffff_ffff) => {
+                // sign-extend the value as a 32-bit register would hold it.
+                let v = i64::from(v as u32 as i32);
+                vec![
+                    (M::LUI, vec![rd.clone(), value(hi(v))]),
+                    (M::ADDI, vec![rd.clone(), rd.clone(), value(lo(v))]),
+                ]
+            }
+            Some(v) => return Err(EncodeErr::OutOfRange(v)),
+            None => vec![
+                (M::LUI, vec![rd.clone(), value(0)]),
+                (M::ADDI, vec![rd.clone(), rd.clone(), value(0)]),
+            ],
+        },
+        (M::LA, [rd @ O::Register(_), O::Immediate(target)]) => {
+            let offset = relative(target.evaluate(), position);
+            vec![
+                (M::AUIPC, vec![rd.clone(), value(hi(offset))]),
+                (M::ADDI, vec![rd.clone(), rd.clone(), value(lo(offset))]),
+            ]
+        }
+        (M::CALL, [O::Immediate(target)]) => {
+            let offset = relative(target.evaluate(), position);
+            vec![
+                (M::AUIPC, vec![ra(), value(hi(offset))]),
+                (M::JALR, vec![ra(), ra(), value(lo(offset))]),
+            ]
+        }
+        (M::J, [target @ O::Immediate(_)]) => vec![(M::JAL, vec![zero(), target.clone()])],
+        (M::JR, [rs @ O::Register(_)]) => vec![(M::JALR, vec![zero(), rs.clone(), value(0)])],
+        (M::RET, []) => vec![(M::JALR, vec![zero(), ra(), value(0)])],
+        (M::BEQZ | M::BNEZ, [rs @ O::Register(_), target @ O::Immediate(_)]) => {
+            let branch = if mnemonic == M::BEQZ { M::BEQ } else { M::BNE };
+            vec![(branch, vec![rs.clone(), zero(), target.clone()])]
+        }
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    expansion
+        .into_iter()
+        .enumerate()
+        .map(|(i, (mnemonic, operands))| encode(mnemonic, &operands, position + (i * 4)))
+        .collect::<Result<Vec<Vec<u8>>, EncodeErr>>()
+        .map(|bytes| bytes.concat())
+}
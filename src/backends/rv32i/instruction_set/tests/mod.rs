@@ -0,0 +1,164 @@
+use crate::backends::rv32i::instruction_set::mnemonic::Mnemonic;
+use crate::backends::rv32i::instruction_set::{
+    encode, size_of, EncodeErr, Immediate, Operand, Register, Resolved,
+};
+use std::convert::TryFrom;
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], [$($word:expr),*]) => {
+        assert_eq!(Ok(words(&[$($word),*])), encode($mnemonic, &[$($operand),*], 0));
+    };
+}
+
+fn words(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+fn reg(name: &str) -> Operand<Resolved> {
+    Operand::Register(Register::try_from(name).unwrap())
+}
+
+fn imm(value: i64) -> Operand<Resolved> {
+    Operand::Immediate(Immediate::Value(Some(value)))
+}
+
+fn mem(offset: i64, base: &str) -> Operand<Resolved> {
+    Operand::Memory(
+        Immediate::Value(Some(offset)),
+        Register::try_from(base).unwrap(),
+    )
+}
+
+#[test]
+fn should_parse_numeric_and_abi_register_names() {
+    assert_eq!(Register::new(10), Register::try_from("a0").ok());
+    assert_eq!(Register::new(10), Register::try_from("x10").ok());
+    assert_eq!(Register::new(8), Register::try_from("fp").ok());
+    assert_eq!(Register::new(27), Register::try_from("s11").ok());
+    assert_eq!(Register::new(31), Register::try_from("t6").ok());
+    assert!(Register::try_from("x32").is_err());
+    assert!(Register::try_from("a8").is_err());
+    assert!(Register::try_from("x01").is_err());
+}
+
+#[test]
+fn should_encode_each_instruction_format() {
+    // R
+    gen_encode_test!(
+        Mnemonic::ADD,
+        [reg("a0"), reg("a1"), reg("a2")],
+        [0x00c58533]
+    );
+    gen_encode_test!(
+        Mnemonic::SUB,
+        [reg("t0"), reg("t1"), reg("t2")],
+        [0x407302b3]
+    );
+    // I
+    gen_encode_test!(
+        Mnemonic::ADDI,
+        [reg("a0"), reg("zero"), imm(5)],
+        [0x00500513]
+    );
+    gen_encode_test!(Mnemonic::LW, [reg("a0"), mem(8, "sp")], [0x00812503]);
+    gen_encode_test!(Mnemonic::SRAI, [reg("a0"), reg("a0"), imm(3)], [0x40355513]);
+    // S
+    gen_encode_test!(Mnemonic::SW, [reg("a0"), mem(-4, "sp")], [0xfea12e23]);
+    // U
+    gen_encode_test!(Mnemonic::LUI, [reg("a0"), imm(0x12345)], [0x12345537]);
+    // B
+    gen_encode_test!(Mnemonic::BEQ, [reg("a0"), reg("a1"), imm(8)], [0x00b50463]);
+    // J
+    gen_encode_test!(Mnemonic::JAL, [reg("ra"), imm(16)], [0x010000ef]);
+    gen_encode_test!(Mnemonic::ECALL, [], [0x00000073]);
+}
+
+#[test]
+fn should_range_check_immediates() {
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(2048)),
+        encode(Mnemonic::ADDI, &[reg("a0"), reg("a0"), imm(2048)], 0)
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(32)),
+        encode(Mnemonic::SLLI, &[reg("a0"), reg("a0"), imm(32)], 0)
+    );
+}
+
+#[test]
+fn should_encode_branches_and_jumps_relative_to_their_position() {
+    assert_eq!(
+        Ok(words(&[0xfe051ee3])),
+        encode(Mnemonic::BNEZ, &[reg("a0"), imm(0)], 4)
+    );
+    assert_eq!(Ok(words(&[0xff9ff06f])), encode(Mnemonic::J, &[imm(0)], 8));
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(4096)),
+        encode(Mnemonic::BEQ, &[reg("a0"), reg("a1"), imm(4096)], 0)
+    );
+    assert_eq!(
+        Err(EncodeErr::MisalignedTarget(3)),
+        encode(Mnemonic::J, &[imm(3)], 0)
+    );
+}
+
+#[test]
+fn should_apply_relocation_operators() {
+    let address = Some(0x12345fff);
+
+    gen_encode_test!(
+        Mnemonic::LUI,
+        [reg("a0"), Operand::Immediate(Immediate::Hi(address))],
+        [0x12346537]
+    );
+    gen_encode_test!(
+        Mnemonic::ADDI,
+        [
+            reg("a0"),
+            reg("a0"),
+            Operand::Immediate(Immediate::Lo(address))
+        ],
+        [0xfff50513]
+    );
+}
+
+#[test]
+fn should_expand_pseudo_instructions() {
+    gen_encode_test!(Mnemonic::NOP, [], [0x00000013]);
+    gen_encode_test!(Mnemonic::RET, [], [0x00008067]);
+    gen_encode_test!(Mnemonic::MV, [reg("a0"), reg("a1")], [0x00058513]);
+    gen_encode_test!(Mnemonic::LI, [reg("a0"), imm(-1)], [0xfff00513]);
+    gen_encode_test!(
+        Mnemonic::LI,
+        [reg("a0"), imm(0x12345678)],
+        [0x12345537, 0x67850513]
+    );
+    gen_encode_test!(
+        Mnemonic::LI,
+        [reg("a0"), imm(0xfff)],
+        [0x00001537, 0xfff50513]
+    );
+    gen_encode_test!(Mnemonic::CALL, [imm(0x1000)], [0x00001097, 0x000080e7]);
+    assert_eq!(
+        Ok(words(&[0x00000517, 0xff050513])),
+        encode(Mnemonic::LA, &[reg("a0"), imm(0)], 0x10)
+    );
+}
+
+#[test]
+fn should_size_li_by_its_value() {
+    assert_eq!(4, size_of(Mnemonic::LI, &[reg("a0"), imm(0x7ff)]));
+    assert_eq!(8, size_of(Mnemonic::LI, &[reg("a0"), imm(0x800)]));
+    assert_eq!(
+        8,
+        size_of(
+            Mnemonic::LI,
+            &[reg("a0"), Operand::Immediate(Immediate::Value(None))]
+        )
+    );
+    assert_eq!(8, size_of(Mnemonic::CALL, &[imm(0)]));
+    assert_eq!(
+        4,
+        size_of(Mnemonic::ADD, &[reg("a0"), reg("a1"), reg("a2")])
+    );
+}
@@ -0,0 +1,87 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+use crate::backends::rv32i::instruction_set::{
+    encode, size_of, EncodeErr, Immediate, Instruction, Operand,
+};
+use crate::backends::BackendErr;
+
+/// Rv32iAssembler implements the [InstructionSet] of the RV32I base integer
+/// instruction set.
+#[derive(Default)]
+pub struct Rv32iAssembler;
+
+impl Rv32iAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl InstructionSet for Rv32iAssembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    fn size_of(&self, symbol_table: &SymbolTable, instruction: &Instruction) -> usize {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| Ok::<_, ()>(resolve_value(symbol_table, v).ok())))
+            .collect::<Result<Vec<_>, ()>>()
+            .unwrap_or_default();
+
+        size_of(instruction.mnemonic, &operands)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_value(symbol_table, v).map(Some)))
+            .collect::<Result<Vec<_>, BackendErr>>()?;
+
+        encode(instruction.mnemonic, &operands, position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                BackendErr::BranchOutOfRange(target(&instruction.operands), offset)
+            }
+            EncodeErr::MisalignedTarget(offset) => BackendErr::Unspecified(format!(
+                "branch to {} is misaligned: {} bytes",
+                target(&instruction.operands),
+                offset
+            )),
+        })
+    }
+
+    fn address_width(&self) -> u32 {
+        32
+    }
+}
+
+/// Names the target of a branch or jump for reporting, being its final
+/// operand.
+fn target(operands: &[Operand<Value>]) -> String {
+    match operands.last() {
+        Some(Operand::Immediate(Immediate::Value(Value::Reference(id)))) => id.clone(),
+        operand => format!("{:?}", operand),
+    }
+}
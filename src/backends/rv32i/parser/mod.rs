@@ -0,0 +1,115 @@
+use crate::backends::rv32i::instruction_set::mnemonic::Mnemonic;
+use crate::backends::rv32i::instruction_set::{Immediate, Instruction, Operand, Register, Value};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches any operand. Memory operands are tried first so that the offset
+/// of `8(sp)` isn't taken as an immediate alone.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    memory()
+        .or(|| register().map(Operand::Register))
+        .or(|| immediate().map(Operand::Immediate))
+}
+
+/// Matches an optional offset followed by a parenthesized base register,
+/// such as `-4(sp)`, `%lo(table)(a0)` or `(t0)`.
+fn memory<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(optional(immediate()), parenthesized(register())).map(|(offset, base)| {
+        Operand::Memory(offset.unwrap_or(Immediate::Value(Value::Literal(0))), base)
+    })
+}
+
+fn parenthesized<'a, T: 'a>(
+    inner: impl parcel::Parser<'a, &'a [char], T>,
+) -> impl parcel::Parser<'a, &'a [char], T> {
+    right(join(
+        join(
+            expect_character('('),
+            zero_or_more(non_newline_whitespace()),
+        ),
+        left(join(
+            inner,
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(')'),
+            ),
+        )),
+    ))
+}
+
+/// Matches a register by its `x` or ABI name, such as `x10` or `a0`.
+#[allow(clippy::redundant_closure)]
+fn register<'a>() -> impl parcel::Parser<'a, &'a [char], Register> {
+    join(alphabetic(), zero_or_more(alphabetic().or(|| digit(10))))
+        .map(|(head, tail)| {
+            let name = std::iter::once(head).chain(tail).collect::<String>();
+            Register::try_from(name.as_str())
+        })
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+/// Matches a value, optionally wrapped in the `%hi` or `%lo` relocation
+/// operators.
+#[allow(clippy::redundant_closure)]
+fn immediate<'a>() -> impl parcel::Parser<'a, &'a [char], Immediate<Value>> {
+    right(join(expect_str("%hi"), parenthesized(value())))
+        .map(Immediate::Hi)
+        .or(|| right(join(expect_str("%lo"), parenthesized(value()))).map(Immediate::Lo))
+        .or(|| value().map(Immediate::Value))
+}
+
+#[allow(clippy::redundant_closure)]
+fn value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    join(optional(expect_character('-')), unsigned32())
+        .map(|(sign, v)| match sign {
+            Some(_) => Value::Literal(-i64::from(v)),
+            None => Value::Literal(i64::from(v)),
+        })
+        .or(|| name().map(Value::Reference))
+}
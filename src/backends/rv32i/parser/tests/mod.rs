@@ -0,0 +1,103 @@
+use crate::backends::rv32i::instruction_set::mnemonic::Mnemonic;
+use crate::backends::rv32i::instruction_set::{Immediate, Instruction, Operand, Register, Value};
+use crate::backends::rv32i::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+use std::convert::TryFrom;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, [$($operand:expr),*]) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, vec![$($operand),*])
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+fn reg(name: &str) -> Operand<Value> {
+    Operand::Register(Register::try_from(name).unwrap())
+}
+
+fn literal(value: i64) -> Immediate<Value> {
+    Immediate::Value(Value::Literal(value))
+}
+
+fn reference(name: &str) -> Value {
+    Value::Reference(name.to_string())
+}
+
+#[test]
+fn should_parse_instructions_without_operands() {
+    gen_inst_test!("  ecall", Mnemonic::ECALL, []);
+    gen_inst_test!("RET ", Mnemonic::RET, []);
+}
+
+#[test]
+fn should_parse_register_and_immediate_operands() {
+    gen_inst_test!(
+        "add a0, a1, x12",
+        Mnemonic::ADD,
+        [reg("a0"), reg("a1"), reg("x12")]
+    );
+    gen_inst_test!(
+        "addi sp,sp,-16",
+        Mnemonic::ADDI,
+        [reg("sp"), reg("sp"), Operand::Immediate(literal(-16))]
+    );
+    gen_inst_test!(
+        "beq t0, zero, loop",
+        Mnemonic::BEQ,
+        [
+            reg("t0"),
+            reg("zero"),
+            Operand::Immediate(Immediate::Value(reference("loop")))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_memory_operands() {
+    gen_inst_test!(
+        "lw ra, 12(sp)",
+        Mnemonic::LW,
+        [
+            reg("ra"),
+            Operand::Memory(literal(12), Register::try_from("sp").unwrap())
+        ]
+    );
+    gen_inst_test!(
+        "sb a0, (t1)",
+        Mnemonic::SB,
+        [
+            reg("a0"),
+            Operand::Memory(literal(0), Register::try_from("t1").unwrap())
+        ]
+    );
+}
+
+#[test]
+fn should_parse_relocation_operators() {
+    gen_inst_test!(
+        "lui a0, %hi(table)",
+        Mnemonic::LUI,
+        [
+            reg("a0"),
+            Operand::Immediate(Immediate::Hi(reference("table")))
+        ]
+    );
+    gen_inst_test!(
+        "lw a1, %lo(table)(a0)",
+        Mnemonic::LW,
+        [
+            reg("a1"),
+            Operand::Memory(
+                Immediate::Lo(reference("table")),
+                Register::try_from("a0").unwrap()
+            )
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+use crate::{Emitter, Origin};
+
+/// The logo compared against the boot ROM's copy at startup, refusing to
+/// boot the cartridge if they differ.
+const LOGO: [u8; 48] = [
+    0xce, 0xed, 0x66, 0x66, 0xcc, 0x0d, 0x00, 0x0b, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0c, 0x00, 0x0d,
+    0x00, 0x08, 0x11, 0x1f, 0x88, 0x89, 0x00, 0x0e, 0xdc, 0xcc, 0x6e, 0xe6, 0xdd, 0xdd, 0xd9, 0x99,
+    0xbb, 0xbb, 0x67, 0x63, 0x6e, 0x0e, 0xec, 0xcc, 0xdd, 0xdc, 0x99, 0x9f, 0xbb, 0xb9, 0x33, 0x3e,
+];
+
+const LOGO_OFFSET: usize = 0x104;
+const TITLE_OFFSET: usize = 0x134;
+const TITLE_LEN: usize = 16;
+const CARTRIDGE_TYPE_OFFSET: usize = 0x147;
+const ROM_SIZE_OFFSET: usize = 0x148;
+const HEADER_CHECKSUM_OFFSET: usize = 0x14d;
+const GLOBAL_CHECKSUM_OFFSET: usize = 0x14e;
+
+/// The smallest ROM, of two 16K banks, with each larger size doubling it.
+const MIN_ROM_SIZE: usize = 0x8000;
+/// The largest ROM size code, of 8M.
+const MAX_ROM_SIZE_CODE: u8 = 8;
+
+/// CartridgeHeader represents the fields of the cartridge header, at
+/// `0x100..0x150`, that are filled in once a ROM is assembled. The entry
+/// point and remaining fields, such as the licensee and RAM size, are left
+/// as assembled.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CartridgeHeader {
+    title: String,
+    cartridge_type: u8,
+}
+
+impl CartridgeHeader {
+    /// Returns a header for a ROM only cartridge with the provided title.
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            cartridge_type: 0,
+        }
+    }
+
+    /// with_cartridge_type sets the memory bank controller and any other
+    /// hardware present on the cartridge, such as `0x01` for an MBC1.
+    pub fn with_cartridge_type(mut self, cartridge_type: u8) -> Self {
+        self.cartridge_type = cartridge_type;
+        self
+    }
+
+    /// Emits assembled origins as a ROM starting at address 0, filling in
+    /// its header.
+    pub fn apply(&self, origins: &[Origin<Vec<u8>>]) -> Result<Vec<u8>, String> {
+        let mut rom = std::iter::once(Origin::new(vec![]))
+            .chain(origins.iter().cloned())
+            .collect::<Vec<_>>()
+            .emit();
+
+        self.fill(&mut rom)?;
+        Ok(rom)
+    }
+
+    /// Writes the logo, title, cartridge type and ROM size into a ROM,
+    /// padding it to the nearest ROM size, before computing its header and
+    /// global checksums.
+    fn fill(&self, rom: &mut Vec<u8>) -> Result<(), String> {
+        if self.title.len() > TITLE_LEN || !self.title.is_ascii() {
+            return Err(format!(
+                "cartridge title must be at most {} ascii characters: {}",
+                TITLE_LEN, self.title
+            ));
+        }
+
+        let rom_size_code = (0..=MAX_ROM_SIZE_CODE)
+            .find(|code| rom.len() <= MIN_ROM_SIZE << code)
+            .ok_or_else(|| format!("rom exceeds the maximum cartridge size: {}", rom.len()))?;
+        rom.resize(MIN_ROM_SIZE << rom_size_code, 0);
+
+        rom[LOGO_OFFSET..LOGO_OFFSET + LOGO.len()].copy_from_slice(&LOGO);
+        let title = &mut rom[TITLE_OFFSET..TITLE_OFFSET + TITLE_LEN];
+        title.fill(0);
+        title[..self.title.len()].copy_from_slice(self.title.as_bytes());
+        rom[CARTRIDGE_TYPE_OFFSET] = self.cartridge_type;
+        rom[ROM_SIZE_OFFSET] = rom_size_code;
+
+        rom[HEADER_CHECKSUM_OFFSET] = rom[TITLE_OFFSET..HEADER_CHECKSUM_OFFSET]
+            .iter()
+            .fold(0u8, |checksum, byte| {
+                checksum.wrapping_sub(*byte).wrapping_sub(1)
+            });
+
+        // the global checksum excludes its own bytes and is stored big-endian.
+        rom[GLOBAL_CHECKSUM_OFFSET..GLOBAL_CHECKSUM_OFFSET + 2].fill(0);
+        let global_checksum = rom.iter().fold(0u16, |checksum, byte| {
+            checksum.wrapping_add(u16::from(*byte))
+        });
+        rom[GLOBAL_CHECKSUM_OFFSET..GLOBAL_CHECKSUM_OFFSET + 2]
+            .copy_from_slice(&global_checksum.to_be_bytes());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_fill_in_the_header_of_a_rom() {
+        let mut rom = vec![0u8; 0x150];
+        rom[0x100..0x104].copy_from_slice(&[0x00, 0xc3, 0x50, 0x01]);
+
+        assert_eq!(
+            Ok(()),
+            CartridgeHeader::new("SPASM")
+                .with_cartridge_type(0x01)
+                .fill(&mut rom)
+        );
+
+        assert_eq!(0x8000, rom.len());
+        assert_eq!(&LOGO[..], &rom[0x104..0x134]);
+        assert_eq!(b"SPASM\0\0\0\0\0\0\0\0\0\0\0", &rom[0x134..0x144]);
+        assert_eq!([0x01, 0x00], rom[0x147..0x149]);
+
+        let header_checksum = rom[0x134..0x14d]
+            .iter()
+            .fold(0u8, |x, b| x.wrapping_sub(*b).wrapping_sub(1));
+        assert_eq!(header_checksum, rom[0x14d]);
+
+        let global_checksum = rom
+            .iter()
+            .enumerate()
+            .filter(|(offset, _)| !matches!(offset, 0x14e | 0x14f))
+            .fold(0u16, |x, (_, b)| x.wrapping_add(u16::from(*b)));
+        assert_eq!(global_checksum.to_be_bytes(), rom[0x14e..0x150]);
+    }
+
+    #[test]
+    fn should_emit_origins_from_the_start_of_the_rom() {
+        let origins = vec![Origin::with_offset(0x100, vec![0x00, 0xc3, 0x50, 0x01])];
+        let rom = CartridgeHeader::new("SPASM").apply(&origins).unwrap();
+
+        assert_eq!(0x8000, rom.len());
+        assert_eq!([0x00, 0xc3, 0x50, 0x01], rom[0x100..0x104]);
+    }
+
+    #[test]
+    fn should_pad_a_rom_to_the_nearest_rom_size() {
+        let mut rom = vec![0u8; 0x8001];
+
+        assert_eq!(Ok(()), CartridgeHeader::new("").fill(&mut rom));
+        assert_eq!(0x10000, rom.len());
+        assert_eq!(0x01, rom[0x148]);
+    }
+
+    #[test]
+    fn should_reject_titles_exceeding_the_title_field() {
+        let mut rom = vec![0u8; 0x150];
+
+        assert!(CartridgeHeader::new("A TITLE TOO LONG TO FIT")
+            .fill(&mut rom)
+            .is_err());
+    }
+}
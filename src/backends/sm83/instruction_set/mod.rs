@@ -0,0 +1,431 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+
+/// Register represents the 8-bit registers that may be named as an operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    A,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+}
+
+impl Register {
+    /// Returns the 3-bit encoding of the register within an opcode.
+    fn code(self) -> u8 {
+        match self {
+            Self::B => 0,
+            Self::C => 1,
+            Self::D => 2,
+            Self::E => 3,
+            Self::H => 4,
+            Self::L => 5,
+            Self::A => 7,
+        }
+    }
+}
+
+/// RegisterPair represents the 16-bit registers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterPair {
+    BC,
+    DE,
+    HL,
+    SP,
+    AF,
+}
+
+/// Condition represents the flag conditions of conditional jumps, calls and
+/// returns. The SM83 drops the parity and sign conditions of the Z80.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    NZ,
+    Z,
+    NC,
+    C,
+}
+
+impl Condition {
+    fn code(self) -> u8 {
+        match self {
+            Self::NZ => 0,
+            Self::Z => 1,
+            Self::NC => 2,
+            Self::C => 3,
+        }
+    }
+}
+
+/// Displacement represents the signed offset from the stack pointer taken by
+/// `ld hl, sp+e`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Displacement<V> {
+    pub negative: bool,
+    pub value: V,
+}
+
+impl<V> Displacement<V> {
+    pub fn new(negative: bool, value: V) -> Self {
+        Self { negative, value }
+    }
+}
+
+/// Operand represents each form of SM83 operand, generalized over its value
+/// so that operands may be parsed with references and encoded once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Register(Register),
+    RegisterPair(RegisterPair),
+    Condition(Condition),
+    /// An address held in a register pair, such as `(hl)`.
+    Indirect(RegisterPair),
+    /// The address held in `hl`, incremented once accessed, `(hl+)`.
+    IndirectIncrement,
+    /// The address held in `hl`, decremented once accessed, `(hl-)`.
+    IndirectDecrement,
+    /// The high page address offset by the `c` register, `(c)`.
+    Port,
+    /// The stack pointer offset by a signed displacement, such as `sp+4`.
+    StackOffset(Displacement<V>),
+    Immediate(V),
+    /// An absolute address, such as `(0xc000)`.
+    Address(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        let operand = match self {
+            Self::Register(r) => Operand::Register(*r),
+            Self::RegisterPair(rp) => Operand::RegisterPair(*rp),
+            Self::Condition(c) => Operand::Condition(*c),
+            Self::Indirect(rp) => Operand::Indirect(*rp),
+            Self::IndirectIncrement => Operand::IndirectIncrement,
+            Self::IndirectDecrement => Operand::IndirectDecrement,
+            Self::Port => Operand::Port,
+            Self::StackOffset(d) => {
+                Operand::StackOffset(Displacement::new(d.negative, f(&d.value)?))
+            }
+            Self::Immediate(v) => Operand::Immediate(f(v)?),
+            Self::Address(v) => Operand::Address(f(v)?),
+        };
+
+        Ok(operand)
+    }
+}
+
+/// Instruction represents a single SM83 mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// The 3-bit register encoding that addresses memory through `hl`.
+const INDIRECT_HL: u8 = 6;
+
+fn reg8(operand: &Operand<i64>) -> Option<u8> {
+    match operand {
+        Operand::Register(r) => Some(r.code()),
+        Operand::Indirect(RegisterPair::HL) => Some(INDIRECT_HL),
+        _ => None,
+    }
+}
+
+fn byte(value: i64) -> Result<u8, EncodeErr> {
+    match value {
+        -0x80..=0xff => Ok(value as u8),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn signed_byte(value: i64) -> Result<u8, EncodeErr> {
+    match value {
+        -0x80..=0x7f => Ok(value as i8 as u8),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn word(value: i64) -> Result<[u8; 2], EncodeErr> {
+    match value {
+        -0x8000..=0xffff => Ok((value as u16).to_le_bytes()),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn displacement(d: &Displacement<i64>) -> Result<u8, EncodeErr> {
+    signed_byte(if d.negative { -d.value } else { d.value })
+}
+
+/// Returns the low byte of an address within the high page, `0xff00` through
+/// `0xffff`, as taken by `ldh`. Addresses may also be given as the low byte
+/// alone.
+fn high_page(address: i64) -> Result<u8, EncodeErr> {
+    match address {
+        0..=0xff => Ok(address as u8),
+        0xff00..=0xffff => Ok((address & 0xff) as u8),
+        _ => Err(EncodeErr::OutOfRange(address)),
+    }
+}
+
+/// Returns the offset of a relative jump, positioned at the provided offset,
+/// to its target.
+fn relative(position: usize, target: i64) -> Result<u8, EncodeErr> {
+    let offset = target - (position as i64 + 2);
+    match offset {
+        -0x80..=0x7f => Ok(offset as i8 as u8),
+        _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+    }
+}
+
+/// Conditions are matched as registers where their syntax overlaps, leaving
+/// `c` to be interpreted as the carry condition by jumps, calls and returns.
+fn condition(operand: &Operand<i64>) -> Option<Condition> {
+    match operand {
+        Operand::Condition(c) => Some(*c),
+        Operand::Register(Register::C) => Some(Condition::C),
+        _ => None,
+    }
+}
+
+/// Returns the 2-bit encoding of a register pair among `bc`, `de`, `hl` and
+/// `sp`.
+fn pair_with_sp(pair: RegisterPair) -> Option<u8> {
+    match pair {
+        RegisterPair::BC => Some(0),
+        RegisterPair::DE => Some(1),
+        RegisterPair::HL => Some(2),
+        RegisterPair::SP => Some(3),
+        RegisterPair::AF => None,
+    }
+}
+
+/// As pair_with_sp, with `af` taking the place of `sp` as it does for stack
+/// operations.
+fn pair_with_af(pair: RegisterPair) -> Option<u8> {
+    match pair {
+        RegisterPair::AF => Some(3),
+        RegisterPair::SP => None,
+        pair => pair_with_sp(pair),
+    }
+}
+
+/// Encodes an instruction, positioned at the provided offset, against its
+/// resolved operands.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<i64>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+    use Register as R;
+    use RegisterPair as RP;
+
+    match (mnemonic, operands) {
+        (m, []) => implied(m),
+        (M::LD, [dst, src]) => load(dst, src),
+        (M::LDI, [O::Indirect(RP::HL), O::Register(R::A)]) => Ok(vec![0x22]),
+        (M::LDI, [O::Register(R::A), O::Indirect(RP::HL)]) => Ok(vec![0x2a]),
+        (M::LDD, [O::Indirect(RP::HL), O::Register(R::A)]) => Ok(vec![0x32]),
+        (M::LDD, [O::Register(R::A), O::Indirect(RP::HL)]) => Ok(vec![0x3a]),
+        (M::LDH, [O::Address(address), O::Register(R::A)]) => Ok(vec![0xe0, high_page(*address)?]),
+        (M::LDH, [O::Register(R::A), O::Address(address)]) => Ok(vec![0xf0, high_page(*address)?]),
+        (M::LDH, [O::Port, O::Register(R::A)]) => Ok(vec![0xe2]),
+        (M::LDH, [O::Register(R::A), O::Port]) => Ok(vec![0xf2]),
+        (M::PUSH, [O::RegisterPair(rp)]) => stack(0xc5, *rp),
+        (M::POP, [O::RegisterPair(rp)]) => stack(0xc1, *rp),
+        (M::ADD, [O::RegisterPair(RP::SP), O::Immediate(e)]) => Ok(vec![0xe8, signed_byte(*e)?]),
+        (M::ADD, [O::RegisterPair(RP::HL), O::RegisterPair(src)]) => {
+            let code = pair_with_sp(*src).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0x09 | (code << 4)])
+        }
+        (M::ADD, [O::Register(R::A), src]) => alu(0x80, 0xc6, src),
+        (M::ADC, [O::Register(R::A), src]) => alu(0x88, 0xce, src),
+        (M::SBC, [O::Register(R::A), src]) => alu(0x98, 0xde, src),
+        (M::SUB | M::AND | M::XOR | M::OR | M::CP, [O::Register(R::A), src])
+        | (M::SUB | M::AND | M::XOR | M::OR | M::CP, [src]) => {
+            let (base, immediate) = match mnemonic {
+                M::SUB => (0x90, 0xd6),
+                M::AND => (0xa0, 0xe6),
+                M::XOR => (0xa8, 0xee),
+                M::OR => (0xb0, 0xf6),
+                _ => (0xb8, 0xfe),
+            };
+            alu(base, immediate, src)
+        }
+        (M::INC | M::DEC, [O::RegisterPair(rp)]) => {
+            let base = if mnemonic == M::INC { 0x03 } else { 0x0b };
+            let code = pair_with_sp(*rp).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![base | (code << 4)])
+        }
+        (M::INC | M::DEC, [operand]) => {
+            let base = if mnemonic == M::INC { 0x04 } else { 0x05 };
+            let r = reg8(operand).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![base | (r << 3)])
+        }
+        (M::RLC | M::RRC | M::RL | M::RR | M::SLA | M::SRA | M::SWAP | M::SRL, [operand]) => {
+            let base = match mnemonic {
+                M::RLC => 0x00,
+                M::RRC => 0x08,
+                M::RL => 0x10,
+                M::RR => 0x18,
+                M::SLA => 0x20,
+                M::SRA => 0x28,
+                M::SWAP => 0x30,
+                _ => 0x38,
+            };
+            bit_operation(base, operand)
+        }
+        (M::BIT | M::RES | M::SET, [O::Immediate(bit), operand]) => {
+            let base = match mnemonic {
+                M::BIT => 0x40,
+                M::RES => 0x80,
+                _ => 0xc0,
+            };
+            match bit {
+                0..=7 => bit_operation(base | ((*bit as u8) << 3), operand),
+                _ => Err(EncodeErr::OutOfRange(*bit)),
+            }
+        }
+        (M::RST, [O::Immediate(target)]) => match target {
+            0..=0x38 if target % 8 == 0 => Ok(vec![0xc7 | *target as u8]),
+            _ => Err(EncodeErr::OutOfRange(*target)),
+        },
+        (M::JP, [O::Indirect(RP::HL) | O::RegisterPair(RP::HL)]) => Ok(vec![0xe9]),
+        (M::JP, [O::Immediate(target)]) => absolute(0xc3, *target),
+        (M::CALL, [O::Immediate(target)]) => absolute(0xcd, *target),
+        (M::JP | M::CALL, [cc, O::Immediate(target)]) => {
+            let base = if mnemonic == M::JP { 0xc2 } else { 0xc4 };
+            let cc = condition(cc).ok_or(EncodeErr::InvalidOperands)?;
+            absolute(base | (cc.code() << 3), *target)
+        }
+        (M::RET, [cc]) => {
+            let cc = condition(cc).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0xc0 | (cc.code() << 3)])
+        }
+        (M::JR, [O::Immediate(target)]) => Ok(vec![0x18, relative(position, *target)?]),
+        (M::JR, [cc, O::Immediate(target)]) => {
+            let cc = condition(cc).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0x20 | (cc.code() << 3), relative(position, *target)?])
+        }
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes the instructions that take no operands.
+fn implied(mnemonic: Mnemonic) -> Encoding {
+    use Mnemonic as M;
+
+    let opcodes = match mnemonic {
+        M::NOP => vec![0x00],
+        M::HALT => vec![0x76],
+        // stop is followed by a padding byte, which the cpu skips.
+        M::STOP => vec![0x10, 0x00],
+        M::DI => vec![0xf3],
+        M::EI => vec![0xfb],
+        M::DAA => vec![0x27],
+        M::CPL => vec![0x2f],
+        M::CCF => vec![0x3f],
+        M::SCF => vec![0x37],
+        M::RLCA => vec![0x07],
+        M::RLA => vec![0x17],
+        M::RRCA => vec![0x0f],
+        M::RRA => vec![0x1f],
+        M::RET => vec![0xc9],
+        M::RETI => vec![0xd9],
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(opcodes)
+}
+
+fn absolute(opcode: u8, target: i64) -> Encoding {
+    let [lo, hi] = word(target)?;
+    Ok(vec![opcode, lo, hi])
+}
+
+fn load(dst: &Operand<i64>, src: &Operand<i64>) -> Encoding {
+    use Operand as O;
+    use Register as R;
+    use RegisterPair as RP;
+
+    match (dst, src) {
+        (O::Register(R::A), O::Indirect(RP::BC)) => Ok(vec![0x0a]),
+        (O::Register(R::A), O::Indirect(RP::DE)) => Ok(vec![0x1a]),
+        (O::Register(R::A), O::IndirectIncrement) => Ok(vec![0x2a]),
+        (O::Register(R::A), O::IndirectDecrement) => Ok(vec![0x3a]),
+        (O::Register(R::A), O::Port) => Ok(vec![0xf2]),
+        (O::Register(R::A), O::Address(address)) => absolute(0xfa, *address),
+        (O::Indirect(RP::BC), O::Register(R::A)) => Ok(vec![0x02]),
+        (O::Indirect(RP::DE), O::Register(R::A)) => Ok(vec![0x12]),
+        (O::IndirectIncrement, O::Register(R::A)) => Ok(vec![0x22]),
+        (O::IndirectDecrement, O::Register(R::A)) => Ok(vec![0x32]),
+        (O::Port, O::Register(R::A)) => Ok(vec![0xe2]),
+        (O::Address(address), O::Register(R::A)) => absolute(0xea, *address),
+        (O::Address(address), O::RegisterPair(RP::SP)) => absolute(0x08, *address),
+        (O::RegisterPair(RP::SP), O::RegisterPair(RP::HL)) => Ok(vec![0xf9]),
+        (O::RegisterPair(RP::HL), O::StackOffset(d)) => Ok(vec![0xf8, displacement(d)?]),
+        (O::RegisterPair(rp), O::Immediate(value)) => {
+            let code = pair_with_sp(*rp).ok_or(EncodeErr::InvalidOperands)?;
+            absolute(0x01 | (code << 4), *value)
+        }
+        (dst, O::Immediate(value)) => match reg8(dst) {
+            Some(r) => Ok(vec![0x06 | (r << 3), byte(*value)?]),
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        // `ld (hl), (hl)` would otherwise encode `halt`.
+        (dst, src) => match (reg8(dst), reg8(src)) {
+            (Some(d), Some(s)) if d != INDIRECT_HL || s != INDIRECT_HL => {
+                Ok(vec![0x40 | (d << 3) | s])
+            }
+            _ => Err(EncodeErr::InvalidOperands),
+        },
+    }
+}
+
+fn stack(base: u8, pair: RegisterPair) -> Encoding {
+    let code = pair_with_af(pair).ok_or(EncodeErr::InvalidOperands)?;
+    Ok(vec![base | (code << 4)])
+}
+
+/// Encodes an 8-bit arithmetic or logical operation on the accumulator.
+fn alu(base: u8, immediate: u8, src: &Operand<i64>) -> Encoding {
+    if let Operand::Immediate(value) = src {
+        return Ok(vec![immediate, byte(*value)?]);
+    }
+
+    match reg8(src) {
+        Some(r) => Ok(vec![base | r]),
+        None => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes the `cb` prefixed rotates, shifts, swaps and bit operations.
+fn bit_operation(base: u8, operand: &Operand<i64>) -> Encoding {
+    match reg8(operand) {
+        Some(r) => Ok(vec![0xcb, base | r]),
+        None => Err(EncodeErr::InvalidOperands),
+    }
+}
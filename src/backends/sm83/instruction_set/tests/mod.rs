@@ -0,0 +1,199 @@
+use crate::backends::sm83::instruction_set::mnemonic::Mnemonic;
+use crate::backends::sm83::instruction_set::{
+    encode, Condition, Displacement, EncodeErr, Operand, Register, RegisterPair,
+};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &[$($operand),*], 0));
+    };
+}
+
+#[test]
+fn should_encode_z80_derived_instructions() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(Register::A), Operand::Immediate(0x12)],
+        vec![0x3e, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::B),
+            Operand::Indirect(RegisterPair::HL)
+        ],
+        vec![0x46]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::RegisterPair(RegisterPair::DE),
+            Operand::Immediate(0x1234)
+        ],
+        vec![0x11, 0x34, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::PUSH,
+        [Operand::RegisterPair(RegisterPair::AF)],
+        vec![0xf5]
+    );
+    gen_encode_test!(
+        Mnemonic::BIT,
+        [Operand::Immediate(7), Operand::Register(Register::H)],
+        vec![0xcb, 0x7c]
+    );
+    gen_encode_test!(
+        Mnemonic::JP,
+        [
+            Operand::Condition(Condition::NZ),
+            Operand::Immediate(0x0150)
+        ],
+        vec![0xc2, 0x50, 0x01]
+    );
+}
+
+#[test]
+fn should_encode_loads_that_differ_from_the_z80() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Address(0xc000), Operand::Register(Register::A)],
+        vec![0xea, 0x00, 0xc0]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(Register::A), Operand::Address(0xc000)],
+        vec![0xfa, 0x00, 0xc0]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Address(0xc000),
+            Operand::RegisterPair(RegisterPair::SP)
+        ],
+        vec![0x08, 0x00, 0xc0]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Port, Operand::Register(Register::A)],
+        vec![0xe2]
+    );
+}
+
+#[test]
+fn should_encode_incrementing_and_decrementing_loads() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::IndirectIncrement, Operand::Register(Register::A)],
+        vec![0x22]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(Register::A), Operand::IndirectDecrement],
+        vec![0x3a]
+    );
+    gen_encode_test!(
+        Mnemonic::LDI,
+        [
+            Operand::Register(Register::A),
+            Operand::Indirect(RegisterPair::HL)
+        ],
+        vec![0x2a]
+    );
+}
+
+#[test]
+fn should_encode_high_page_loads() {
+    gen_encode_test!(
+        Mnemonic::LDH,
+        [Operand::Address(0xff40), Operand::Register(Register::A)],
+        vec![0xe0, 0x40]
+    );
+    gen_encode_test!(
+        Mnemonic::LDH,
+        [Operand::Register(Register::A), Operand::Address(0x44)],
+        vec![0xf0, 0x44]
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0xc000)),
+        encode(
+            Mnemonic::LDH,
+            &[Operand::Address(0xc000), Operand::Register(Register::A)],
+            0
+        )
+    );
+}
+
+#[test]
+fn should_encode_stack_pointer_offsets() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::RegisterPair(RegisterPair::HL),
+            Operand::StackOffset(Displacement::new(false, 4))
+        ],
+        vec![0xf8, 0x04]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::RegisterPair(RegisterPair::HL),
+            Operand::StackOffset(Displacement::new(true, 2))
+        ],
+        vec![0xf8, 0xfe]
+    );
+    gen_encode_test!(
+        Mnemonic::ADD,
+        [
+            Operand::RegisterPair(RegisterPair::SP),
+            Operand::Immediate(-8)
+        ],
+        vec![0xe8, 0xf8]
+    );
+}
+
+#[test]
+fn should_encode_game_boy_specific_instructions() {
+    gen_encode_test!(Mnemonic::STOP, [], vec![0x10, 0x00]);
+    gen_encode_test!(Mnemonic::RETI, [], vec![0xd9]);
+    gen_encode_test!(
+        Mnemonic::SWAP,
+        [Operand::Register(Register::A)],
+        vec![0xcb, 0x37]
+    );
+    gen_encode_test!(
+        Mnemonic::JP,
+        [Operand::RegisterPair(RegisterPair::HL)],
+        vec![0xe9]
+    );
+}
+
+#[test]
+fn should_encode_relative_jumps() {
+    assert_eq!(
+        Ok(vec![0x20, 0xfe]),
+        encode(
+            Mnemonic::JR,
+            &[Operand::Condition(Condition::NZ), Operand::Immediate(0x10)],
+            0x10
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(0x100)),
+        encode(Mnemonic::JR, &[Operand::Immediate(0x102)], 0)
+    );
+}
+
+#[test]
+fn should_error_on_z80_only_operands() {
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Mnemonic::ADC,
+            &[
+                Operand::RegisterPair(RegisterPair::HL),
+                Operand::RegisterPair(RegisterPair::BC)
+            ],
+            0
+        )
+    );
+}
@@ -0,0 +1,162 @@
+use crate::backends::sm83::instruction_set::mnemonic::Mnemonic;
+use crate::backends::sm83::instruction_set::{
+    Condition, Displacement, Instruction, Operand, Register, RegisterPair, Value,
+};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+/// Matches any operand. The stack offset of `sp+4` is tried ahead of named
+/// operands so that `sp` isn't taken as a register pair alone.
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    parenthesized()
+        .or(|| stack_offset())
+        .or(|| literal().map(|v| Operand::Immediate(Value::Literal(v))))
+        .or(|| named())
+}
+
+/// Matches a register, register pair or condition by name, otherwise
+/// treating the name as a reference to a symbol or label. Register names
+/// are case-insensitive.
+fn named<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    name().map(|name| keyword(&name).unwrap_or_else(|| Operand::Immediate(Value::Reference(name))))
+}
+
+fn keyword(name: &str) -> Option<Operand<Value>> {
+    let operand = match name.to_lowercase().as_str() {
+        "a" => Operand::Register(Register::A),
+        "b" => Operand::Register(Register::B),
+        "c" => Operand::Register(Register::C),
+        "d" => Operand::Register(Register::D),
+        "e" => Operand::Register(Register::E),
+        "h" => Operand::Register(Register::H),
+        "l" => Operand::Register(Register::L),
+        "bc" => Operand::RegisterPair(RegisterPair::BC),
+        "de" => Operand::RegisterPair(RegisterPair::DE),
+        "hl" => Operand::RegisterPair(RegisterPair::HL),
+        "sp" => Operand::RegisterPair(RegisterPair::SP),
+        "af" => Operand::RegisterPair(RegisterPair::AF),
+        "nz" => Operand::Condition(Condition::NZ),
+        "z" => Operand::Condition(Condition::Z),
+        "nc" => Operand::Condition(Condition::NC),
+        _ => return None,
+    };
+
+    Some(operand)
+}
+
+/// Matches the stack pointer offset by a signed displacement, such as the
+/// `sp-2` of `ld hl, sp-2`.
+#[allow(clippy::redundant_closure)]
+fn stack_offset<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(
+        name().predicate(|name| name.to_lowercase() == "sp"),
+        join(
+            right(join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character('+').or(|| expect_character('-')),
+            )),
+            right(join(zero_or_more(non_newline_whitespace()), value())),
+        ),
+    )
+    .map(|(_, (sign, value))| Operand::StackOffset(Displacement::new(sign == '-', value)))
+}
+
+/// Matches any operand wrapped in parentheses, being an indirect register
+/// pair, the incrementing and decrementing `(hl+)` and `(hl-)`, the `(c)`
+/// port or an absolute address.
+fn parenthesized<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(
+        join(
+            expect_character('('),
+            zero_or_more(non_newline_whitespace()),
+        ),
+        left(join(
+            indirect(),
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(')'),
+            ),
+        )),
+    ))
+}
+
+#[allow(clippy::redundant_closure)]
+fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    unsigned16()
+        .map(|v| Operand::Address(Value::Literal(i64::from(v))))
+        .or(|| {
+            join(
+                name(),
+                optional(expect_character('+').or(|| expect_character('-'))),
+            )
+            .map(|(name, step)| {
+                let operand = keyword(&name);
+                match (name.to_lowercase().as_str(), operand, step) {
+                    ("hl", _, Some('+')) | ("hli", _, None) => Some(Operand::IndirectIncrement),
+                    ("hl", _, Some(_)) | ("hld", _, None) => Some(Operand::IndirectDecrement),
+                    (_, Some(Operand::Register(Register::C)), None) => Some(Operand::Port),
+                    (_, Some(Operand::RegisterPair(rp)), None) if rp != RegisterPair::AF => {
+                        Some(Operand::Indirect(rp))
+                    }
+                    (_, None, None) => Some(Operand::Address(Value::Reference(name))),
+                    _ => None,
+                }
+            })
+            .predicate(|operand| operand.is_some())
+            .map(|operand| operand.unwrap())
+        })
+}
+
+/// Matches a literal, allowing a leading `-` for the signed operand of
+/// `add sp, e`.
+fn literal<'a>() -> impl parcel::Parser<'a, &'a [char], i64> {
+    join(optional(expect_character('-')), unsigned16()).map(|(sign, v)| match sign {
+        Some(_) => -i64::from(v),
+        None => i64::from(v),
+    })
+}
@@ -0,0 +1,101 @@
+use crate::backends::sm83::instruction_set::mnemonic::Mnemonic;
+use crate::backends::sm83::instruction_set::{
+    Condition, Displacement, Instruction, Operand, Register, RegisterPair, Value,
+};
+use crate::backends::sm83::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, $operands:expr) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, $operands)
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_instructions_without_operands() {
+    gen_inst_test!("  stop", Mnemonic::STOP, vec![]);
+    gen_inst_test!("RETI  ", Mnemonic::RETI, vec![]);
+}
+
+#[test]
+fn should_parse_registers_conditions_and_references() {
+    gen_inst_test!(
+        "ld a, $12",
+        Mnemonic::LD,
+        vec![
+            Operand::Register(Register::A),
+            Operand::Immediate(Value::Literal(0x12))
+        ]
+    );
+    gen_inst_test!(
+        "jr nz, loop",
+        Mnemonic::JR,
+        vec![
+            Operand::Condition(Condition::NZ),
+            Operand::Immediate(Value::Reference("loop".to_string()))
+        ]
+    );
+    gen_inst_test!(
+        "add sp, -8",
+        Mnemonic::ADD,
+        vec![
+            Operand::RegisterPair(RegisterPair::SP),
+            Operand::Immediate(Value::Literal(-8))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_parenthesized_operands() {
+    gen_inst_test!(
+        "ld (hl+), a",
+        Mnemonic::LD,
+        vec![Operand::IndirectIncrement, Operand::Register(Register::A)]
+    );
+    gen_inst_test!(
+        "ld a, ( HLD )",
+        Mnemonic::LD,
+        vec![Operand::Register(Register::A), Operand::IndirectDecrement]
+    );
+    gen_inst_test!(
+        "ldh (c), a",
+        Mnemonic::LDH,
+        vec![Operand::Port, Operand::Register(Register::A)]
+    );
+    gen_inst_test!(
+        "ldh a, ($ff44)",
+        Mnemonic::LDH,
+        vec![
+            Operand::Register(Register::A),
+            Operand::Address(Value::Literal(0xff44))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_stack_pointer_offsets() {
+    gen_inst_test!(
+        "ld hl, sp + 4",
+        Mnemonic::LD,
+        vec![
+            Operand::RegisterPair(RegisterPair::HL),
+            Operand::StackOffset(Displacement::new(false, Value::Literal(4)))
+        ]
+    );
+    gen_inst_test!(
+        "ld sp, hl",
+        Mnemonic::LD,
+        vec![
+            Operand::RegisterPair(RegisterPair::SP),
+            Operand::RegisterPair(RegisterPair::HL)
+        ]
+    );
+}
@@ -0,0 +1,475 @@
+use crate::preparser::types::Endianness;
+use std::convert::TryFrom;
+
+/// The widest instruction a form may encode, in bits.
+const MAX_WIDTH: u32 = 64;
+
+/// Placeholder represents a named operand of an instruction pattern, such as
+/// `{a}`, that is encoded either as given or, for `{a:relative}`, as an
+/// offset from the instruction following it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder {
+    pub name: String,
+    pub relative: bool,
+}
+
+/// Segment represents a piece of an operand pattern, being either text that
+/// must appear as given or the index of a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Literal(String),
+    Placeholder(usize),
+}
+
+/// Field represents a bit-field of an encoding, ordered from the most
+/// significant bit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    /// Fixed bits, such as an opcode, given in binary or hexadecimal.
+    Literal { value: u64, bits: u32 },
+    /// Bits `hi` through `lo` of the value of a placeholder.
+    Operand {
+        placeholder: usize,
+        hi: u32,
+        lo: u32,
+    },
+}
+
+impl Field {
+    fn bits(&self) -> u32 {
+        match self {
+            Self::Literal { bits, .. } => *bits,
+            Self::Operand { hi, lo, .. } => hi - lo + 1,
+        }
+    }
+}
+
+/// Error type returned when a form can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+}
+
+/// Form represents a single mnemonic and operand pattern along with the
+/// bit-fields that it encodes to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form {
+    pub mnemonic: String,
+    pub pattern: Vec<Segment>,
+    pub placeholders: Vec<Placeholder>,
+    pub fields: Vec<Field>,
+}
+
+impl Form {
+    /// Returns the size of the form's encoding in bytes.
+    pub fn size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| field.bits() as usize)
+            .sum::<usize>()
+            / 8
+    }
+
+    /// Encodes the form, positioned at the provided offset, against the
+    /// resolved value of each of its placeholders.
+    pub fn encode(
+        &self,
+        values: &[i64],
+        position: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<u8>, EncodeErr> {
+        let next = (position + self.size()) as i64;
+        let values = self
+            .placeholders
+            .iter()
+            .zip(values.iter())
+            .map(|(placeholder, value)| {
+                if placeholder.relative {
+                    value - next
+                } else {
+                    *value
+                }
+            })
+            .collect::<Vec<i64>>();
+
+        // each value must fit within the widest field it's encoded in.
+        for (idx, value) in values.iter().enumerate() {
+            let width = self
+                .fields
+                .iter()
+                .filter_map(|field| match field {
+                    Field::Operand {
+                        placeholder, hi, ..
+                    } if *placeholder == idx => Some(hi + 1),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(MAX_WIDTH);
+            check_range(*value, width, self.placeholders[idx].relative)?;
+        }
+
+        let word = self.fields.iter().fold(0u128, |word, field| {
+            let bits = match field {
+                Field::Literal { value, .. } => u128::from(*value),
+                Field::Operand {
+                    placeholder,
+                    hi,
+                    lo,
+                } => ((values[*placeholder] as u128) >> lo) & ((1u128 << (hi - lo + 1)) - 1),
+            };
+
+            (word << field.bits()) | bits
+        });
+
+        let bytes = word.to_be_bytes();
+        let bytes = &bytes[bytes.len() - self.size()..];
+        match endianness {
+            Endianness::Big => Ok(bytes.to_vec()),
+            Endianness::Little => Ok(bytes.iter().rev().copied().collect()),
+        }
+    }
+}
+
+/// Checks that a value fits within the provided width. Relative values must
+/// fit as signed, while others may be given either signed or unsigned.
+fn check_range(value: i64, width: u32, relative: bool) -> Result<(), EncodeErr> {
+    let (value, half) = (i128::from(value), 1i128 << (width - 1));
+    match (relative, value) {
+        (true, v) if (-half..half).contains(&v) => Ok(()),
+        (true, v) => Err(EncodeErr::BranchOutOfRange(v as isize)),
+        (false, v) if (-half..(half << 1)).contains(&v) => Ok(()),
+        (false, v) => Err(EncodeErr::OutOfRange(v as i64)),
+    }
+}
+
+/// The widest address a description may declare, in bits, matching the
+/// width of the values that labels resolve to.
+const MAX_ADDRESS_WIDTH: u32 = 32;
+
+/// IsaDescription represents an instruction set loaded from a description,
+/// consisting of the byte order of its encodings and constants, the width
+/// of its addresses and each of its instruction forms, which are tried in
+/// the order they're given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsaDescription {
+    pub endianness: Endianness,
+    pub address_width: u32,
+    pub forms: Vec<Form>,
+}
+
+impl Default for IsaDescription {
+    fn default() -> Self {
+        Self {
+            endianness: Endianness::Little,
+            address_width: 16,
+            forms: Vec::new(),
+        }
+    }
+}
+
+impl IsaDescription {
+    /// Applies a single line of a description, being either a form, given as
+    /// `pattern => fields`, or a directive.
+    fn apply_line(&mut self, text: &str) -> Result<(), String> {
+        if let Some((pattern, encoding)) = text.split_once("=>") {
+            self.forms.push(parse_form(pattern, encoding)?);
+            return Ok(());
+        }
+
+        match text.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["endianness", "little"] => self.endianness = Endianness::Little,
+            ["endianness", "big"] => self.endianness = Endianness::Big,
+            ["address_width", bits] => {
+                self.address_width = bits
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|bits| (1..=MAX_ADDRESS_WIDTH).contains(bits))
+                    .ok_or_else(|| format!("invalid address width: {}", bits))?
+            }
+            _ => return Err(format!("unknown directive: {}", text)),
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for IsaDescription {
+    type Error = String;
+
+    /// Parses a description, one form or directive per line, with `;`
+    /// starting a comment.
+    fn try_from(source: &str) -> Result<Self, Self::Error> {
+        source
+            .lines()
+            .enumerate()
+            .map(|(line, text)| (line + 1, text.split(';').next().unwrap_or("").trim()))
+            .filter(|(_, text)| !text.is_empty())
+            .try_fold(Self::default(), |mut description, (line, text)| {
+                description
+                    .apply_line(text)
+                    .map_err(|e| format!("line {}: {}", line, e))?;
+                Ok(description)
+            })
+    }
+}
+
+/// Parses a form from its pattern, such as `ld r{d}, ({a})`, and the
+/// whitespace-separated fields it encodes to, such as `0x2 d:4 a:16`.
+fn parse_form(pattern: &str, encoding: &str) -> Result<Form, String> {
+    let pattern = pattern.trim();
+    let (mnemonic, operands) = pattern
+        .split_once(char::is_whitespace)
+        .unwrap_or((pattern, ""));
+    if mnemonic.is_empty() || !mnemonic.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("invalid mnemonic: {}", mnemonic));
+    }
+
+    let (segments, placeholders) = parse_pattern(operands)?;
+    let fields = encoding
+        .split_whitespace()
+        .map(|field| parse_field(field, &placeholders))
+        .collect::<Result<Vec<Field>, String>>()?;
+
+    if let Some(unused) = placeholders.iter().enumerate().find(|(idx, _)| {
+        !fields
+            .iter()
+            .any(|field| matches!(field, Field::Operand { placeholder, .. } if placeholder == idx))
+    }) {
+        return Err(format!("placeholder is never encoded: {}", unused.1.name));
+    }
+
+    let width = fields.iter().map(Field::bits).sum::<u32>();
+    if width == 0 || width % 8 != 0 || width > MAX_WIDTH {
+        return Err(format!(
+            "encoding of {} must be a multiple of 8 bits, up to {}: {}",
+            pattern, MAX_WIDTH, width
+        ));
+    }
+
+    Ok(Form {
+        mnemonic: mnemonic.to_uppercase(),
+        pattern: segments,
+        placeholders,
+        fields,
+    })
+}
+
+/// Parses an operand pattern, ignoring whitespace, into its literal text and
+/// placeholders. Placeholders must be separated by literal text so that
+/// each operand is delimited.
+fn parse_pattern(operands: &str) -> Result<(Vec<Segment>, Vec<Placeholder>), String> {
+    let operands = operands
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let mut segments = Vec::new();
+    let mut placeholders: Vec<Placeholder> = Vec::new();
+    let mut rest = operands.as_str();
+
+    while !rest.is_empty() {
+        match rest.strip_prefix('{') {
+            Some(placeholder) => {
+                let (placeholder, remaining) = placeholder
+                    .split_once('}')
+                    .ok_or_else(|| format!("unterminated placeholder: {}", rest))?;
+                let (name, relative) = match placeholder.split_once(':') {
+                    Some((name, "relative")) => (name, true),
+                    None => (placeholder, false),
+                    _ => return Err(format!("invalid placeholder: {{{}}}", placeholder)),
+                };
+
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(format!("invalid placeholder: {{{}}}", placeholder));
+                } else if placeholders.iter().any(|p| p.name == name) {
+                    return Err(format!("duplicate placeholder: {}", name));
+                } else if matches!(segments.last(), Some(Segment::Placeholder(_))) {
+                    return Err(format!("placeholders must be separated: {}", operands));
+                }
+
+                segments.push(Segment::Placeholder(placeholders.len()));
+                placeholders.push(Placeholder {
+                    name: name.to_string(),
+                    relative,
+                });
+                rest = remaining;
+            }
+            None => {
+                let end = rest.find('{').unwrap_or(rest.len());
+                segments.push(Segment::Literal(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    Ok((segments, placeholders))
+}
+
+/// Parses a field, being fixed bits, such as `0b0001` or `0x4f`, whose
+/// width is given by their digits, or bits of a placeholder, given either
+/// as `name:bits` for its low bits or `name[hi:lo]` for a slice.
+fn parse_field(field: &str, placeholders: &[Placeholder]) -> Result<Field, String> {
+    let invalid = || format!("invalid field: {}", field);
+
+    let literal = |digits: &str, radix: u32, bits_per_digit: u32| {
+        let bits = digits.len() as u32 * bits_per_digit;
+        match u64::from_str_radix(digits, radix) {
+            Ok(value) if bits <= MAX_WIDTH => Ok(Field::Literal { value, bits }),
+            _ => Err(invalid()),
+        }
+    };
+
+    if let Some(digits) = field.strip_prefix("0b") {
+        return literal(digits, 2, 1);
+    } else if let Some(digits) = field.strip_prefix("0x") {
+        return literal(digits, 16, 4);
+    }
+
+    let (name, hi, lo) = match (field.split_once(':'), field.split_once('[')) {
+        (_, Some((name, slice))) => {
+            let (hi, lo) = slice
+                .strip_suffix(']')
+                .and_then(|slice| slice.split_once(':'))
+                .ok_or_else(invalid)?;
+            (name, hi.parse::<u32>().ok(), lo.parse::<u32>().ok())
+        }
+        (Some((name, bits)), None) => {
+            let hi = bits
+                .parse::<u32>()
+                .ok()
+                .and_then(|bits| bits.checked_sub(1));
+            (name, hi, Some(0))
+        }
+        (None, None) => return Err(invalid()),
+    };
+
+    let placeholder = placeholders
+        .iter()
+        .position(|placeholder| placeholder.name == name)
+        .ok_or_else(|| format!("unknown placeholder: {}", name))?;
+    match (hi, lo) {
+        (Some(hi), Some(lo)) if lo <= hi && hi < MAX_WIDTH => Ok(Field::Operand {
+            placeholder,
+            hi,
+            lo,
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_forms_and_directives() {
+        let description = IsaDescription::try_from(
+            "
+; a homebrew cpu
+endianness big
+address_width 20
+
+ld r{d}, ({a}) => 0b0010 d:4 a:16 ; load
+",
+        )
+        .unwrap();
+
+        assert_eq!(Endianness::Big, description.endianness);
+        assert_eq!(20, description.address_width);
+        assert_eq!(
+            vec![Form {
+                mnemonic: "LD".to_string(),
+                pattern: vec![
+                    Segment::Literal("r".to_string()),
+                    Segment::Placeholder(0),
+                    Segment::Literal(",(".to_string()),
+                    Segment::Placeholder(1),
+                    Segment::Literal(")".to_string()),
+                ],
+                placeholders: vec![
+                    Placeholder {
+                        name: "d".to_string(),
+                        relative: false
+                    },
+                    Placeholder {
+                        name: "a".to_string(),
+                        relative: false
+                    },
+                ],
+                fields: vec![
+                    Field::Literal { value: 2, bits: 4 },
+                    Field::Operand {
+                        placeholder: 0,
+                        hi: 3,
+                        lo: 0
+                    },
+                    Field::Operand {
+                        placeholder: 1,
+                        hi: 15,
+                        lo: 0
+                    },
+                ],
+            }],
+            description.forms
+        );
+        assert_eq!(3, description.forms[0].size());
+    }
+
+    #[test]
+    fn should_reject_invalid_descriptions() {
+        for (source, error) in [
+            (
+                "nop => 0b0",
+                "line 1: encoding of nop must be a multiple of 8 bits, up to 64: 1",
+            ),
+            ("jmp {a} => 0x00", "line 1: placeholder is never encoded: a"),
+            ("jmp {a} => 0x0 b:12", "line 1: unknown placeholder: b"),
+            (
+                "mov {a}{b} => a:4 b:4",
+                "line 1: placeholders must be separated: {a}{b}",
+            ),
+            (
+                "\nendianness middle",
+                "line 2: unknown directive: endianness middle",
+            ),
+            ("address_width 33", "line 1: invalid address width: 33"),
+        ] {
+            assert_eq!(Err(error.to_string()), IsaDescription::try_from(source));
+        }
+    }
+
+    #[test]
+    fn should_encode_fields_from_the_most_significant_bit() {
+        let description =
+            IsaDescription::try_from("jmp {a} => 0x4 a[11:8] a[7:0] 0b0000 0b1111").unwrap();
+        let form = &description.forms[0];
+
+        assert_eq!(
+            Ok(vec![0x0f, 0x34, 0x42]),
+            form.encode(&[0x234], 0, Endianness::Little)
+        );
+        assert_eq!(
+            Ok(vec![0x42, 0x34, 0x0f]),
+            form.encode(&[0x234], 0, Endianness::Big)
+        );
+        assert_eq!(
+            Err(EncodeErr::OutOfRange(0x1000)),
+            form.encode(&[0x1000], 0, Endianness::Big)
+        );
+    }
+
+    #[test]
+    fn should_encode_relative_placeholders_from_the_following_instruction() {
+        let description = IsaDescription::try_from("br {o:relative} => 0x20 o:8").unwrap();
+        let form = &description.forms[0];
+
+        assert_eq!(
+            Ok(vec![0x20, 0xfe]),
+            form.encode(&[0x10], 0x10, Endianness::Big)
+        );
+        assert_eq!(
+            Err(EncodeErr::BranchOutOfRange(128)),
+            form.encode(&[0x92], 0x10, Endianness::Big)
+        );
+    }
+}
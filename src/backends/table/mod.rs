@@ -0,0 +1,81 @@
+pub mod description;
+pub mod parser;
+
+use crate::backends::layout::{resolve_value, InstructionSet, SymbolTable, Value};
+use crate::backends::table::description::{EncodeErr, IsaDescription};
+use crate::backends::table::parser::Instruction;
+use crate::backends::BackendErr;
+use crate::preparser::types::Endianness;
+
+/// TableAssembler implements the [InstructionSet] loaded from a description.
+pub struct TableAssembler {
+    description: IsaDescription,
+}
+
+impl TableAssembler {
+    pub fn new(description: IsaDescription) -> Self {
+        Self { description }
+    }
+}
+
+impl InstructionSet for TableAssembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parser::instruction(&self.description, source)
+    }
+
+    /// The size of a form is fixed by its fields.
+    fn size_of(&self, _: &SymbolTable, instruction: &Instruction) -> usize {
+        self.description.forms[instruction.form].size()
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let form = &self.description.forms[instruction.form];
+        let values = instruction
+            .operands
+            .iter()
+            .map(|value| resolve_value(symbol_table, value))
+            .collect::<Result<Vec<i64>, BackendErr>>()?;
+
+        form.encode(&values, position, self.description.endianness)
+            .map_err(|e| match e {
+                EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                    "operand out of range for {}: {}",
+                    form.mnemonic, value
+                )),
+                EncodeErr::BranchOutOfRange(offset) => {
+                    BackendErr::BranchOutOfRange(target(&self.description, instruction), offset)
+                }
+            })
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.description.endianness
+    }
+
+    fn address_width(&self) -> u32 {
+        self.description.address_width
+    }
+}
+
+/// Returns the name of a branch target, being the value of the first
+/// relative placeholder.
+fn target(description: &IsaDescription, instruction: &Instruction) -> String {
+    let relative = description.forms[instruction.form]
+        .placeholders
+        .iter()
+        .zip(instruction.operands.iter())
+        .find(|(placeholder, _)| placeholder.relative)
+        .map(|(_, value)| value);
+
+    match relative {
+        Some(Value::Reference(id)) => id.clone(),
+        value => format!("{:?}", value),
+    }
+}
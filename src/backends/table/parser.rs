@@ -0,0 +1,147 @@
+pub use crate::backends::layout::Value;
+use crate::backends::table::description::{IsaDescription, Segment};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, right};
+
+use crate::parser::*;
+
+/// Instruction represents an instruction matched against a form, by the
+/// form's index within its description, and the value of each of the
+/// form's placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub form: usize,
+    pub operands: Vec<Value>,
+}
+
+impl Instruction {
+    pub fn new(form: usize, operands: Vec<Value>) -> Self {
+        Self { form, operands }
+    }
+}
+
+/// Matches the text of an instruction against each form of a description,
+/// in order, returning the first to match. Mnemonics and literal operand
+/// text are case-insensitive and whitespace between operands is ignored.
+pub fn instruction(description: &IsaDescription, source: &str) -> Result<Instruction, String> {
+    let source = source.trim();
+    let (mnemonic, operands) = source.split_at(
+        source
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(source.len()),
+    );
+    let operands = operands
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<char>>();
+
+    description
+        .forms
+        .iter()
+        .enumerate()
+        .filter(|(_, form)| form.mnemonic.eq_ignore_ascii_case(mnemonic))
+        .find_map(|(idx, form)| {
+            match_pattern(&form.pattern, &operands).map(|values| Instruction::new(idx, values))
+        })
+        .ok_or_else(|| format!("no instruction form matches: {}", source))
+}
+
+/// Matches operands against a pattern, returning the value of each
+/// placeholder in the order they appear.
+fn match_pattern(pattern: &[Segment], input: &[char]) -> Option<Vec<Value>> {
+    let mut values = Vec::new();
+    let mut rest = input;
+
+    for segment in pattern {
+        match segment {
+            Segment::Literal(text) => {
+                let text = text.chars().collect::<Vec<char>>();
+                if rest.len() < text.len()
+                    || !rest
+                        .iter()
+                        .zip(text.iter())
+                        .all(|(a, b)| a.eq_ignore_ascii_case(b))
+                {
+                    return None;
+                }
+                rest = &rest[text.len()..];
+            }
+            Segment::Placeholder(_) => match value().parse(rest) {
+                Ok(MatchStatus::Match((remaining, value))) => {
+                    values.push(value);
+                    rest = remaining;
+                }
+                _ => return None,
+            },
+        }
+    }
+
+    if rest.is_empty() {
+        Some(values)
+    } else {
+        None
+    }
+}
+
+/// Matches a value, allowing a leading `-` on literals.
+#[allow(clippy::redundant_closure)]
+fn value<'a>() -> impl parcel::Parser<'a, &'a [char], Value> {
+    right(join(expect_character('-'), unsigned32()))
+        .map(|v| Value::Literal(-i64::from(v)))
+        .or(|| unsigned32().map(|v| Value::Literal(i64::from(v))))
+        .or(|| name().map(Value::Reference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    const DESCRIPTION: &str = "
+nop            => 0x00
+ld r{d}, #{k}  => 0x1 d:4 k:8
+ld r{d}, ({a}) => 0x2 d:4 a:16
+ld r{d}, r{s}  => 0x3 d:4 s:4 0x0
+";
+
+    #[test]
+    fn should_match_the_first_form_in_order() {
+        let description = IsaDescription::try_from(DESCRIPTION).unwrap();
+
+        assert_eq!(
+            Ok(Instruction::new(0, vec![])),
+            instruction(&description, "  NOP ")
+        );
+        assert_eq!(
+            Ok(Instruction::new(
+                1,
+                vec![Value::Literal(3), Value::Literal(-1)]
+            )),
+            instruction(&description, "ld r3, #-1")
+        );
+        assert_eq!(
+            Ok(Instruction::new(
+                2,
+                vec![Value::Literal(1), Value::Reference("table".to_string())]
+            )),
+            instruction(&description, "LD R1, ( table )")
+        );
+        assert_eq!(
+            Ok(Instruction::new(
+                3,
+                vec![Value::Literal(1), Value::Literal(15)]
+            )),
+            instruction(&description, "ld r1,r15")
+        );
+    }
+
+    #[test]
+    fn should_reject_instructions_matching_no_form() {
+        let description = IsaDescription::try_from(DESCRIPTION).unwrap();
+
+        assert!(instruction(&description, "ld r1").is_err());
+        assert!(instruction(&description, "ld r1, #2, #3").is_err());
+        assert!(instruction(&description, "jmp start").is_err());
+    }
+}
@@ -0,0 +1,560 @@
+pub mod mnemonic;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::backends::layout::Value;
+use mnemonic::Mnemonic;
+
+/// Register represents the 8-bit registers that may be named as an operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    A,
+    B,
+    C,
+    D,
+    E,
+    H,
+    L,
+    I,
+    R,
+}
+
+impl Register {
+    /// Returns the 3-bit encoding of the register within an opcode. The
+    /// interrupt vector and refresh registers are only accessible through
+    /// dedicated loads and have no such encoding.
+    fn code(self) -> Option<u8> {
+        match self {
+            Self::B => Some(0),
+            Self::C => Some(1),
+            Self::D => Some(2),
+            Self::E => Some(3),
+            Self::H => Some(4),
+            Self::L => Some(5),
+            Self::A => Some(7),
+            Self::I | Self::R => None,
+        }
+    }
+}
+
+/// RegisterPair represents the 16-bit registers, including the index
+/// registers and the alternate accumulator and flags, `af'`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterPair {
+    BC,
+    DE,
+    HL,
+    SP,
+    AF,
+    AlternateAF,
+    IX,
+    IY,
+}
+
+impl RegisterPair {
+    /// Returns the prefix selecting an index register in place of `hl`.
+    fn index_prefix(self) -> Option<u8> {
+        match self {
+            Self::IX => Some(0xdd),
+            Self::IY => Some(0xfd),
+            _ => None,
+        }
+    }
+}
+
+/// Condition represents the flag conditions of conditional jumps, calls and
+/// returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    NZ,
+    Z,
+    NC,
+    C,
+    PO,
+    PE,
+    P,
+    M,
+}
+
+impl Condition {
+    fn code(self) -> u8 {
+        match self {
+            Self::NZ => 0,
+            Self::Z => 1,
+            Self::NC => 2,
+            Self::C => 3,
+            Self::PO => 4,
+            Self::PE => 5,
+            Self::P => 6,
+            Self::M => 7,
+        }
+    }
+}
+
+/// Displacement represents the signed offset from an index register.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Displacement<V> {
+    pub negative: bool,
+    pub value: V,
+}
+
+impl<V> Displacement<V> {
+    pub fn new(negative: bool, value: V) -> Self {
+        Self { negative, value }
+    }
+}
+
+/// Operand represents each form of Z80 operand, generalized over its value so
+/// that operands may be parsed with references and encoded once resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand<V> {
+    Register(Register),
+    RegisterPair(RegisterPair),
+    Condition(Condition),
+    /// An address held in a register pair, such as `(hl)`.
+    Indirect(RegisterPair),
+    /// The I/O port addressed by the `c` register, `(c)`.
+    Port,
+    /// An address offset from an index register, such as `(ix+4)`.
+    Indexed(RegisterPair, Displacement<V>),
+    Immediate(V),
+    /// An absolute address or port, such as `(0x1234)`.
+    Address(V),
+}
+
+impl<V> Operand<V> {
+    /// Converts the value of an operand, if it has one, returning the first
+    /// error encountered.
+    pub fn try_map<U, E>(&self, f: impl Fn(&V) -> Result<U, E>) -> Result<Operand<U>, E> {
+        let operand = match self {
+            Self::Register(r) => Operand::Register(*r),
+            Self::RegisterPair(rp) => Operand::RegisterPair(*rp),
+            Self::Condition(c) => Operand::Condition(*c),
+            Self::Indirect(rp) => Operand::Indirect(*rp),
+            Self::Port => Operand::Port,
+            Self::Indexed(rp, d) => {
+                Operand::Indexed(*rp, Displacement::new(d.negative, f(&d.value)?))
+            }
+            Self::Immediate(v) => Operand::Immediate(f(v)?),
+            Self::Address(v) => Operand::Address(f(v)?),
+        };
+
+        Ok(operand)
+    }
+}
+
+/// Instruction represents a single Z80 mnemonic and its operands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub mnemonic: Mnemonic,
+    pub operands: Vec<Operand<Value>>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: Mnemonic, operands: Vec<Operand<Value>>) -> Self {
+        Self { mnemonic, operands }
+    }
+}
+
+/// Error type returned when an instruction can't be encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeErr {
+    InvalidOperands,
+    OutOfRange(i64),
+    BranchOutOfRange(isize),
+}
+
+type Encoding = Result<Vec<u8>, EncodeErr>;
+
+/// Reg8 represents an operand of an 8-bit operation, either encoded in the
+/// opcode itself or as an index prefix and displacement.
+enum Reg8 {
+    Code(u8),
+    Indexed(u8, u8),
+}
+
+/// The 3-bit register encoding that addresses memory through `hl`, or an
+/// index register when prefixed.
+const INDIRECT_HL: u8 = 6;
+
+fn reg8(operand: &Operand<i64>) -> Result<Option<Reg8>, EncodeErr> {
+    let reg8 = match operand {
+        Operand::Register(r) => r.code().map(Reg8::Code),
+        Operand::Indirect(RegisterPair::HL) => Some(Reg8::Code(INDIRECT_HL)),
+        Operand::Indirect(rp) => rp.index_prefix().map(|prefix| Reg8::Indexed(prefix, 0)),
+        Operand::Indexed(rp, d) => match rp.index_prefix() {
+            Some(prefix) => Some(Reg8::Indexed(prefix, displacement(d)?)),
+            None => None,
+        },
+        _ => None,
+    };
+
+    Ok(reg8)
+}
+
+fn byte(value: i64) -> Result<u8, EncodeErr> {
+    match value {
+        -0x80..=0xff => Ok(value as u8),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn word(value: i64) -> Result<[u8; 2], EncodeErr> {
+    match value {
+        -0x8000..=0xffff => Ok((value as u16).to_le_bytes()),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+fn displacement(d: &Displacement<i64>) -> Result<u8, EncodeErr> {
+    let value = if d.negative { -d.value } else { d.value };
+    match value {
+        -0x80..=0x7f => Ok(value as i8 as u8),
+        _ => Err(EncodeErr::OutOfRange(value)),
+    }
+}
+
+/// Returns the offset of a relative jump, positioned at the provided offset,
+/// to its target.
+fn relative(position: usize, target: i64) -> Result<u8, EncodeErr> {
+    let offset = target - (position as i64 + 2);
+    match offset {
+        -0x80..=0x7f => Ok(offset as i8 as u8),
+        _ => Err(EncodeErr::BranchOutOfRange(offset as isize)),
+    }
+}
+
+/// Conditions are matched as registers where their syntax overlaps, leaving
+/// `c` to be interpreted as the carry condition by jumps, calls and returns.
+fn condition(operand: &Operand<i64>) -> Option<Condition> {
+    match operand {
+        Operand::Condition(c) => Some(*c),
+        Operand::Register(Register::C) => Some(Condition::C),
+        _ => None,
+    }
+}
+
+/// Returns the prefix, if any, and 2-bit encoding of a register pair among
+/// `bc`, `de`, `hl` and `sp`, with the index registers standing in for `hl`.
+fn pair_with_sp(pair: RegisterPair) -> Option<(Option<u8>, u8)> {
+    match pair {
+        RegisterPair::BC => Some((None, 0)),
+        RegisterPair::DE => Some((None, 1)),
+        RegisterPair::HL => Some((None, 2)),
+        RegisterPair::SP => Some((None, 3)),
+        RegisterPair::IX | RegisterPair::IY => Some((pair.index_prefix(), 2)),
+        RegisterPair::AF | RegisterPair::AlternateAF => None,
+    }
+}
+
+/// As pair_with_sp, with `af` taking the place of `sp` as it does for stack
+/// operations.
+fn pair_with_af(pair: RegisterPair) -> Option<(Option<u8>, u8)> {
+    match pair {
+        RegisterPair::AF => Some((None, 3)),
+        RegisterPair::SP => None,
+        pair => pair_with_sp(pair),
+    }
+}
+
+fn prefixed(prefix: Option<u8>, opcodes: &[u8]) -> Vec<u8> {
+    prefix.into_iter().chain(opcodes.iter().copied()).collect()
+}
+
+/// Encodes an instruction, positioned at the provided offset, against its
+/// resolved operands.
+pub fn encode(mnemonic: Mnemonic, operands: &[Operand<i64>], position: usize) -> Encoding {
+    use Mnemonic as M;
+    use Operand as O;
+
+    match (mnemonic, operands) {
+        (m, []) => implied(m),
+        (M::LD, [dst, src]) => load(dst, src),
+        (M::PUSH, [O::RegisterPair(rp)]) => stack(0xc5, *rp),
+        (M::POP, [O::RegisterPair(rp)]) => stack(0xc1, *rp),
+        (M::EX, [O::RegisterPair(RegisterPair::DE), O::RegisterPair(RegisterPair::HL)]) => {
+            Ok(vec![0xeb])
+        }
+        (
+            M::EX,
+            [O::RegisterPair(RegisterPair::AF), O::RegisterPair(RegisterPair::AlternateAF)],
+        ) => Ok(vec![0x08]),
+        (M::EX, [O::Indirect(RegisterPair::SP), O::RegisterPair(rp)])
+            if matches!(rp, RegisterPair::HL | RegisterPair::IX | RegisterPair::IY) =>
+        {
+            Ok(prefixed(rp.index_prefix(), &[0xe3]))
+        }
+        (M::ADD, [O::RegisterPair(dst), O::RegisterPair(src)]) => add16(*dst, *src),
+        (M::ADC, [O::RegisterPair(RegisterPair::HL), O::RegisterPair(src)]) => {
+            extended16(0x4a, *src)
+        }
+        (M::SBC, [O::RegisterPair(RegisterPair::HL), O::RegisterPair(src)]) => {
+            extended16(0x42, *src)
+        }
+        (M::ADD, [O::Register(Register::A), src]) => alu(0x80, 0xc6, src),
+        (M::ADC, [O::Register(Register::A), src]) => alu(0x88, 0xce, src),
+        (M::SBC, [O::Register(Register::A), src]) => alu(0x98, 0xde, src),
+        (M::SUB | M::AND | M::XOR | M::OR | M::CP, [O::Register(Register::A), src])
+        | (M::SUB | M::AND | M::XOR | M::OR | M::CP, [src]) => {
+            let (base, immediate) = match mnemonic {
+                M::SUB => (0x90, 0xd6),
+                M::AND => (0xa0, 0xe6),
+                M::XOR => (0xa8, 0xee),
+                M::OR => (0xb0, 0xf6),
+                _ => (0xb8, 0xfe),
+            };
+            alu(base, immediate, src)
+        }
+        (M::INC | M::DEC, [O::RegisterPair(rp)]) => {
+            let base = if mnemonic == M::INC { 0x03 } else { 0x0b };
+            let (prefix, code) = pair_with_sp(*rp).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(prefixed(prefix, &[base | (code << 4)]))
+        }
+        (M::INC | M::DEC, [operand]) => {
+            let base = if mnemonic == M::INC { 0x04 } else { 0x05 };
+            match reg8(operand)? {
+                Some(Reg8::Code(r)) => Ok(vec![base | (r << 3)]),
+                Some(Reg8::Indexed(prefix, d)) => Ok(vec![prefix, base | (INDIRECT_HL << 3), d]),
+                None => Err(EncodeErr::InvalidOperands),
+            }
+        }
+        (M::RLC | M::RRC | M::RL | M::RR | M::SLA | M::SRA | M::SRL, [operand]) => {
+            let base = match mnemonic {
+                M::RLC => 0x00,
+                M::RRC => 0x08,
+                M::RL => 0x10,
+                M::RR => 0x18,
+                M::SLA => 0x20,
+                M::SRA => 0x28,
+                _ => 0x38,
+            };
+            bit_operation(base, operand)
+        }
+        (M::BIT | M::RES | M::SET, [O::Immediate(bit), operand]) => {
+            let base = match mnemonic {
+                M::BIT => 0x40,
+                M::RES => 0x80,
+                _ => 0xc0,
+            };
+            match bit {
+                0..=7 => bit_operation(base | ((*bit as u8) << 3), operand),
+                _ => Err(EncodeErr::OutOfRange(*bit)),
+            }
+        }
+        (M::IM, [O::Immediate(mode)]) => match mode {
+            0 => Ok(vec![0xed, 0x46]),
+            1 => Ok(vec![0xed, 0x56]),
+            2 => Ok(vec![0xed, 0x5e]),
+            _ => Err(EncodeErr::OutOfRange(*mode)),
+        },
+        (M::RST, [O::Immediate(target)]) => match target {
+            0..=0x38 if target % 8 == 0 => Ok(vec![0xc7 | *target as u8]),
+            _ => Err(EncodeErr::OutOfRange(*target)),
+        },
+        (M::JP, [O::Indirect(rp)])
+            if matches!(rp, RegisterPair::HL | RegisterPair::IX | RegisterPair::IY) =>
+        {
+            Ok(prefixed(rp.index_prefix(), &[0xe9]))
+        }
+        (M::JP, [O::Immediate(target)]) => absolute(0xc3, *target),
+        (M::CALL, [O::Immediate(target)]) => absolute(0xcd, *target),
+        (M::JP | M::CALL, [cc, O::Immediate(target)]) => {
+            let base = if mnemonic == M::JP { 0xc2 } else { 0xc4 };
+            let cc = condition(cc).ok_or(EncodeErr::InvalidOperands)?;
+            absolute(base | (cc.code() << 3), *target)
+        }
+        (M::RET, [cc]) => {
+            let cc = condition(cc).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0xc0 | (cc.code() << 3)])
+        }
+        (M::JR, [O::Immediate(target)]) => Ok(vec![0x18, relative(position, *target)?]),
+        (M::DJNZ, [O::Immediate(target)]) => Ok(vec![0x10, relative(position, *target)?]),
+        // only the first four conditions are available to relative jumps.
+        (M::JR, [cc, O::Immediate(target)]) => match condition(cc) {
+            Some(cc @ (Condition::NZ | Condition::Z | Condition::NC | Condition::C)) => {
+                Ok(vec![0x20 | (cc.code() << 3), relative(position, *target)?])
+            }
+            _ => Err(EncodeErr::InvalidOperands),
+        },
+        (M::IN, [O::Register(Register::A), O::Address(port)]) => Ok(vec![0xdb, byte(*port)?]),
+        (M::IN, [O::Register(r), O::Port]) => {
+            let r = r.code().ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0xed, 0x40 | (r << 3)])
+        }
+        (M::OUT, [O::Address(port), O::Register(Register::A)]) => Ok(vec![0xd3, byte(*port)?]),
+        (M::OUT, [O::Port, O::Register(r)]) => {
+            let r = r.code().ok_or(EncodeErr::InvalidOperands)?;
+            Ok(vec![0xed, 0x41 | (r << 3)])
+        }
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes the instructions that take no operands.
+fn implied(mnemonic: Mnemonic) -> Encoding {
+    use Mnemonic as M;
+
+    let opcodes = match mnemonic {
+        M::NOP => vec![0x00],
+        M::HALT => vec![0x76],
+        M::DI => vec![0xf3],
+        M::EI => vec![0xfb],
+        M::DAA => vec![0x27],
+        M::CPL => vec![0x2f],
+        M::CCF => vec![0x3f],
+        M::SCF => vec![0x37],
+        M::RLCA => vec![0x07],
+        M::RLA => vec![0x17],
+        M::RRCA => vec![0x0f],
+        M::RRA => vec![0x1f],
+        M::EXX => vec![0xd9],
+        M::RET => vec![0xc9],
+        M::NEG => vec![0xed, 0x44],
+        M::RETI => vec![0xed, 0x4d],
+        M::RETN => vec![0xed, 0x45],
+        M::RLD => vec![0xed, 0x6f],
+        M::RRD => vec![0xed, 0x67],
+        M::LDI => vec![0xed, 0xa0],
+        M::LDIR => vec![0xed, 0xb0],
+        M::LDD => vec![0xed, 0xa8],
+        M::LDDR => vec![0xed, 0xb8],
+        M::CPI => vec![0xed, 0xa1],
+        M::CPIR => vec![0xed, 0xb1],
+        M::CPD => vec![0xed, 0xa9],
+        M::CPDR => vec![0xed, 0xb9],
+        M::INI => vec![0xed, 0xa2],
+        M::INIR => vec![0xed, 0xb2],
+        M::IND => vec![0xed, 0xaa],
+        M::INDR => vec![0xed, 0xba],
+        M::OUTI => vec![0xed, 0xa3],
+        M::OTIR => vec![0xed, 0xb3],
+        M::OUTD => vec![0xed, 0xab],
+        M::OTDR => vec![0xed, 0xbb],
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(opcodes)
+}
+
+fn absolute(opcode: u8, target: i64) -> Encoding {
+    let [lo, hi] = word(target)?;
+    Ok(vec![opcode, lo, hi])
+}
+
+fn load(dst: &Operand<i64>, src: &Operand<i64>) -> Encoding {
+    use Operand as O;
+    use Register as R;
+    use RegisterPair as RP;
+
+    match (dst, src) {
+        (O::Register(R::A), O::Register(R::I)) => Ok(vec![0xed, 0x57]),
+        (O::Register(R::A), O::Register(R::R)) => Ok(vec![0xed, 0x5f]),
+        (O::Register(R::I), O::Register(R::A)) => Ok(vec![0xed, 0x47]),
+        (O::Register(R::R), O::Register(R::A)) => Ok(vec![0xed, 0x4f]),
+        (O::Register(R::A), O::Indirect(RP::BC)) => Ok(vec![0x0a]),
+        (O::Register(R::A), O::Indirect(RP::DE)) => Ok(vec![0x1a]),
+        (O::Register(R::A), O::Address(address)) => absolute(0x3a, *address),
+        (O::Indirect(RP::BC), O::Register(R::A)) => Ok(vec![0x02]),
+        (O::Indirect(RP::DE), O::Register(R::A)) => Ok(vec![0x12]),
+        (O::Address(address), O::Register(R::A)) => absolute(0x32, *address),
+        (O::RegisterPair(RP::SP), O::RegisterPair(rp))
+            if matches!(rp, RP::HL | RP::IX | RP::IY) =>
+        {
+            Ok(prefixed(rp.index_prefix(), &[0xf9]))
+        }
+        (O::RegisterPair(rp), O::Immediate(value)) => {
+            let (prefix, code) = pair_with_sp(*rp).ok_or(EncodeErr::InvalidOperands)?;
+            Ok(prefixed(prefix, &absolute(0x01 | (code << 4), *value)?))
+        }
+        // `hl` and the index registers have shorter, unprefixed forms.
+        (O::RegisterPair(rp), O::Address(address)) => match pair_with_sp(*rp) {
+            Some((prefix, 2)) => Ok(prefixed(prefix, &absolute(0x2a, *address)?)),
+            Some((_, code)) => Ok(prefixed(
+                Some(0xed),
+                &absolute(0x4b | (code << 4), *address)?,
+            )),
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        (O::Address(address), O::RegisterPair(rp)) => match pair_with_sp(*rp) {
+            Some((prefix, 2)) => Ok(prefixed(prefix, &absolute(0x22, *address)?)),
+            Some((_, code)) => Ok(prefixed(
+                Some(0xed),
+                &absolute(0x43 | (code << 4), *address)?,
+            )),
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        (dst, O::Immediate(value)) => match reg8(dst)? {
+            Some(Reg8::Code(r)) => Ok(vec![0x06 | (r << 3), byte(*value)?]),
+            Some(Reg8::Indexed(prefix, d)) => Ok(vec![prefix, 0x36, d, byte(*value)?]),
+            None => Err(EncodeErr::InvalidOperands),
+        },
+        // `ld (hl), (hl)` would otherwise encode `halt`.
+        (dst, src) => match (reg8(dst)?, reg8(src)?) {
+            (Some(Reg8::Code(d)), Some(Reg8::Code(s))) if d != INDIRECT_HL || s != INDIRECT_HL => {
+                Ok(vec![0x40 | (d << 3) | s])
+            }
+            (Some(Reg8::Code(d)), Some(Reg8::Indexed(prefix, disp))) if d != INDIRECT_HL => {
+                Ok(vec![prefix, 0x46 | (d << 3), disp])
+            }
+            (Some(Reg8::Indexed(prefix, disp)), Some(Reg8::Code(s))) if s != INDIRECT_HL => {
+                Ok(vec![prefix, 0x70 | s, disp])
+            }
+            _ => Err(EncodeErr::InvalidOperands),
+        },
+    }
+}
+
+fn stack(base: u8, pair: RegisterPair) -> Encoding {
+    let (prefix, code) = pair_with_af(pair).ok_or(EncodeErr::InvalidOperands)?;
+    Ok(prefixed(prefix, &[base | (code << 4)]))
+}
+
+/// Encodes an 8-bit arithmetic or logical operation on the accumulator.
+fn alu(base: u8, immediate: u8, src: &Operand<i64>) -> Encoding {
+    if let Operand::Immediate(value) = src {
+        return Ok(vec![immediate, byte(*value)?]);
+    }
+
+    match reg8(src)? {
+        Some(Reg8::Code(r)) => Ok(vec![base | r]),
+        Some(Reg8::Indexed(prefix, d)) => Ok(vec![prefix, base | INDIRECT_HL, d]),
+        None => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes a 16-bit addition to `hl` or an index register, where the
+/// destination may also be added to itself.
+fn add16(dst: RegisterPair, src: RegisterPair) -> Encoding {
+    if !matches!(dst, RegisterPair::HL | RegisterPair::IX | RegisterPair::IY) {
+        return Err(EncodeErr::InvalidOperands);
+    }
+
+    let code = match src {
+        RegisterPair::BC => 0,
+        RegisterPair::DE => 1,
+        RegisterPair::SP => 3,
+        src if src == dst => 2,
+        _ => return Err(EncodeErr::InvalidOperands),
+    };
+
+    Ok(prefixed(dst.index_prefix(), &[0x09 | (code << 4)]))
+}
+
+/// Encodes the `ed` prefixed 16-bit additions and subtractions with carry.
+fn extended16(base: u8, src: RegisterPair) -> Encoding {
+    match pair_with_sp(src) {
+        Some((None, code)) => Ok(vec![0xed, base | (code << 4)]),
+        _ => Err(EncodeErr::InvalidOperands),
+    }
+}
+
+/// Encodes the `cb` prefixed rotates, shifts and bit operations. The
+/// displacement of an indexed operand precedes the opcode.
+fn bit_operation(base: u8, operand: &Operand<i64>) -> Encoding {
+    match reg8(operand)? {
+        Some(Reg8::Code(r)) => Ok(vec![0xcb, base | r]),
+        Some(Reg8::Indexed(prefix, d)) => Ok(vec![prefix, 0xcb, d, base | INDIRECT_HL]),
+        None => Err(EncodeErr::InvalidOperands),
+    }
+}
@@ -0,0 +1,195 @@
+use crate::backends::z80::instruction_set::mnemonic::Mnemonic;
+use crate::backends::z80::instruction_set::{
+    encode, Condition, Displacement, EncodeErr, Operand, Register, RegisterPair,
+};
+
+macro_rules! gen_encode_test {
+    ($mnemonic:expr, [$($operand:expr),*], $expected:expr) => {
+        assert_eq!(Ok($expected), encode($mnemonic, &[$($operand),*], 0));
+    };
+}
+
+fn indexed(rp: RegisterPair, d: i64) -> Operand<i64> {
+    Operand::Indexed(rp, Displacement::new(d < 0, d.abs()))
+}
+
+#[test]
+fn should_encode_unprefixed_loads() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [Operand::Register(Register::A), Operand::Immediate(0x12)],
+        vec![0x3e, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::B),
+            Operand::Indirect(RegisterPair::HL)
+        ],
+        vec![0x46]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Address(0x4000),
+            Operand::RegisterPair(RegisterPair::HL)
+        ],
+        vec![0x22, 0x00, 0x40]
+    );
+}
+
+#[test]
+fn should_encode_ed_prefixed_instructions() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Address(0x4000),
+            Operand::RegisterPair(RegisterPair::DE)
+        ],
+        vec![0xed, 0x53, 0x00, 0x40]
+    );
+    gen_encode_test!(
+        Mnemonic::SBC,
+        [
+            Operand::RegisterPair(RegisterPair::HL),
+            Operand::RegisterPair(RegisterPair::BC)
+        ],
+        vec![0xed, 0x42]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::R),
+            Operand::Register(Register::A)
+        ],
+        vec![0xed, 0x4f]
+    );
+    gen_encode_test!(
+        Mnemonic::IN,
+        [Operand::Register(Register::E), Operand::Port],
+        vec![0xed, 0x58]
+    );
+    gen_encode_test!(Mnemonic::IM, [Operand::Immediate(2)], vec![0xed, 0x5e]);
+    gen_encode_test!(Mnemonic::LDIR, [], vec![0xed, 0xb0]);
+}
+
+#[test]
+fn should_encode_cb_prefixed_instructions() {
+    gen_encode_test!(
+        Mnemonic::SET,
+        [Operand::Immediate(0), Operand::Register(Register::A)],
+        vec![0xcb, 0xc7]
+    );
+    gen_encode_test!(
+        Mnemonic::SRL,
+        [Operand::Indirect(RegisterPair::HL)],
+        vec![0xcb, 0x3e]
+    );
+    gen_encode_test!(
+        Mnemonic::BIT,
+        [Operand::Immediate(7), indexed(RegisterPair::IX, 1)],
+        vec![0xdd, 0xcb, 0x01, 0x7e]
+    );
+}
+
+#[test]
+fn should_encode_index_register_instructions() {
+    gen_encode_test!(
+        Mnemonic::LD,
+        [indexed(RegisterPair::IX, 5), Operand::Register(Register::A)],
+        vec![0xdd, 0x77, 0x05]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::B),
+            indexed(RegisterPair::IY, -2)
+        ],
+        vec![0xfd, 0x46, 0xfe]
+    );
+    gen_encode_test!(
+        Mnemonic::LD,
+        [
+            Operand::RegisterPair(RegisterPair::IX),
+            Operand::Immediate(0x1234)
+        ],
+        vec![0xdd, 0x21, 0x34, 0x12]
+    );
+    gen_encode_test!(
+        Mnemonic::ADD,
+        [
+            Operand::RegisterPair(RegisterPair::IX),
+            Operand::RegisterPair(RegisterPair::IX)
+        ],
+        vec![0xdd, 0x29]
+    );
+    gen_encode_test!(
+        Mnemonic::CP,
+        [indexed(RegisterPair::IX, 3)],
+        vec![0xdd, 0xbe, 0x03]
+    );
+    gen_encode_test!(
+        Mnemonic::POP,
+        [Operand::RegisterPair(RegisterPair::IY)],
+        vec![0xfd, 0xe1]
+    );
+    gen_encode_test!(
+        Mnemonic::JP,
+        [Operand::Indirect(RegisterPair::IX)],
+        vec![0xdd, 0xe9]
+    );
+}
+
+#[test]
+fn should_interpret_the_c_register_as_the_carry_condition() {
+    gen_encode_test!(
+        Mnemonic::CALL,
+        [Operand::Register(Register::C), Operand::Immediate(0x10)],
+        vec![0xdc, 0x10, 0x00]
+    );
+    gen_encode_test!(
+        Mnemonic::RET,
+        [Operand::Condition(Condition::M)],
+        vec![0xf8]
+    );
+}
+
+#[test]
+fn should_encode_relative_jumps_from_their_position() {
+    assert_eq!(
+        Ok(vec![0x20, 0xfa]),
+        encode(
+            Mnemonic::JR,
+            &[Operand::Condition(Condition::NZ), Operand::Immediate(0)],
+            4
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::InvalidOperands),
+        encode(
+            Mnemonic::JR,
+            &[Operand::Condition(Condition::PE), Operand::Immediate(0)],
+            4
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::BranchOutOfRange(254)),
+        encode(Mnemonic::DJNZ, &[Operand::Immediate(0x100)], 0)
+    );
+}
+
+#[test]
+fn should_reject_out_of_range_operands() {
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x100)),
+        encode(
+            Mnemonic::LD,
+            &[Operand::Register(Register::A), Operand::Immediate(0x100)],
+            0
+        )
+    );
+    assert_eq!(
+        Err(EncodeErr::OutOfRange(0x3)),
+        encode(Mnemonic::RST, &[Operand::Immediate(0x3)], 0)
+    );
+}
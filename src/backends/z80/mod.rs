@@ -0,0 +1,74 @@
+pub mod instruction_set;
+pub mod parser;
+
+use parcel::prelude::v1::*;
+
+use crate::backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+use crate::backends::z80::instruction_set::{encode, EncodeErr, Instruction, Operand};
+use crate::backends::BackendErr;
+
+/// Z80Assembler implements the [InstructionSet] of the Z80.
+#[derive(Default)]
+pub struct Z80Assembler;
+
+impl Z80Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl InstructionSet for Z80Assembler {
+    type Instruction = Instruction;
+
+    fn parse_instruction(&self, source: &str) -> Result<Instruction, String> {
+        parse_instruction_with(source, |input| parser::instruction().parse(input))
+    }
+
+    /// The size of a Z80 instruction depends only on the form of its
+    /// operands, allowing it to be sized with every value zeroed.
+    fn size_of(&self, _: &SymbolTable, instruction: &Instruction) -> usize {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|_| Ok::<i64, ()>(0)))
+            .collect::<Result<Vec<Operand<i64>>, ()>>()
+            .unwrap_or_default();
+
+        encode(instruction.mnemonic, &operands, 0)
+            .map(|bytes| bytes.len())
+            .unwrap_or(0)
+    }
+
+    fn encode(
+        &self,
+        symbol_table: &SymbolTable,
+        position: usize,
+        instruction: &Instruction,
+    ) -> Result<Vec<u8>, BackendErr> {
+        let operands = instruction
+            .operands
+            .iter()
+            .map(|operand| operand.try_map(|v| resolve_value(symbol_table, v)))
+            .collect::<Result<Vec<Operand<i64>>, BackendErr>>()?;
+
+        encode(instruction.mnemonic, &operands, position).map_err(|e| match e {
+            EncodeErr::InvalidOperands => BackendErr::UndefinedInstruction(format!(
+                "invalid operands for {:?}: {:?}",
+                instruction.mnemonic, instruction.operands
+            )),
+            EncodeErr::OutOfRange(value) => BackendErr::Unspecified(format!(
+                "operand out of range for {:?}: {}",
+                instruction.mnemonic, value
+            )),
+            EncodeErr::BranchOutOfRange(offset) => {
+                let target = match instruction.operands.last() {
+                    Some(Operand::Immediate(Value::Reference(id))) => id.clone(),
+                    _ => format!("{:?}", instruction.operands.last()),
+                };
+                BackendErr::BranchOutOfRange(target, offset)
+            }
+        })
+    }
+}
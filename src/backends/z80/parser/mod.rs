@@ -0,0 +1,167 @@
+use crate::backends::z80::instruction_set::mnemonic::Mnemonic;
+use crate::backends::z80::instruction_set::{
+    Condition, Displacement, Instruction, Operand, Register, RegisterPair, Value,
+};
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, optional, right, zero_or_more};
+use std::convert::TryFrom;
+
+use crate::parser::*;
+
+#[cfg(test)]
+mod tests;
+
+pub fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Instruction> {
+    join(
+        right(join(zero_or_more(non_newline_whitespace()), mnemonic())),
+        left(join(
+            optional(right(join(
+                one_or_more(non_newline_whitespace()),
+                operands(),
+            ))),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    )
+    .map(|(m, operands)| Instruction::new(m, operands.unwrap_or_default()))
+}
+
+fn mnemonic<'a>() -> impl parcel::Parser<'a, &'a [char], Mnemonic> {
+    one_or_more(alphabetic())
+        .map(|m| Mnemonic::try_from(m.into_iter().collect::<String>().as_str()))
+        .predicate(|res| res.is_ok())
+        .map(|res| res.unwrap())
+}
+
+fn operands<'a>() -> impl parcel::Parser<'a, &'a [char], Vec<Operand<Value>>> {
+    join(operand(), zero_or_more(right(join(separator(), operand()))))
+        .map(|(head, tail)| std::iter::once(head).chain(tail).collect())
+}
+
+fn separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    join(
+        zero_or_more(non_newline_whitespace()),
+        join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        ),
+    )
+    .map(|_| ())
+}
+
+#[allow(clippy::redundant_closure)]
+fn operand<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    parenthesized()
+        .or(|| unsigned16().map(|v| Operand::Immediate(Value::Literal(i64::from(v)))))
+        .or(|| named())
+}
+
+/// Matches a register, register pair or condition by name, otherwise
+/// treating the name as a reference to a symbol or label. Register names
+/// are case-insensitive.
+fn named<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    join(name(), optional(expect_character('\'')))
+        .map(|(name, alternate)| match alternate {
+            Some(_) if name.to_lowercase() == "af" => {
+                Some(Operand::RegisterPair(RegisterPair::AlternateAF))
+            }
+            Some(_) => None,
+            None => {
+                Some(keyword(&name).unwrap_or_else(|| Operand::Immediate(Value::Reference(name))))
+            }
+        })
+        .predicate(|operand| operand.is_some())
+        .map(|operand| operand.unwrap())
+}
+
+fn keyword(name: &str) -> Option<Operand<Value>> {
+    let operand = match name.to_lowercase().as_str() {
+        "a" => Operand::Register(Register::A),
+        "b" => Operand::Register(Register::B),
+        "c" => Operand::Register(Register::C),
+        "d" => Operand::Register(Register::D),
+        "e" => Operand::Register(Register::E),
+        "h" => Operand::Register(Register::H),
+        "l" => Operand::Register(Register::L),
+        "i" => Operand::Register(Register::I),
+        "r" => Operand::Register(Register::R),
+        "bc" => Operand::RegisterPair(RegisterPair::BC),
+        "de" => Operand::RegisterPair(RegisterPair::DE),
+        "hl" => Operand::RegisterPair(RegisterPair::HL),
+        "sp" => Operand::RegisterPair(RegisterPair::SP),
+        "af" => Operand::RegisterPair(RegisterPair::AF),
+        "ix" => Operand::RegisterPair(RegisterPair::IX),
+        "iy" => Operand::RegisterPair(RegisterPair::IY),
+        "nz" => Operand::Condition(Condition::NZ),
+        "z" => Operand::Condition(Condition::Z),
+        "nc" => Operand::Condition(Condition::NC),
+        "po" => Operand::Condition(Condition::PO),
+        "pe" => Operand::Condition(Condition::PE),
+        "p" => Operand::Condition(Condition::P),
+        "m" => Operand::Condition(Condition::M),
+        _ => return None,
+    };
+
+    Some(operand)
+}
+
+/// Matches any operand wrapped in parentheses, being an indirect register
+/// pair, an indexed address, the `(c)` port or an absolute address.
+fn parenthesized<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    right(join(
+        join(
+            expect_character('('),
+            zero_or_more(non_newline_whitespace()),
+        ),
+        left(join(
+            indirect(),
+            join(
+                zero_or_more(non_newline_whitespace()),
+                expect_character(')'),
+            ),
+        )),
+    ))
+}
+
+#[allow(clippy::redundant_closure)]
+fn indirect<'a>() -> impl parcel::Parser<'a, &'a [char], Operand<Value>> {
+    unsigned16()
+        .map(|v| Operand::Address(Value::Literal(i64::from(v))))
+        .or(|| {
+            join(name(), optional(displacement()))
+                .map(|(name, displacement)| {
+                    let operand = keyword(&name);
+                    match (operand, displacement) {
+                        (Some(Operand::RegisterPair(rp)), Some(d))
+                            if matches!(rp, RegisterPair::IX | RegisterPair::IY) =>
+                        {
+                            Some(Operand::Indexed(rp, d))
+                        }
+                        (Some(Operand::Register(Register::C)), None) => Some(Operand::Port),
+                        (Some(Operand::RegisterPair(rp)), None)
+                            if !matches!(rp, RegisterPair::AF) =>
+                        {
+                            Some(Operand::Indirect(rp))
+                        }
+                        (None, None) => Some(Operand::Address(Value::Reference(name))),
+                        _ => None,
+                    }
+                })
+                .predicate(|operand| operand.is_some())
+                .map(|operand| operand.unwrap())
+        })
+}
+
+/// Matches the signed displacement of an indexed operand, such as the `+4`
+/// of `(ix+4)`.
+#[allow(clippy::redundant_closure)]
+fn displacement<'a>() -> impl parcel::Parser<'a, &'a [char], Displacement<Value>> {
+    join(
+        right(join(
+            zero_or_more(non_newline_whitespace()),
+            expect_character('+').or(|| expect_character('-')),
+        )),
+        right(join(zero_or_more(non_newline_whitespace()), value())),
+    )
+    .map(|(sign, value)| Displacement::new(sign == '-', value))
+}
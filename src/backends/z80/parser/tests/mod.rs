@@ -0,0 +1,97 @@
+use crate::backends::z80::instruction_set::mnemonic::Mnemonic;
+use crate::backends::z80::instruction_set::{
+    Condition, Displacement, Instruction, Operand, Register, RegisterPair, Value,
+};
+use crate::backends::z80::parser::instruction;
+use parcel::prelude::v1::*;
+use parcel::MatchStatus;
+
+macro_rules! gen_inst_test {
+    ($input:expr, $mnemonic:expr, [$($operand:expr),*]) => {
+        let input = $input.chars().collect::<Vec<char>>();
+        assert_eq!(
+            Ok(MatchStatus::Match((
+                &input[input.len()..],
+                Instruction::new($mnemonic, vec![$($operand),*])
+            ))),
+            instruction().parse(&input)
+        );
+    };
+}
+
+#[test]
+fn should_parse_implied_instructions() {
+    gen_inst_test!("  ldir", Mnemonic::LDIR, []);
+    gen_inst_test!("EXX", Mnemonic::EXX, []);
+}
+
+#[test]
+fn should_parse_register_and_immediate_operands() {
+    gen_inst_test!(
+        "ld a, 0x12",
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::A),
+            Operand::Immediate(Value::Literal(0x12))
+        ]
+    );
+    gen_inst_test!(
+        "ex af,af'",
+        Mnemonic::EX,
+        [
+            Operand::RegisterPair(RegisterPair::AF),
+            Operand::RegisterPair(RegisterPair::AlternateAF)
+        ]
+    );
+}
+
+#[test]
+fn should_parse_indirect_and_indexed_operands() {
+    gen_inst_test!(
+        "ld (ix+5), a",
+        Mnemonic::LD,
+        [
+            Operand::Indexed(
+                RegisterPair::IX,
+                Displacement::new(false, Value::Literal(5))
+            ),
+            Operand::Register(Register::A)
+        ]
+    );
+    gen_inst_test!(
+        "ld b, ( iy - offset )",
+        Mnemonic::LD,
+        [
+            Operand::Register(Register::B),
+            Operand::Indexed(
+                RegisterPair::IY,
+                Displacement::new(true, Value::Reference("offset".to_string()))
+            )
+        ]
+    );
+    gen_inst_test!(
+        "out (c), a",
+        Mnemonic::OUT,
+        [Operand::Port, Operand::Register(Register::A)]
+    );
+    gen_inst_test!(
+        "ld hl, (table)",
+        Mnemonic::LD,
+        [
+            Operand::RegisterPair(RegisterPair::HL),
+            Operand::Address(Value::Reference("table".to_string()))
+        ]
+    );
+}
+
+#[test]
+fn should_parse_conditions_and_label_references() {
+    gen_inst_test!(
+        "jp nz, loop",
+        Mnemonic::JP,
+        [
+            Operand::Condition(Condition::NZ),
+            Operand::Immediate(Value::Reference("loop".to_string()))
+        ]
+    );
+}
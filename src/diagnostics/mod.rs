@@ -0,0 +1,67 @@
+/// Severity represents the level at which a diagnostic is reported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let output = match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+
+        write!(f, "{}", output)
+    }
+}
+
+/// SourceLocation represents the line of the source that a statement was
+/// parsed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceLocation {
+    pub line: usize,
+}
+
+impl SourceLocation {
+    pub fn new(line: usize) -> Self {
+        Self { line }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}", self.line)
+    }
+}
+
+/// Diagnostic represents a message raised during assembly that doesn't halt
+/// assembly, such as a failed warning-level assertion or a `.print`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub location: SourceLocation,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, location: SourceLocation, message: String) -> Self {
+        Self {
+            severity,
+            location,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.location, self.message)
+    }
+}
+
+/// DiagnosticSender provides the channel that backends report diagnostics
+/// over, leaving the presentation of each message to the caller.
+pub type DiagnosticSender = std::sync::mpsc::Sender<Diagnostic>;
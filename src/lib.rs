@@ -1,7 +1,17 @@
 use parcel::prelude::v1::*;
 mod addressing;
 mod backends;
-pub use backends::Backend;
+pub use backends::builtin;
+pub use backends::layout::{
+    parse_instruction_with, resolve_value, InstructionSet, SymbolTable, Value,
+};
+pub use backends::mos6502::CycleCount;
+pub use backends::registry::{InstructionSetBackend, Registry};
+pub use backends::sm83::cartridge::CartridgeHeader;
+pub use backends::table::description::IsaDescription;
+pub use backends::{Backend, BackendErr};
+pub use preparser::types::Endianness;
+pub mod diagnostics;
 mod parser;
 mod preparser;
 
@@ -101,6 +111,7 @@ impl Emitter<Vec<u8>> for Vec<Origin<Vec<u8>>> {
 }
 
 type AssembledOrigins = Vec<Origin<Vec<u8>>>;
+type PreparsedOrigins = Vec<Origin<Vec<preparser::Token<String>>>>;
 
 /// A type storing the results of an assemble representing an array of bytes
 /// or a String Error.
@@ -113,16 +124,138 @@ pub trait Assembler<T, U, E: std::fmt::Display> {
     fn assemble(&self, source: T) -> AssemblerResult<U, E>;
 }
 
+/// AssemblerOptions configures an assembly beyond its backend and source.
+#[derive(Default)]
+pub struct AssemblerOptions {
+    definitions: Vec<(String, u32)>,
+    diagnostics: Option<diagnostics::DiagnosticSender>,
+    relax_branches: bool,
+    cpu: Option<String>,
+}
+
+impl AssemblerOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// with_definition defines a symbol ahead of assembly, as if it were
+    /// defined at the start of the source, taking precedence over any
+    /// `.define` of the same symbol within the source.
+    pub fn with_definition(mut self, id: &str, value: u32) -> Self {
+        self.definitions.push((id.to_string(), value));
+        self
+    }
+
+    /// with_diagnostics sets the channel that non-fatal diagnostics are
+    /// reported over. Without a channel, diagnostics are discarded.
+    pub fn with_diagnostics(mut self, diagnostics: diagnostics::DiagnosticSender) -> Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    /// with_branch_relaxation enables rewriting any conditional branch whose
+    /// label target is out of range as an inverted branch over a `jmp`.
+    pub fn with_branch_relaxation(mut self) -> Self {
+        self.relax_branches = true;
+        self
+    }
+
+    /// with_cpu selects the processor targeted by the backend, taking
+    /// precedence over the backend's default until a `.cpu` directive is
+    /// encountered.
+    pub fn with_cpu(mut self, cpu: &str) -> Self {
+        self.cpu = Some(cpu.to_string());
+        self
+    }
+
+    /// Returns each symbol defined ahead of assembly with its value.
+    pub fn definitions(&self) -> &[(String, u32)] {
+        &self.definitions
+    }
+
+    /// Returns the channel that non-fatal diagnostics are reported over, if
+    /// one is set.
+    pub fn diagnostics(&self) -> Option<&diagnostics::DiagnosticSender> {
+        self.diagnostics.as_ref()
+    }
+
+    /// Returns true if out of range conditional branches are to be relaxed.
+    pub fn relax_branches(&self) -> bool {
+        self.relax_branches
+    }
+
+    /// Returns the processor selected for the backend, if any.
+    pub fn cpu(&self) -> Option<&str> {
+        self.cpu.as_deref()
+    }
+}
+
 // Converts a source string to it's corresponding array of little endinan binary
 // opcodes.
-pub fn assemble(backend: Backend, source: &str) -> AssemblerResult<AssembledOrigins, String> {
+pub fn assemble(backend: &dyn Backend, source: &str) -> AssemblerResult<AssembledOrigins, String> {
+    assemble_with_options(backend, source, AssemblerOptions::new())
+}
+
+// Converts a source string to it's corresponding array of little endinan binary
+// opcodes, applying any definitions and reporting diagnostics as configured by
+// the provided options.
+pub fn assemble_with_options(
+    backend: &dyn Backend,
+    source: &str,
+    options: AssemblerOptions,
+) -> AssemblerResult<AssembledOrigins, String> {
+    backend.assemble(source, options)
+}
+
+/// Assembles the source against a member of the 6502 family with the
+/// provided options, additionally summing the cycles taken by the
+/// instructions from the start label up to, but not including, the end label.
+pub fn assemble_counting_cycles(
+    backend: &builtin::Mos6502Family,
+    source: &str,
+    options: AssemblerOptions,
+    start: &str,
+    end: &str,
+) -> AssemblerResult<(AssembledOrigins, CycleCount), String> {
+    backend.assemble_counting_cycles(source, options, start, end)
+}
+
+// Preparses the source into its origins, injecting any definitions ahead of
+// the first origin's statements so that they are available to the entire
+// source, and dropping any `.define` of the same symbols so that definitions
+// override the source. Statements preceding any `.origin` directive are
+// placed at the default origin.
+fn preparse(
+    source: &str,
+    definitions: Vec<(String, u32)>,
+    default_origin: usize,
+) -> Result<PreparsedOrigins, String> {
     let input: Vec<char> = source.chars().collect();
-    let origin_tokens = preparser::PreParser::new()
+    let mut origin_tokens = preparser::PreParser::new()
+        .with_default_origin(default_origin)
         .parse(&input)
         .map(|ms| ms.unwrap())?;
 
-    match backend {
-        Backend::Mos6502 => backends::mos6502::Mos6502Assembler::new().assemble(origin_tokens),
+    for origin in origin_tokens.iter_mut() {
+        origin.instructions.retain(|token| match token {
+            preparser::Token::Symbol(id, Some(_)) => {
+                !definitions.iter().any(|(defined, _)| defined == id)
+            }
+            _ => true,
+        });
     }
-    .map_err(|e| e.to_string())
+
+    let definitions = definitions.into_iter().map(|(id, value)| {
+        let value = match value {
+            0..=0xff => preparser::types::LeByteEncodedValue::from(value as u8),
+            0x100..=0xffff => preparser::types::LeByteEncodedValue::from(value as u16),
+            _ => preparser::types::LeByteEncodedValue::from(value),
+        };
+        preparser::Token::Symbol(id, Some(value))
+    });
+    if let Some(head) = origin_tokens.first_mut() {
+        head.instructions.splice(0..0, definitions);
+    }
+
+    Ok(origin_tokens)
 }
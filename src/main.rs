@@ -1,7 +1,12 @@
 use scrap::prelude::v1::*;
-use spasm::assemble;
-use spasm::Backend;
+use spasm::assemble_counting_cycles;
+use spasm::assemble_with_options;
+use spasm::builtin;
+use spasm::AssemblerOptions;
+use spasm::CartridgeHeader;
 use spasm::Emitter;
+use spasm::IsaDescription;
+use spasm::Registry;
 use std::convert::TryFrom;
 use std::env;
 use std::fmt;
@@ -49,15 +54,50 @@ fn main() -> RuntimeResult<()> {
     )
     .optional()
     .with_default("a.out".to_string());
-    let backend_flag = scrap::Flag::with_choices(
+    let relax_flag = scrap::Flag::store_true(
+        "relax-branches",
+        "r",
+        "rewrite out of range branches as a branch over a jump.",
+    )
+    .optional();
+    let cpu_flag = scrap::FlagWithValue::new(
+        "cpu",
+        "c",
+        "a processor to target, overriding the backend default. such as 6502x for undocumented opcodes, 8085 for the i8080 backend or 6803 for the 6800 backend.",
+        scrap::StringValue,
+    )
+    .optional();
+    let cycles_flag = scrap::FlagWithValue::new(
+        "cycles",
+        "C",
+        "report the cycles taken between two labels, given as start,end.",
+        scrap::StringValue,
+    )
+    .optional();
+    let cartridge_header_flag = scrap::FlagWithValue::new(
+        "cartridge-header",
+        "g",
+        "fill in the cartridge header of an sm83 rom, given as title[,type].",
+        scrap::StringValue,
+    )
+    .optional();
+    let isa_file_flag = scrap::FlagWithValue::new(
+        "isa-file",
+        "i",
+        "a description of a custom instruction set to assemble against, overriding the backend.",
+        scrap::StringValue,
+    )
+    .optional();
+    let backend_flag = scrap::FlagWithValue::new(
         "backend",
         "b",
-        "a target architecture backend.",
-        ["mos6502".to_string()],
+        "a target architecture backend, as listed by --list-backends.",
         scrap::StringValue,
     )
     .optional()
     .with_default("mos6502".to_string());
+    let list_backends_flag =
+        scrap::Flag::store_true("list-backends", "l", "list the available backends.").optional();
 
     let cmd_group = scrap::CmdGroup::new("spasm")
         .description("An experimental multi-target assembler.")
@@ -65,25 +105,60 @@ fn main() -> RuntimeResult<()> {
         .version(CMD_VERSION)
         .with_command(
             scrap::Cmd::new("assemble")
-                .description("assemble a source file into its corresponding binary format")
+                .description(
+                    "assemble a source file into its corresponding binary format. symbols may be defined with one or more -D NAME[=VALUE] flags, overriding any .define of the same name.",
+                )
                 .with_flag(version_flag)
                 .with_flag(output_flag)
                 .with_flag(backend_flag)
+                .with_flag(list_backends_flag)
+                .with_flag(relax_flag)
+                .with_flag(cpu_flag)
+                .with_flag(cycles_flag)
+                .with_flag(cartridge_header_flag)
+                .with_flag(isa_file_flag)
                 .with_flag(help_flag)
                 .with_helpstring_and_args_handler(
-                    |help_string, args, (((version, output), backend), help)| {
+                    move |help_string, args, (((((((((version, output), backend), list_backends), relax), cpu), cycles), cartridge_header), isa_file), help)| {
                         if help.is_some() {
                             println!("{}", help_string);
                             Ok(())
                         } else if version.is_some() {
                             println!("{}", CMD_VERSION);
                             Ok(())
+                        } else if list_backends.is_some() {
+                            for name in Registry::new().names() {
+                                println!("{}", name);
+                            }
+                            Ok(())
                         } else {
-                            args.into_iter()
-                                .map(|path| {
-                                    let in_f = path.unwrap();
+                            // definitions are taken from the arguments left
+                            // unmatched by any flag, so that a flag's value is
+                            // never read as a definition.
+                            let args = args.into_iter().map(|arg| arg.unwrap()).collect::<Vec<_>>();
+                            let (definitions, paths) = split_definitions(
+                                &args.iter().map(AsRef::<str>::as_ref).collect::<Vec<&str>>(),
+                            )?;
+
+                            let object_options = ObjectOptions {
+                                backend: &backend,
+                                cycles: cycles.as_deref(),
+                                cartridge_header: cartridge_header.as_deref(),
+                                isa_file: isa_file.as_deref(),
+                            };
+
+                            paths.into_iter()
+                                .map(|in_f| {
                                     read_src_file(in_f)
-                                        .and_then(|input| assemble_object(&backend, input))
+                                        .and_then(|input| {
+                                            let options = assembler_options(
+                                                &definitions,
+                                                relax.is_some(),
+                                                cpu.as_deref(),
+                                            );
+
+                                            assemble_object(options, &object_options, &input)
+                                        })
                                         .and_then(|bin_data| write_dest_file(&output, &bin_data))
                                 })
                                 .collect::<Result<Vec<()>, _>>()
@@ -116,6 +191,86 @@ fn main() -> RuntimeResult<()> {
         )
 }
 
+/// Separates any `-D NAME[=VALUE]` symbol definitions, which may be repeated,
+/// from the remaining arguments, returning an error if a symbol is defined
+/// more than once.
+fn split_definitions<'a>(args: &[&'a str]) -> RuntimeResult<(Vec<(String, u32)>, Vec<&'a str>)> {
+    let mut definitions: Vec<(String, u32)> = Vec::new();
+    let mut remaining = Vec::new();
+    let mut args_iter = args.iter().copied();
+
+    while let Some(arg) = args_iter.next() {
+        let definition = match (arg, arg.strip_prefix("-D")) {
+            ("-D" | "--define", _) => args_iter.next().ok_or_else(|| {
+                RuntimeError::InvalidArguments(format!("{} requires a symbol definition", arg))
+            })?,
+            (_, Some(definition)) => definition,
+            _ => {
+                remaining.push(arg);
+                continue;
+            }
+        };
+
+        let (id, value) = parse_definition(definition)?;
+        if definitions.iter().any(|(defined, _)| *defined == id) {
+            return Err(RuntimeError::InvalidArguments(format!(
+                "symbol defined more than once: {}",
+                id
+            )));
+        }
+        definitions.push((id, value));
+    }
+
+    Ok((definitions, remaining))
+}
+
+/// Parses a `NAME[=VALUE]` definition, defaulting to a value of 1 when no
+/// value is provided.
+fn parse_definition(definition: &str) -> RuntimeResult<(String, u32)> {
+    let (id, value) = match definition.split_once('=') {
+        Some((id, value)) => (id, parse_definition_value(value)),
+        None => (definition, Some(1)),
+    };
+
+    match value {
+        Some(v) if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Ok((id.to_string(), v))
+        }
+        _ => Err(RuntimeError::InvalidArguments(format!(
+            "invalid symbol definition: {}",
+            definition
+        ))),
+    }
+}
+
+fn parse_definition_value(value: &str) -> Option<u32> {
+    if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix('$')) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = value.strip_prefix("0b") {
+        u32::from_str_radix(bin, 2).ok()
+    } else {
+        value.parse::<u32>().ok()
+    }
+}
+
+/// Parses a `TITLE[,TYPE]` cartridge header, defaulting to a ROM only
+/// cartridge when no type is provided.
+fn parse_cartridge_header(header: &str) -> RuntimeResult<CartridgeHeader> {
+    let (title, cartridge_type) = match header.split_once(',') {
+        Some((title, cartridge_type)) => (
+            title,
+            parse_definition_value(cartridge_type).and_then(|v| u8::try_from(v).ok()),
+        ),
+        None => (header, Some(0)),
+    };
+
+    cartridge_type
+        .map(|cartridge_type| CartridgeHeader::new(title).with_cartridge_type(cartridge_type))
+        .ok_or_else(|| {
+            RuntimeError::InvalidArguments(format!("invalid cartridge header: {}", header))
+        })
+}
+
 fn read_src_file<F: AsRef<str>>(filename: F) -> RuntimeResult<String> {
     let mut f = File::open(filename.as_ref()).map_err(|_| RuntimeError::FileUnreadable)?;
 
@@ -126,6 +281,13 @@ fn read_src_file<F: AsRef<str>>(filename: F) -> RuntimeResult<String> {
     }
 }
 
+fn read_isa_file(filename: &str) -> RuntimeResult<IsaDescription> {
+    read_src_file(filename).and_then(|src| {
+        IsaDescription::try_from(src.as_str())
+            .map_err(|e| RuntimeError::InvalidArguments(format!("{}: {}", filename, e)))
+    })
+}
+
 fn write_dest_file<F: AsRef<str>>(filename: F, data: &[u8]) -> RuntimeResult<()> {
     let mut f = OpenOptions::new()
         .truncate(true)
@@ -140,17 +302,182 @@ fn write_dest_file<F: AsRef<str>>(filename: F, data: &[u8]) -> RuntimeResult<()>
     }
 }
 
-fn assemble_object<B, S>(backend: B, asm_src: S) -> RuntimeResult<Vec<u8>>
-where
-    B: AsRef<str>,
-    S: AsRef<str>,
-{
-    let backend: Backend = Backend::try_from(backend.as_ref()).map_err(|_| {
-        RuntimeError::InvalidArguments(format!("unknown backend: {}", backend.as_ref()))
-    })?;
+fn assembler_options(
+    definitions: &[(String, u32)],
+    relax_branches: bool,
+    cpu: Option<&str>,
+) -> AssemblerOptions {
+    let options = definitions
+        .iter()
+        .fold(AssemblerOptions::new(), |options, (id, value)| {
+            options.with_definition(id, *value)
+        });
+    let options = if relax_branches {
+        options.with_branch_relaxation()
+    } else {
+        options
+    };
+    match cpu {
+        Some(cpu) => options.with_cpu(cpu),
+        None => options,
+    }
+}
+
+/// ObjectOptions configures the selection of a backend and the output of an
+/// object, beyond the [AssemblerOptions] of its assembly.
+struct ObjectOptions<'a> {
+    backend: &'a str,
+    cycles: Option<&'a str>,
+    cartridge_header: Option<&'a str>,
+    isa_file: Option<&'a str>,
+}
+
+fn assemble_object(
+    options: AssemblerOptions,
+    object_options: &ObjectOptions,
+    asm_src: &str,
+) -> RuntimeResult<Vec<u8>> {
+    // a description is registered as the table backend, taking the place of
+    // the selected backend.
+    let (registry, name) = match object_options.isa_file {
+        Some(path) => (
+            Registry::new().with_backend(builtin::Table::new(read_isa_file(path)?)),
+            "table",
+        ),
+        None => (Registry::new(), object_options.backend),
+    };
+    let backend = registry
+        .get(name)
+        .ok_or_else(|| RuntimeError::InvalidArguments(format!("unknown backend: {}", name)))?;
+
+    let range = object_options
+        .cycles
+        .map(|range| {
+            range
+                .split_once(',')
+                .map(|(start, end)| (start.trim(), end.trim()))
+                .ok_or_else(|| {
+                    RuntimeError::InvalidArguments(format!("invalid cycle range: {}", range))
+                })
+        })
+        .transpose()?;
+
+    let (diagnostics_tx, diagnostics_rx) = std::sync::mpsc::channel();
+    let options = options.with_diagnostics(diagnostics_tx);
+    let res = match range {
+        // cycle counts are only tracked by the 6502 family backends.
+        Some((start, end)) => match builtin::mos6502_family(backend.name()) {
+            Some(family) => assemble_counting_cycles(&family, asm_src, options, start, end)
+                .map(|(obj, count)| (obj, Some((start, end, count)))),
+            None => Err(format!(
+                "cycle counts are unsupported by the {} backend",
+                backend.name()
+            )),
+        },
+        None => assemble_with_options(backend, asm_src, options).map(|obj| (obj, None)),
+    };
+
+    // report diagnostics regardless of whether assembly succeeded.
+    for diagnostic in diagnostics_rx.try_iter() {
+        eprintln!("{}", diagnostic);
+    }
+
+    let (obj, cycle_count) = res.map_err(RuntimeError::Undefined)?;
+    let bin: Vec<u8> = match object_options.cartridge_header {
+        Some(_) if backend.name() != "sm83" => {
+            return Err(RuntimeError::InvalidArguments(
+                "a cartridge header requires the sm83 backend".to_string(),
+            ))
+        }
+        Some(header) => parse_cartridge_header(header)?
+            .apply(&obj)
+            .map_err(RuntimeError::Undefined)?,
+        None => obj.emit(),
+    };
 
-    let obj = assemble(backend, asm_src.as_ref()).map_err(RuntimeError::Undefined)?;
-    let bin: Vec<u8> = obj.emit();
+    if let Some((start, end, count)) = cycle_count {
+        println!(
+            "{} to {}: {} cycles, {} worst case",
+            start, end, count.base, count.worst_case
+        );
+    }
 
     Ok(bin)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_definitions_with_and_without_values() {
+        for (definition, expected) in [
+            ("DEBUG", ("DEBUG", 1)),
+            ("BOARD=2", ("BOARD", 2)),
+            ("BASE=0xc000", ("BASE", 0xc000)),
+            ("BASE=$C000", ("BASE", 0xc000)),
+            ("MASK=0b101", ("MASK", 5)),
+        ] {
+            assert_eq!(
+                Ok((expected.0.to_string(), expected.1)),
+                parse_definition(definition).map_err(|e| e.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_definitions() {
+        for definition in [
+            "",
+            "=1",
+            "BOARD=",
+            "BOARD=two",
+            "BOARD=0x",
+            "BOARD2=1",
+            "BOARD=1=2",
+        ] {
+            assert_eq!(
+                Err(format!("invalid symbol definition: {}", definition)),
+                parse_definition(definition).map_err(|e| e.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn should_split_definitions_from_the_remaining_arguments() {
+        let (definitions, remaining) =
+            split_definitions(&["-DDEBUG", "main.asm", "-D", "BOARD=2", "--define", "REV=3"])
+                .map_err(|e| e.to_string())
+                .unwrap();
+
+        assert_eq!(
+            vec![
+                ("DEBUG".to_string(), 1),
+                ("BOARD".to_string(), 2),
+                ("REV".to_string(), 3)
+            ],
+            definitions
+        );
+        assert_eq!(vec!["main.asm"], remaining);
+    }
+
+    #[test]
+    fn should_reject_invalid_and_missing_definitions_when_splitting() {
+        assert_eq!(
+            Err("invalid symbol definition: BOARD=two".to_string()),
+            split_definitions(&["-DBOARD=two", "main.asm"]).map_err(|e| e.to_string())
+        );
+        assert_eq!(
+            Err("-D requires a symbol definition".to_string()),
+            split_definitions(&["main.asm", "-D"]).map_err(|e| e.to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_a_symbol_defined_more_than_once() {
+        assert_eq!(
+            Err("symbol defined more than once: BOARD".to_string()),
+            split_definitions(&["-DBOARD=1", "-D", "BOARD=2"]).map_err(|e| e.to_string())
+        );
+    }
+}
@@ -1,6 +1,8 @@
-use parcel::parsers::character::{eof, expect_character, expect_str, whitespace};
+use parcel::parsers::character::{alphabetic, eof, expect_character, expect_str, whitespace};
 use parcel::prelude::v1::*;
 use parcel::MatchStatus;
+
+use crate::backends::layout::Value;
 use parcel::{join, one_or_more, optional, right, take_n, take_until_n};
 
 #[cfg(test)]
@@ -53,11 +55,31 @@ pub fn non_whitespace_character<'a>() -> impl Parser<'a, &'a [char], char> {
     }
 }
 
+/// Matches a 16-bit literal or a reference to a symbol by name, as taken by
+/// the operands of most backends.
+#[allow(clippy::redundant_closure)]
+pub fn value<'a>() -> impl Parser<'a, &'a [char], Value> {
+    unsigned16()
+        .map(|v| Value::Literal(i64::from(v)))
+        .or(|| name().map(Value::Reference))
+}
+
+/// Matches the name of a symbol, label or register.
+pub fn name<'a>() -> impl Parser<'a, &'a [char], String> {
+    one_or_more(alphabetic()).map(|n| n.into_iter().collect())
+}
+
 #[allow(clippy::redundant_closure)]
 pub fn unsigned32<'a>() -> impl Parser<'a, &'a [char], u32> {
     hex_u32().or(|| binary_u32()).or(|| dec_u32())
 }
 
+/// Matches a 24-bit unsigned value, such as the long addresses of the 65816.
+#[allow(clippy::redundant_closure)]
+pub fn unsigned24<'a>() -> impl Parser<'a, &'a [char], u32> {
+    hex_u24().or(|| dec_u32().predicate(|v| *v <= 0x00ff_ffff))
+}
+
 #[allow(clippy::redundant_closure)]
 pub fn unsigned16<'a>() -> impl Parser<'a, &'a [char], u16> {
     hex_u16().or(|| binary_u16()).or(|| dec_u16())
@@ -85,15 +107,23 @@ fn sign<'a>() -> impl Parser<'a, &'a [char], Sign> {
 
 fn hex_u32<'a>() -> impl Parser<'a, &'a [char], u32> {
     right(join(
-        expect_str("0x"),
+        hex_prefix(),
         hex_bytes(4).peek_next(special_character().or(|| whitespace().or(eof))),
     ))
     .map(|hex| char_vec_to_u32_from_radix!(hex, 16))
 }
 
+fn hex_u24<'a>() -> impl Parser<'a, &'a [char], u32> {
+    right(join(
+        hex_prefix(),
+        hex_bytes(3).peek_next(special_character().or(|| whitespace().or(eof))),
+    ))
+    .map(|hex| char_vec_to_u32_from_radix!(hex, 16))
+}
+
 fn hex_u16<'a>() -> impl Parser<'a, &'a [char], u16> {
     right(join(
-        expect_str("0x"),
+        hex_prefix(),
         hex_bytes(2).peek_next(special_character().or(|| whitespace().or(eof))),
     ))
     .map(|hex| char_vec_to_u16_from_radix!(hex, 16))
@@ -101,7 +131,7 @@ fn hex_u16<'a>() -> impl Parser<'a, &'a [char], u16> {
 
 fn hex_u8<'a>() -> impl Parser<'a, &'a [char], u8> {
     right(join(
-        expect_str("0x"),
+        hex_prefix(),
         hex_bytes(1).peek_next(special_character().or(|| whitespace().or(eof))),
     ))
     .map(|hex| char_vec_to_u8_from_radix!(hex, 16))
@@ -109,12 +139,17 @@ fn hex_u8<'a>() -> impl Parser<'a, &'a [char], u8> {
 
 fn hex_i8<'a>() -> impl Parser<'a, &'a [char], i8> {
     right(join(
-        expect_str("0x"),
+        hex_prefix(),
         hex_bytes(1).peek_next(special_character().or(|| whitespace().or(eof))),
     ))
     .map(|hex| char_vec_to_i8_from_radix!(hex, 16))
 }
 
+/// Matches either of the `0x` or `$` hexadecimal prefixes.
+fn hex_prefix<'a>() -> impl Parser<'a, &'a [char], ()> {
+    expect_str("0x").or(|| expect_str("$")).map(|_| ())
+}
+
 pub fn hex_bytes<'a>(bytes: usize) -> impl Parser<'a, &'a [char], Vec<char>> {
     take_until_n(hex_digit(), bytes * 2)
 }
@@ -244,7 +279,7 @@ pub fn decimal<'a>() -> impl Parser<'a, &'a [char], char> {
 pub fn special_character<'a>() -> impl Parser<'a, &'a [char], char> {
     let special = [
         '-', '_', '\\', '|', '#', '&', '’', '(', ')', '*', '+', ',', '.', '/', ':', ';', '<', '=',
-        '>',
+        '>', '[', ']', '!', '%', '^',
     ];
     move |input: &'a [char]| match input.first() {
         Some(&next) if special.contains(&next) => Ok(MatchStatus::Match((&input[1..], next))),
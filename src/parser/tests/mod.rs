@@ -23,3 +23,13 @@ fn should_parse_a_matching_u8_hex_value_into_any_encompassing_unsigned_integer()
         hex_u32().parse(&input)
     );
 }
+
+#[test]
+fn should_parse_a_dollar_prefixed_hex_value() {
+    let input: Vec<char> = "$C000".chars().collect();
+
+    assert_eq!(
+        Ok(MatchStatus::Match((&input[5..], 0xc000))),
+        hex_u16().parse(&input)
+    );
+}
@@ -0,0 +1,360 @@
+use crate::parser::unsigned32;
+use crate::preparser::SymbolId;
+use parcel::parsers::character::*;
+use parcel::prelude::v1::*;
+use parcel::{join, left, one_or_more, right, zero_or_more};
+
+/// ExpressionErr represents the failure states of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionErr {
+    UndefinedReference(SymbolId),
+    DivideByZero,
+}
+
+impl std::fmt::Display for ExpressionErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UndefinedReference(id) => write!(f, "reference undefined: {}", id),
+            Self::DivideByZero => write!(f, "divide by zero"),
+        }
+    }
+}
+
+/// UnaryOperator represents the operators that take a single operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Negate,
+    LogicalNot,
+    BitwiseNot,
+    /// BankByte selects bits 16-23 of its operand, the bank of a 24-bit
+    /// address.
+    BankByte,
+}
+
+/// BinaryOperator represents the operators that take a left and right operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Multiply,
+    Divide,
+    Modulo,
+    Add,
+    Subtract,
+    ShiftLeft,
+    ShiftRight,
+    BitwiseAnd,
+    BitwiseXor,
+    BitwiseOr,
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LogicalAnd,
+    LogicalOr,
+}
+
+/// Expression represents an arithmetic or logical expression that can't be
+/// evaluated until all references, including labels, have been resolved by
+/// the backend.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Literal(u32),
+    Reference(SymbolId),
+    /// CurrentLocation represents the `*` operand, evaluating to the offset
+    /// of the statement the expression belongs to.
+    CurrentLocation,
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// evaluate reduces an expression to a value, resolving references with
+    /// the provided lookup function and `*` to the provided location.
+    pub fn evaluate<F>(&self, location: usize, lookup: &F) -> Result<i64, ExpressionErr>
+    where
+        F: Fn(&str) -> Option<i64>,
+    {
+        match self {
+            Self::Literal(v) => Ok(i64::from(*v)),
+            Self::Reference(id) => {
+                lookup(id).ok_or_else(|| ExpressionErr::UndefinedReference(id.clone()))
+            }
+            Self::CurrentLocation => Ok(location as i64),
+            Self::Unary(op, expr) => {
+                let v = expr.evaluate(location, lookup)?;
+                Ok(match op {
+                    UnaryOperator::Negate => v.wrapping_neg(),
+                    UnaryOperator::LogicalNot => i64::from(v == 0),
+                    UnaryOperator::BitwiseNot => !v,
+                    UnaryOperator::BankByte => (v >> 16) & 0xff,
+                })
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let l = lhs.evaluate(location, lookup)?;
+                let r = rhs.evaluate(location, lookup)?;
+                match op {
+                    BinaryOperator::Multiply => Ok(l.wrapping_mul(r)),
+                    BinaryOperator::Divide if r == 0 => Err(ExpressionErr::DivideByZero),
+                    BinaryOperator::Divide => Ok(l.wrapping_div(r)),
+                    BinaryOperator::Modulo if r == 0 => Err(ExpressionErr::DivideByZero),
+                    BinaryOperator::Modulo => Ok(l.wrapping_rem(r)),
+                    BinaryOperator::Add => Ok(l.wrapping_add(r)),
+                    BinaryOperator::Subtract => Ok(l.wrapping_sub(r)),
+                    BinaryOperator::ShiftLeft => Ok(l.wrapping_shl(r as u32)),
+                    BinaryOperator::ShiftRight => Ok(l.wrapping_shr(r as u32)),
+                    BinaryOperator::BitwiseAnd => Ok(l & r),
+                    BinaryOperator::BitwiseXor => Ok(l ^ r),
+                    BinaryOperator::BitwiseOr => Ok(l | r),
+                    BinaryOperator::Equal => Ok(i64::from(l == r)),
+                    BinaryOperator::NotEqual => Ok(i64::from(l != r)),
+                    BinaryOperator::LessThan => Ok(i64::from(l < r)),
+                    BinaryOperator::LessThanOrEqual => Ok(i64::from(l <= r)),
+                    BinaryOperator::GreaterThan => Ok(i64::from(l > r)),
+                    BinaryOperator::GreaterThanOrEqual => Ok(i64::from(l >= r)),
+                    BinaryOperator::LogicalAnd => Ok(i64::from(l != 0 && r != 0)),
+                    BinaryOperator::LogicalOr => Ok(i64::from(l != 0 || r != 0)),
+                }
+            }
+        }
+    }
+}
+
+/// Binary operators grouped by precedence from loosest to tightest binding,
+/// with equality binding more loosely than the relational operators, as in C.
+const PRECEDENCE: [&[BinaryOperator]; 10] = [
+    &[BinaryOperator::LogicalOr],
+    &[BinaryOperator::LogicalAnd],
+    &[BinaryOperator::Equal, BinaryOperator::NotEqual],
+    &[
+        BinaryOperator::LessThan,
+        BinaryOperator::LessThanOrEqual,
+        BinaryOperator::GreaterThan,
+        BinaryOperator::GreaterThanOrEqual,
+    ],
+    &[BinaryOperator::BitwiseOr],
+    &[BinaryOperator::BitwiseXor],
+    &[BinaryOperator::BitwiseAnd],
+    &[BinaryOperator::ShiftLeft, BinaryOperator::ShiftRight],
+    &[BinaryOperator::Add, BinaryOperator::Subtract],
+    &[
+        BinaryOperator::Multiply,
+        BinaryOperator::Divide,
+        BinaryOperator::Modulo,
+    ],
+];
+
+/// The text of each binary operator. Longer operators are listed ahead of
+/// their prefixes so that `<=` is never read as `<`, and the start of a
+/// trailing comment is listed as no operator so that it ends the expression
+/// rather than dividing it.
+const OPERATORS: [(&str, Option<BinaryOperator>); 20] = [
+    ("//", None),
+    ("/*", None),
+    ("||", Some(BinaryOperator::LogicalOr)),
+    ("&&", Some(BinaryOperator::LogicalAnd)),
+    ("==", Some(BinaryOperator::Equal)),
+    ("!=", Some(BinaryOperator::NotEqual)),
+    ("<=", Some(BinaryOperator::LessThanOrEqual)),
+    (">=", Some(BinaryOperator::GreaterThanOrEqual)),
+    ("<<", Some(BinaryOperator::ShiftLeft)),
+    (">>", Some(BinaryOperator::ShiftRight)),
+    ("<", Some(BinaryOperator::LessThan)),
+    (">", Some(BinaryOperator::GreaterThan)),
+    ("|", Some(BinaryOperator::BitwiseOr)),
+    ("^", Some(BinaryOperator::BitwiseXor)),
+    ("&", Some(BinaryOperator::BitwiseAnd)),
+    ("+", Some(BinaryOperator::Add)),
+    ("-", Some(BinaryOperator::Subtract)),
+    ("*", Some(BinaryOperator::Multiply)),
+    ("/", Some(BinaryOperator::Divide)),
+    ("%", Some(BinaryOperator::Modulo)),
+];
+
+/// expression parses an infix expression of literals, references and `*`,
+/// stopping at the first character that can't continue the expression.
+pub fn expression<'a>() -> impl parcel::Parser<'a, &'a [char], Expression> {
+    binary(0)
+}
+
+/// Matches a left-associative chain of the operators at the provided level
+/// of precedence, with each operand bound at the next level.
+fn binary<'a>(level: usize) -> impl parcel::Parser<'a, &'a [char], Expression> {
+    move |input: &'a [char]| {
+        if level == PRECEDENCE.len() {
+            return unary().parse(input);
+        }
+
+        join(
+            binary(level + 1),
+            zero_or_more(join(
+                right(join(
+                    zero_or_more(non_newline_whitespace()),
+                    binary_operator(level),
+                )),
+                right(join(
+                    zero_or_more(non_newline_whitespace()),
+                    binary(level + 1),
+                )),
+            )),
+        )
+        .map(|(head, tail)| {
+            tail.into_iter().fold(head, |lhs, (op, rhs)| {
+                Expression::Binary(op, Box::new(lhs), Box::new(rhs))
+            })
+        })
+        .parse(input)
+    }
+}
+
+/// Matches any binary operator belonging to the provided level of
+/// precedence.
+fn binary_operator<'a>(level: usize) -> impl parcel::Parser<'a, &'a [char], BinaryOperator> {
+    move |input: &'a [char]| {
+        let matched =
+            OPERATORS
+                .iter()
+                .find_map(|(token, op)| match expect_str(*token).parse(input) {
+                    Ok(MatchStatus::Match((remainder, _))) => Some((remainder, *op)),
+                    _ => None,
+                });
+
+        match matched {
+            Some((remainder, Some(op))) if PRECEDENCE[level].contains(&op) => {
+                Ok(MatchStatus::Match((remainder, op)))
+            }
+            _ => Ok(MatchStatus::NoMatch(input)),
+        }
+    }
+}
+
+fn unary<'a>() -> impl parcel::Parser<'a, &'a [char], Expression> {
+    join(
+        zero_or_more(left(join(
+            unary_operator(),
+            zero_or_more(non_newline_whitespace()),
+        ))),
+        primary(),
+    )
+    .map(|(ops, expr)| {
+        ops.into_iter()
+            .rev()
+            .fold(expr, |expr, op| Expression::Unary(op, Box::new(expr)))
+    })
+}
+
+#[allow(clippy::redundant_closure)]
+fn unary_operator<'a>() -> impl parcel::Parser<'a, &'a [char], UnaryOperator> {
+    expect_character('-')
+        .map(|_| UnaryOperator::Negate)
+        .or(|| expect_character('!').map(|_| UnaryOperator::LogicalNot))
+        .or(|| expect_character('~').map(|_| UnaryOperator::BitwiseNot))
+        .or(|| expect_character('^').map(|_| UnaryOperator::BankByte))
+}
+
+#[allow(clippy::redundant_closure)]
+fn primary<'a>() -> impl parcel::Parser<'a, &'a [char], Expression> {
+    move |input: &'a [char]| {
+        right(join(
+            join(
+                expect_character('('),
+                zero_or_more(non_newline_whitespace()),
+            ),
+            left(join(
+                expression(),
+                join(
+                    zero_or_more(non_newline_whitespace()),
+                    expect_character(')'),
+                ),
+            )),
+        ))
+        .or(|| expect_character('*').map(|_| Expression::CurrentLocation))
+        .or(|| one_or_more(alphabetic()).map(|id| Expression::Reference(id.into_iter().collect())))
+        .or(|| unsigned32().map(Expression::Literal))
+        .or(|| {
+            right(join(
+                expect_character('\''),
+                left(join(
+                    alphabetic().predicate(|c| c.is_ascii_alphabetic()),
+                    expect_character('\''),
+                )),
+            ))
+            .map(|c| Expression::Literal(c as u32))
+        })
+        .parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parcel::prelude::v1::*;
+
+    fn eval(input: &str) -> Result<i64, ExpressionErr> {
+        let input: Vec<char> = input.chars().collect();
+        let expr = expression().parse(&input).unwrap().unwrap();
+        let lookup = |id: &str| match id {
+            "end" => Some(0xc100),
+            _ => None,
+        };
+
+        expr.evaluate(0x10, &lookup)
+    }
+
+    #[test]
+    fn should_evaluate_arithmetic_with_precedence() {
+        assert_eq!(Ok(14), eval("2 + 3 * 4"));
+        assert_eq!(Ok(20), eval("(2 + 3) * 4"));
+        assert_eq!(Ok(-4), eval("-(2 + 2)"));
+        assert_eq!(Ok(0x10), eval("1 << 4"));
+    }
+
+    #[test]
+    fn should_evaluate_literals_in_each_radix() {
+        assert_eq!(Ok(0xc000), eval("$C000"));
+        assert_eq!(Ok(0xc000), eval("0xc000"));
+        assert_eq!(Ok(5), eval("0b00000000000000000000000000000101"));
+        assert_eq!(Ok(97), eval("'a'"));
+    }
+
+    #[test]
+    fn should_evaluate_comparisons_to_a_boolean_value() {
+        assert_eq!(Ok(0), eval("end <= $C000"));
+        assert_eq!(Ok(1), eval("end > $C000 && 1"));
+        assert_eq!(Ok(1), eval("1 < 2 == 1"));
+        assert_eq!(Ok(0), eval("3 == 2 < 3"));
+        assert_eq!(Ok(1), eval("2 != 3 < 1"));
+    }
+
+    #[test]
+    fn should_evaluate_bank_bytes() {
+        assert_eq!(Ok(0x12), eval("^$12C000"));
+        assert_eq!(Ok(0x13), eval("^$12C000 + 1"));
+        assert_eq!(Ok(0x00), eval("^end"));
+    }
+
+    #[test]
+    fn should_stop_at_a_trailing_comment() {
+        let input: Vec<char> = "4 / 2 // halved".chars().collect();
+        let (remainder, expr) = match expression().parse(&input) {
+            Ok(MatchStatus::Match(m)) => m,
+            _ => panic!("expected a match"),
+        };
+
+        assert_eq!(" // halved", remainder.iter().collect::<String>());
+        assert_eq!(Ok(2), expr.evaluate(0, &|_: &str| None));
+    }
+
+    #[test]
+    fn should_resolve_current_location() {
+        assert_eq!(Ok(0xffea), eval("$FFFA-*"));
+    }
+
+    #[test]
+    fn should_error_on_undefined_references() {
+        assert_eq!(
+            Err(ExpressionErr::UndefinedReference("start".to_string())),
+            eval("start + 1")
+        );
+    }
+}
@@ -1,7 +1,9 @@
+use crate::diagnostics::{Severity, SourceLocation};
 use crate::Origin;
 use parcel::parsers::character::*;
 use parcel::prelude::v1::*;
 use parcel::{join, left, one_of, one_or_more, optional, right, zero_or_more};
+use std::rc::Rc;
 
 // Pull in shared combinators
 use crate::parser::*;
@@ -9,6 +11,7 @@ use crate::parser::*;
 #[cfg(test)]
 mod tests;
 
+pub mod expression;
 pub mod types;
 
 /// SymbolId represents a symbol identifier.
@@ -22,26 +25,162 @@ pub enum PrimitiveOrReference {
     Reference(String),
 }
 
+/// Assertion represents a condition that must hold once all labels have been
+/// resolved, reporting its message at the given severity otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion {
+    pub expression: expression::Expression,
+    pub severity: Severity,
+    pub message: String,
+    pub location: SourceLocation,
+}
+
+impl Assertion {
+    pub fn new(
+        expression: expression::Expression,
+        severity: Severity,
+        message: String,
+        location: SourceLocation,
+    ) -> Self {
+        Self {
+            expression,
+            severity,
+            message,
+            location,
+        }
+    }
+}
+
+/// CycleAssertion represents a requirement that the instructions between two
+/// labels take exactly the given number of cycles, excluding any penalties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleAssertion {
+    pub start: SymbolId,
+    pub end: SymbolId,
+    pub cycles: usize,
+    pub location: SourceLocation,
+}
+
+impl CycleAssertion {
+    pub fn new(start: SymbolId, end: SymbolId, cycles: usize, location: SourceLocation) -> Self {
+        Self {
+            start,
+            end,
+            cycles,
+            location,
+        }
+    }
+}
+
+/// MessageFragment represents a single argument to a message directive,
+/// either literal text or an expression to be interpolated once evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageFragment {
+    Text(String),
+    Expression(expression::Expression),
+}
+
+/// Message represents a user-emitted diagnostic from a `.print`, `.warning`
+/// or `.error` directive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub severity: Severity,
+    pub fragments: Vec<MessageFragment>,
+    pub location: SourceLocation,
+}
+
+impl Message {
+    pub fn new(
+        severity: Severity,
+        fragments: Vec<MessageFragment>,
+        location: SourceLocation,
+    ) -> Self {
+        Self {
+            severity,
+            fragments,
+            location,
+        }
+    }
+}
+
+/// Vectors represents the labels that the 6502's interrupt vectors point to,
+/// placed as little-endian words at `0xfffa` in the order nmi, reset and irq.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vectors {
+    pub nmi: SymbolId,
+    pub reset: SymbolId,
+    pub irq: SymbolId,
+    pub location: SourceLocation,
+}
+
+impl Vectors {
+    pub fn new(nmi: SymbolId, reset: SymbolId, irq: SymbolId, location: SourceLocation) -> Self {
+        Self {
+            nmi,
+            reset,
+            irq,
+            location,
+        }
+    }
+}
+
+/// Register represents a processor register whose width may be set by a
+/// directive, such as the 65816's `.a16`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    Accumulator,
+    Index,
+}
+
 /// Token wraps the token variants that can be derived from the
 /// parser.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token<T> {
-    Instruction(T),
+    /// Instruction represents a single instruction, recording its line in the
+    /// source for reporting.
+    Instruction(T, SourceLocation),
     /// Symbol represents any symbolic value with an optionally defined value.
     /// A value of None signifies that the value can't be determined in the
     /// preparser and will be defined by the backend. One such example is labels
     /// that depend on fixed sized instructions to determine their offset.
     Symbol(SymbolId, Option<types::LeByteEncodedValue>),
     Constant(PrimitiveOrReference),
+    Assertion(Assertion),
+    CycleAssertion(CycleAssertion),
+    Message(Message),
+    /// Phase begins a relocated block, where labels resolve relative to the
+    /// provided run address while bytes continue to be emitted at the
+    /// current position of the enclosing origin.
+    Phase(usize),
+    /// Dephase ends a relocated block, returning labels to the emit position.
+    Dephase,
+    /// Cpu selects the processor that all following instructions target,
+    /// with the name left to the backend to interpret.
+    Cpu(String, SourceLocation),
+    /// RegisterWidth sets the width, in bits, of a register for all following
+    /// instructions, sizing any immediate operands that target it.
+    RegisterWidth(Register, usize, SourceLocation),
+    /// Vectors defines the interrupt vector table, which is placed in its
+    /// own origin regardless of where it appears in the source.
+    Vectors(Vectors),
 }
 
 #[derive(Default)]
-pub struct PreParser {}
+pub struct PreParser {
+    default_origin: usize,
+}
 
 impl PreParser {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// with_default_origin sets the offset of any statements preceding the
+    /// first `.origin` directive, defaulting to 0.
+    pub fn with_default_origin(mut self, default_origin: usize) -> Self {
+        self.default_origin = default_origin;
+        self
+    }
 }
 
 type PreparseTokenStream = Vec<Token<String>>;
@@ -49,90 +188,342 @@ type OriginStream = Vec<Origin<PreparseTokenStream>>;
 
 impl<'a> Parser<'a, &'a [char], OriginStream> for PreParser {
     fn parse(&self, input: &'a [char]) -> ParseResult<'a, &'a [char], OriginStream> {
-        join(
-            origin_statements().or(|| statements().map(Origin::new)),
-            zero_or_more(origin_statements()),
+        let default_origin = self.default_origin;
+        let lines = LineIndex::new(input);
+        let default_lines = lines.clone();
+
+        let res = join(
+            origin_statements(lines.clone()).or(move || {
+                statements(default_lines.clone())
+                    .map(move |statements| Origin::with_offset(default_origin, statements))
+            }),
+            zero_or_more(origin_statements(lines.clone())),
         )
         .map(|(head, tail)| [head].into_iter().chain(tail).collect())
-        .parse(input)
+        .parse(input)?;
+
+        let remainder = match &res {
+            MatchStatus::Match((remainder, _)) => remainder,
+            MatchStatus::NoMatch(remainder) => remainder,
+        };
+        match unparsed_statement(&lines, remainder) {
+            Some(e) => Err(e),
+            None => Ok(res),
+        }
+    }
+}
+
+/// Returns a syntax error, located at the statement that parsing halted on,
+/// for any input other than whitespace that remains once parsing halts.
+fn unparsed_statement(lines: &LineIndex, remainder: &[char]) -> Option<String> {
+    let statement_start = remainder.iter().position(|c| !c.is_whitespace())?;
+    let statement = &remainder[statement_start..];
+    let text = statement
+        .iter()
+        .take_while(|c| **c != '\n')
+        .collect::<String>();
+
+    Some(format!(
+        "{}: invalid statement: {}",
+        lines.location(statement),
+        text.trim_end()
+    ))
+}
+
+/// LineIndex records the offset of each newline in a source, so that the line
+/// of a statement is found without rescanning the source before it.
+#[derive(Debug, Clone)]
+struct LineIndex {
+    len: usize,
+    newlines: Rc<[usize]>,
+}
+
+impl LineIndex {
+    fn new(source: &[char]) -> Self {
+        let newlines = source
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == '\n')
+            .map(|(offset, _)| offset)
+            .collect();
+
+        Self {
+            len: source.len(),
+            newlines,
+        }
+    }
+
+    /// Returns the location of a statement from the remaining unparsed input
+    /// at the start of the statement.
+    fn location(&self, remaining: &[char]) -> SourceLocation {
+        let offset = self.len.saturating_sub(remaining.len());
+        let line = self.newlines.partition_point(|newline| *newline < offset) + 1;
+
+        SourceLocation::new(line)
     }
 }
 
 #[allow(clippy::redundant_closure)]
-fn origin_statements<'a>() -> impl parcel::Parser<'a, &'a [char], Origin<PreparseTokenStream>> {
+fn origin_statements<'a>(
+    lines: LineIndex,
+) -> impl parcel::Parser<'a, &'a [char], Origin<PreparseTokenStream>> {
     right(join(
-        zero_or_more(non_newline_whitespace().or(|| newline())),
+        zero_or_more(statement_separator()),
         join(
             origin(),
-            zero_or_more(statement()).map(|ioc| ioc.into_iter().flatten().collect()),
+            zero_or_more(statement(lines)).map(|ioc| ioc.into_iter().flatten().collect()),
         ),
     ))
     .map(|(offset, statements)| Origin::with_offset(offset as usize, statements))
 }
 
-fn statements<'a>() -> impl parcel::Parser<'a, &'a [char], PreparseTokenStream> {
-    one_or_more(statement()).map(|ioc| ioc.into_iter().flatten().collect())
+fn statements<'a>(lines: LineIndex) -> impl parcel::Parser<'a, &'a [char], PreparseTokenStream> {
+    one_or_more(statement(lines)).map(|ioc| ioc.into_iter().flatten().collect())
 }
 
+/// Parses a single statement, looking up its line once per statement, after
+/// any separators, for the directives that record their location.
 #[allow(clippy::redundant_closure)]
-fn statement<'a>() -> impl parcel::Parser<'a, &'a [char], Option<Token<String>>> {
+fn statement<'a>(lines: LineIndex) -> impl parcel::Parser<'a, &'a [char], Option<Token<String>>> {
     right(join(
-        zero_or_more(non_newline_whitespace().or(|| newline())),
-        left(join(
-            labeldef()
-                .map(Some)
-                .or(|| symboldef().map(Some))
-                .or(|| constant().map(Some))
-                .or(|| instruction().map(Some))
-                .or(|| comment().map(|_| None)),
-            right(join(
-                join(zero_or_more(non_newline_whitespace()), optional(comment())),
-                newline().or(|| eof()),
-            )),
-        )),
+        zero_or_more(statement_separator()),
+        move |input: &'a [char]| {
+            let location = lines.location(input);
+
+            left(join(
+                labeldef()
+                    .map(Some)
+                    .or(|| symboldef().map(Some))
+                    .or(|| constant().map(Some))
+                    .or(|| phase().map(Some))
+                    .or(move || cpu(location).map(Some))
+                    .or(move || register_width(location).map(Some))
+                    .or(move || vectors(location).map(Some))
+                    .or(move || cycle_assertion(location).map(Some))
+                    .or(move || assertion(location).map(Some))
+                    .or(move || message(location).map(Some))
+                    .or(move || instruction(location).map(Some))
+                    .or(|| comment().map(|_| None)),
+                right(join(
+                    join(zero_or_more(non_newline_whitespace()), optional(comment())),
+                    newline().or(|| eof()),
+                )),
+            ))
+            .parse(input)
+        },
     ))
 }
 
-fn instruction<'a>() -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+fn instruction<'a>(location: SourceLocation) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    instruction_text().map(move |text| Token::Instruction(text, location))
+}
+
+#[allow(clippy::redundant_closure)]
+fn instruction_text<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
     join(
         alphabetic(),
-        one_or_more(alphabetic().or(|| {
-            non_newline_whitespace().or(|| digit(10)).or(|| {
-                one_of(vec![
-                    expect_character('-'),
-                    expect_character('_'),
-                    expect_character('\\'),
-                    expect_character('#'),
-                    expect_character('&'),
-                    expect_character('\''),
-                    expect_character('|'),
-                    expect_character('('),
-                    expect_character(')'),
-                    expect_character('*'),
-                    expect_character('+'),
-                    expect_character(','),
-                    expect_character('.'),
-                    expect_character('/'),
-                    expect_character(':'),
-                    expect_character('<'),
-                    expect_character('='),
-                    expect_character('>'),
-                ])
-            })
+        // block comments are collapsed to a single space so that they may
+        // separate operands without becoming part of the instruction.
+        one_or_more(block_comment().map(|_| ' ').or(|| alphabetic()).or(|| {
+            non_newline_whitespace()
+                .or(|| digit(10))
+                .or(|| slash())
+                .or(|| {
+                    one_of(vec![
+                        expect_character('-'),
+                        expect_character('_'),
+                        expect_character('$'),
+                        expect_character('%'),
+                        expect_character('@'),
+                        expect_character('\\'),
+                        expect_character('#'),
+                        expect_character('&'),
+                        expect_character('\''),
+                        expect_character('|'),
+                        expect_character('('),
+                        expect_character(')'),
+                        expect_character('*'),
+                        expect_character('+'),
+                        expect_character(','),
+                        expect_character('.'),
+                        expect_character(':'),
+                        expect_character('<'),
+                        expect_character('='),
+                        expect_character('>'),
+                        expect_character('['),
+                        expect_character(']'),
+                        expect_character('^'),
+                    ])
+                })
         })),
     )
-    .map(|(head, tail)| Token::Instruction(vec![head].into_iter().chain(tail).collect()))
+    .map(|(head, tail)| vec![head].into_iter().chain(tail).collect())
+}
+
+/// Matches a `/` that doesn't begin a comment.
+fn slash<'a>() -> impl parcel::Parser<'a, &'a [char], char> {
+    move |input: &'a [char]| match input {
+        ['/', '/', ..] | ['/', '*', ..] => Ok(MatchStatus::NoMatch(input)),
+        ['/', ..] => Ok(MatchStatus::Match((&input[1..], '/'))),
+        _ => Ok(MatchStatus::NoMatch(input)),
+    }
 }
 
 #[allow(clippy::redundant_closure)]
 fn comment<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    line_comment().or(|| block_comment())
+}
+
+#[allow(clippy::redundant_closure)]
+fn line_comment<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
     right(join(
-        expect_character(';'),
+        expect_str(";").or(|| expect_str("//")),
         zero_or_more(non_whitespace_character().or(|| non_newline_whitespace())),
     ))
     .map(|_| ())
 }
 
+/// Parses a `/* ... */` comment which, unlike line comments, may span
+/// multiple lines.
+fn block_comment<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    right(join(
+        expect_str("/*"),
+        left(join(
+            zero_or_more(block_comment_character()),
+            expect_str("*/"),
+        )),
+    ))
+    .map(|_| ())
+}
+
+fn block_comment_character<'a>() -> impl parcel::Parser<'a, &'a [char], char> {
+    move |input: &'a [char]| match input {
+        [] | ['*', '/', ..] => Ok(MatchStatus::NoMatch(input)),
+        [next, ..] => Ok(MatchStatus::Match((&input[1..], *next))),
+    }
+}
+
+/// Matches any whitespace, newlines or block comments that may separate
+/// statements.
+#[allow(clippy::redundant_closure)]
+fn statement_separator<'a>() -> impl parcel::Parser<'a, &'a [char], ()> {
+    non_newline_whitespace()
+        .or(|| newline())
+        .map(|_| ())
+        .or(|| block_comment())
+}
+
+/// Parses an `.assert expression, severity, "message"` directive, recording
+/// its line in the source for reporting.
+fn assertion<'a>(location: SourceLocation) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    right(join(
+        join(expect_str(".assert"), one_or_more(non_newline_whitespace())),
+        join(
+            left(join(expression::expression(), argument_separator())),
+            join(left(join(severity(), argument_separator())), string()),
+        ),
+    ))
+    .map(move |(expr, (severity, message))| {
+        Token::Assertion(Assertion::new(expr, severity, message, location))
+    })
+}
+
+/// Parses an `.assert_cycles start, end, cycles` directive, recording its
+/// line in the source for reporting.
+fn cycle_assertion<'a>(
+    location: SourceLocation,
+) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    right(join(
+        join(
+            expect_str(".assert_cycles"),
+            one_or_more(non_newline_whitespace()),
+        ),
+        join(
+            left(join(one_or_more(alphabetic()), argument_separator())),
+            join(
+                left(join(one_or_more(alphabetic()), argument_separator())),
+                unsigned32(),
+            ),
+        ),
+    ))
+    .map(move |(start, (end, cycles))| {
+        Token::CycleAssertion(CycleAssertion::new(
+            start.into_iter().collect(),
+            end.into_iter().collect(),
+            cycles as usize,
+            location,
+        ))
+    })
+}
+
+/// Parses a `.print`, `.warning` or `.error` directive followed by a comma
+/// separated list of strings and expressions, recording its line in the
+/// source for reporting.
+fn message<'a>(location: SourceLocation) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    join(
+        message_severity(),
+        right(join(
+            one_or_more(non_newline_whitespace()),
+            join(
+                message_fragment(),
+                zero_or_more(right(join(argument_separator(), message_fragment()))),
+            ),
+        )),
+    )
+    .map(move |(severity, (head, tail))| {
+        let fragments = [head].into_iter().chain(tail).collect();
+        Token::Message(Message::new(severity, fragments, location))
+    })
+}
+
+#[allow(clippy::redundant_closure)]
+fn message_severity<'a>() -> impl parcel::Parser<'a, &'a [char], Severity> {
+    expect_str(".print")
+        .map(|_| Severity::Info)
+        .or(|| expect_str(".warning").map(|_| Severity::Warning))
+        .or(|| expect_str(".error").map(|_| Severity::Error))
+}
+
+#[allow(clippy::redundant_closure)]
+fn message_fragment<'a>() -> impl parcel::Parser<'a, &'a [char], MessageFragment> {
+    string()
+        .map(MessageFragment::Text)
+        .or(|| expression::expression().map(MessageFragment::Expression))
+}
+
+fn argument_separator<'a>() -> impl parcel::Parser<'a, &'a [char], char> {
+    right(join(
+        zero_or_more(non_newline_whitespace()),
+        left(join(
+            expect_character(','),
+            zero_or_more(non_newline_whitespace()),
+        )),
+    ))
+}
+
+#[allow(clippy::redundant_closure)]
+fn severity<'a>() -> impl parcel::Parser<'a, &'a [char], Severity> {
+    expect_str("error")
+        .map(|_| Severity::Error)
+        .or(|| expect_str("warning").map(|_| Severity::Warning))
+}
+
+#[allow(clippy::redundant_closure)]
+fn string<'a>() -> impl parcel::Parser<'a, &'a [char], String> {
+    right(join(
+        expect_character('"'),
+        left(join(
+            zero_or_more(
+                non_whitespace_character()
+                    .or(|| non_newline_whitespace())
+                    .predicate(|c| *c != '"'),
+            ),
+            expect_character('"'),
+        )),
+    ))
+    .map(|cv| cv.into_iter().collect())
+}
+
 fn labeldef<'a>() -> impl parcel::Parser<'a, &'a [char], Token<String>> {
     left(join(one_or_more(alphabetic()), expect_character(':')))
         .map(|cv| Token::Symbol(cv.into_iter().collect(), None))
@@ -246,6 +637,95 @@ fn origin<'a>() -> impl parcel::Parser<'a, &'a [char], u32> {
     ))
 }
 
+#[allow(clippy::redundant_closure)]
+fn phase<'a>() -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    right(join(
+        join(expect_str(".phase"), one_or_more(non_newline_whitespace())),
+        unsigned32(),
+    ))
+    .map(|offset| Token::Phase(offset as usize))
+    .or(|| expect_str(".dephase").map(|_| Token::Dephase))
+}
+
+/// Parses a `.cpu name` directive, recording its line in the source for
+/// reporting.
+fn cpu<'a>(location: SourceLocation) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    right(join(
+        join(expect_str(".cpu"), one_or_more(non_newline_whitespace())),
+        one_or_more(alphabetic().or(|| digit(10))),
+    ))
+    .map(move |name| Token::Cpu(name.into_iter().collect(), location))
+}
+
+/// Parses one of the `.a8`, `.a16`, `.i8` or `.i16` register width
+/// directives, recording its line in the source for reporting.
+#[allow(clippy::redundant_closure)]
+fn register_width<'a>(
+    location: SourceLocation,
+) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    join(
+        expect_str(".a")
+            .map(|_| Register::Accumulator)
+            .or(|| expect_str(".i").map(|_| Register::Index)),
+        expect_str("16")
+            .map(|_| 16)
+            .or(|| expect_str("8").map(|_| 8)),
+    )
+    .map(move |(register, width)| Token::RegisterWidth(register, width, location))
+}
+
+/// Parses a `.vectors nmi=label, reset=label, irq=label` directive, with
+/// each vector given exactly once in any order, recording its line in the
+/// source for reporting.
+fn vectors<'a>(location: SourceLocation) -> impl parcel::Parser<'a, &'a [char], Token<String>> {
+    right(join(
+        join(
+            expect_str(".vectors"),
+            one_or_more(non_newline_whitespace()),
+        ),
+        join(
+            vector(),
+            zero_or_more(right(join(argument_separator(), vector()))),
+        ),
+    ))
+    .map(|(head, tail)| [head].into_iter().chain(tail).collect::<Vec<_>>())
+    .predicate(|vectors| {
+        vectors.len() == 3
+            && ["nmi", "reset", "irq"]
+                .iter()
+                .all(|name| vectors.iter().any(|(vector, _)| vector == name))
+    })
+    .map(move |vectors| {
+        let label_of = |name: &str| {
+            vectors
+                .iter()
+                .find(|(vector, _)| *vector == name)
+                .map(|(_, label)| label.clone())
+                .unwrap_or_default()
+        };
+
+        Token::Vectors(Vectors::new(
+            label_of("nmi"),
+            label_of("reset"),
+            label_of("irq"),
+            location,
+        ))
+    })
+}
+
+/// Parses a single `name=label` vector assignment.
+#[allow(clippy::redundant_closure)]
+fn vector<'a>() -> impl parcel::Parser<'a, &'a [char], (&'static str, String)> {
+    join(
+        expect_str("nmi")
+            .map(|_| "nmi")
+            .or(|| expect_str("reset").map(|_| "reset"))
+            .or(|| expect_str("irq").map(|_| "irq")),
+        right(join(expect_character('='), one_or_more(alphabetic()))),
+    )
+    .map(|(name, label)| (name, label.into_iter().collect()))
+}
+
 #[allow(clippy::redundant_closure)]
 fn constant<'a>() -> impl parcel::Parser<'a, &'a [char], Token<String>> {
     const_byte()
@@ -1,4 +1,8 @@
-use crate::preparser::{types, PreParser, PrimitiveOrReference, Token};
+use crate::diagnostics::{Severity, SourceLocation};
+use crate::preparser::expression::{BinaryOperator, Expression};
+use crate::preparser::{
+    types, Assertion, CycleAssertion, PreParser, PrimitiveOrReference, Register, Token, Vectors,
+};
 use parcel::prelude::v1::*;
 
 macro_rules! chars {
@@ -20,7 +24,10 @@ fn should_parse_instruction_to_string() {
     assert_eq!(
         Ok(MatchStatus::Match((
             &input[input.len()..],
-            vec![zero_origin!(vec![Token::Instruction("nop".to_string())])]
+            vec![zero_origin!(vec![Token::Instruction(
+                "nop".to_string(),
+                SourceLocation::new(1)
+            )])]
         ))),
         PreParser::new().parse(&input)
     );
@@ -111,14 +118,51 @@ fn should_parse_origin() {
         Ok(MatchStatus::Match((
             &input[input.len()..],
             vec![
-                crate::Origin::new(vec![Token::Instruction("nop".to_string())]),
-                crate::Origin::with_offset(0x1a2b, vec![Token::Instruction("nop".to_string())])
+                crate::Origin::new(vec![Token::Instruction(
+                    "nop".to_string(),
+                    SourceLocation::new(1)
+                )]),
+                crate::Origin::with_offset(
+                    0x1a2b,
+                    vec![Token::Instruction(
+                        "nop".to_string(),
+                        SourceLocation::new(3)
+                    )]
+                )
             ]
         ))),
         PreParser::new().parse(&input)
     );
 }
 
+#[test]
+fn should_place_statements_preceding_an_origin_at_the_default_origin() {
+    let input = chars!("nop\n.origin 0x0300\nnop");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![
+                crate::Origin::with_offset(
+                    0x200,
+                    vec![Token::Instruction(
+                        "nop".to_string(),
+                        SourceLocation::new(1)
+                    )]
+                ),
+                crate::Origin::with_offset(
+                    0x300,
+                    vec![Token::Instruction(
+                        "nop".to_string(),
+                        SourceLocation::new(3)
+                    )]
+                )
+            ]
+        ))),
+        PreParser::new().with_default_origin(0x200).parse(&input)
+    );
+}
+
 #[test]
 fn should_parse_constants() {
     let input = chars!(
@@ -157,7 +201,7 @@ fn should_throw_error_on_non_ascii_character_constants() {
     let input = chars!(".char       '𒀀'");
 
     assert_eq!(
-        Ok(MatchStatus::NoMatch(&input[..])),
+        Err("line 1: invalid statement: .char       '𒀀'".to_string()),
         PreParser::new().parse(&input)
     );
 }
@@ -220,3 +264,153 @@ init:
         PreParser::new().parse(&input)
     );
 }
+
+#[test]
+fn should_parse_assertion_with_source_location() {
+    let input = chars!("nop\n.assert end <= $C000, error, \"code overflows into IO\"");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![
+                Token::Instruction("nop".to_string(), SourceLocation::new(1)),
+                Token::Assertion(Assertion::new(
+                    Expression::Binary(
+                        BinaryOperator::LessThanOrEqual,
+                        Box::new(Expression::Reference("end".to_string())),
+                        Box::new(Expression::Literal(0xc000))
+                    ),
+                    Severity::Error,
+                    "code overflows into IO".to_string(),
+                    SourceLocation::new(2)
+                ))
+            ])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
+
+#[test]
+fn should_strip_block_and_line_comments() {
+    let input = chars!(
+        "
+/* disabled:
+nop
+nop */
+lda /* zeropage */ 0x10 // load
+// asl A
+nop ; trailing
+"
+    );
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![
+                Token::Instruction("lda   0x10 ".to_string(), SourceLocation::new(5)),
+                Token::Instruction("nop ".to_string(), SourceLocation::new(7))
+            ])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_cpu_directive() {
+    let input = chars!(".cpu 6502x\nlax 0x10");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![
+                Token::Cpu("6502x".to_string(), SourceLocation::new(1)),
+                Token::Instruction("lax 0x10".to_string(), SourceLocation::new(2))
+            ])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_register_width_directives() {
+    let input = chars!(".a16\n.i8\nlda #0x1234");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![
+                Token::RegisterWidth(Register::Accumulator, 16, SourceLocation::new(1)),
+                Token::RegisterWidth(Register::Index, 8, SourceLocation::new(2)),
+                Token::Instruction("lda #0x1234".to_string(), SourceLocation::new(3))
+            ])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
+
+#[test]
+fn should_parse_cycle_assertion() {
+    let input = chars!(".assert_cycles start, end, 12");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![Token::CycleAssertion(
+                CycleAssertion::new(
+                    "start".to_string(),
+                    "end".to_string(),
+                    12,
+                    SourceLocation::new(1)
+                )
+            )])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_with_location_on_malformed_statements() {
+    for (source, statement) in [
+        (
+            "nop\n.vectors reset=start, irq=isr\n",
+            ".vectors reset=start, irq=isr",
+        ),
+        (
+            "nop\n  .vectors reset=start irq=isr nmi=isr\n",
+            ".vectors reset=start irq=isr nmi=isr",
+        ),
+        (
+            "nop\n.assert 1 == 1, fatal, \"never\"\nnop",
+            ".assert 1 == 1, fatal, \"never\"",
+        ),
+        ("nop\n.print\nnop", ".print"),
+        ("nop\n.phase start\nnop", ".phase start"),
+        ("nop\n.cpu\nnop", ".cpu"),
+        ("nop\n\tlda #$12 !\nnop", "lda #$12 !"),
+    ] {
+        let input = chars!(source);
+
+        assert_eq!(
+            Err(format!("line 2: invalid statement: {}", statement)),
+            PreParser::new().parse(&input)
+        );
+    }
+}
+
+#[test]
+fn should_parse_vectors_in_any_order() {
+    let input = chars!(".vectors reset=start, irq=isr, nmi=isr");
+
+    assert_eq!(
+        Ok(MatchStatus::Match((
+            &input[input.len()..],
+            vec![zero_origin!(vec![Token::Vectors(Vectors::new(
+                "isr".to_string(),
+                "start".to_string(),
+                "isr".to_string(),
+                SourceLocation::new(1)
+            ))])]
+        ))),
+        PreParser::new().parse(&input)
+    );
+}
@@ -21,6 +21,15 @@ pub trait Reify<T> {
     fn reify(&self) -> Result<T, Self::Error>;
 }
 
+/// Endianness represents the byte order a target emits multi-byte values
+/// in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
 /// LeByteEncodedValue represents an arbitrarily length binary value encoded
 /// in little-endian format
 #[derive(Debug, Clone, PartialEq)]
@@ -65,6 +74,14 @@ impl LeByteEncodedValue {
     pub fn to_vec(&self) -> Vec<u8> {
         self.inner.clone()
     }
+
+    /// Returns the value encoded in the provided byte order.
+    pub fn to_vec_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        match endianness {
+            Endianness::Little => self.to_vec(),
+            Endianness::Big => self.inner.iter().rev().copied().collect(),
+        }
+    }
 }
 
 impl crate::addressing::SizeOf for LeByteEncodedValue {
@@ -105,6 +122,22 @@ impl_from_to_le_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f6
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn should_encode_a_value_in_either_byte_order() {
+        use super::Endianness;
+
+        let value = super::LeByteEncodedValue::from(0x1234u16);
+
+        assert_eq!(
+            vec![0x34, 0x12],
+            value.to_vec_with_endianness(Endianness::Little)
+        );
+        assert_eq!(
+            vec![0x12, 0x34],
+            value.to_vec_with_endianness(Endianness::Big)
+        );
+    }
+
     #[test]
     fn should_return_bits_required_to_express_a_type() {
         assert_eq!(3, super::LeByteEncodedValue::from(4u8).bits());
@@ -1,5 +1,5 @@
 use crate::assemble;
-use crate::backends::Backend;
+use crate::builtin;
 use crate::Emitter;
 
 macro_rules! zero_origin {
@@ -21,7 +21,7 @@ jmp 0x1234\n";
         Ok(vec![zero_origin!(vec![
             0xea, 0xa9, 0x12, 0x8d, 0x34, 0x12, 0x10, 0x1a, 0x10, 0xf0, 0x4c, 0x34, 0x12
         ])]),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -42,7 +42,7 @@ init:
         Ok(vec![zero_origin!(vec![
             0xea, 0xa9, 0x12, 0xea, 0xa9, 0x12, 0x8d, 0x34, 0x12, 0x4c, 0x03, 0x00
         ])]),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -60,7 +60,7 @@ init:
 
     assert_eq!(
         Err("reference undefined: notinit".to_string()),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -79,7 +79,7 @@ jmp 0x1234
         Ok(vec![zero_origin!(vec![
             0xea, 0xa9, 0x12, 0x8d, 0x34, 0x12, 0x4c, 0x34, 0x12
         ])]),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -94,7 +94,7 @@ jmp 0x1234
 
     assert_eq!(
         Err("reference undefined: test".to_string()),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -114,7 +114,7 @@ init:
         Ok(vec![zero_origin!(vec![
             0xea, 0xa9, 0x12, 0x8d, 0x34, 0x12, 0x4c, 0x00, 0x00
         ])]),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -134,7 +134,7 @@ init: ; test
         Ok(vec![zero_origin!(vec![
             0xea, 0xa9, 0x12, 0x8d, 0x34, 0x12, 0x4c, 0x00, 0x00
         ])]),
-        assemble(Backend::Mos6502, input)
+        assemble(&builtin::MOS6502, input)
     )
 }
 
@@ -150,7 +150,7 @@ nop
 
     assert_eq!(
         Ok(vec![0xea, 0x00, 0x00, 0xea, 0x00, 0x00, 0xea]),
-        assemble(Backend::Mos6502, input).map(|res| res.emit())
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
     );
 }
 
@@ -167,7 +167,7 @@ nop
 
     assert_eq!(
         Ok(vec![0xea, 0x00, 0x00, 0xea, 0x2b, 0x1a, 0xea]),
-        assemble(Backend::Mos6502, input).map(|res| res.emit())
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
     );
 }
 
@@ -184,7 +184,7 @@ init:
 
     assert_eq!(
         Ok(vec![0xea, 0xea, 0x01, 0x00, 0xff]),
-        assemble(Backend::Mos6502, input).map(|res| res.emit())
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
     );
 }
 
@@ -198,7 +198,7 @@ origin fffc
 .word 0x0001
 ";
 
-    assert!(assemble(Backend::Mos6502, input).is_err())
+    assert!(assemble(&builtin::MOS6502, input).is_err())
 }
 
 #[test]
@@ -214,6 +214,1168 @@ init:
 
     assert_eq!(
         Ok(vec![0x03, 0x00, 0xff]),
-        assemble(Backend::Mos6502, input).map(|res| res.emit())
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_pass_assertions_against_resolved_labels() {
+    let input = "
+.origin 0x10
+init:
+  nop
+  jmp init
+end:
+.assert end <= $C000, error, \"code overflows into IO\"
+";
+
+    assert_eq!(
+        Ok(vec![0xea, 0x4c, 0x10, 0x00]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_throw_an_error_with_location_on_failed_error_assertions() {
+    let input = "
+init:
+  nop
+  jmp init
+end:
+.assert end - init < 4, error, \"routine too large\"
+";
+
+    assert_eq!(
+        Err("line 6: assertion failed: routine too large".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_with_location_on_undefined_references_in_assertions() {
+    let input = "
+init:
+  nop
+.assert end - init < 4, error, \"routine too large\"
+";
+
+    assert_eq!(
+        Err("line 4: reference undefined: end".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_report_failed_warning_assertions_as_diagnostics() {
+    use crate::diagnostics::{Diagnostic, Severity, SourceLocation};
+
+    let input = "
+nop
+.assert * == 0, warning, \"not at start\"
+";
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    assert_eq!(
+        Ok(vec![0xea]),
+        crate::assemble_with_options(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new().with_diagnostics(tx)
+        )
+        .map(|res| res.emit())
+    );
+    assert_eq!(
+        vec![Diagnostic::new(
+            Severity::Warning,
+            SourceLocation::new(3),
+            "assertion failed: not at start".to_string()
+        )],
+        rx.try_iter().collect::<Vec<Diagnostic>>()
+    );
+}
+
+#[test]
+fn should_report_print_and_warning_directives_as_diagnostics() {
+    use crate::diagnostics::{Diagnostic, Severity, SourceLocation};
+
+    let input = "
+.origin 0xfff0
+  nop
+  nop
+.print \"free bytes: \", $FFFA-*
+.warning \"board revision \", 2, \" is untested\"
+";
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    assert_eq!(
+        Ok(vec![0xea, 0xea]),
+        crate::assemble_with_options(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new().with_diagnostics(tx)
+        )
+        .map(|res| res.emit())
+    );
+    assert_eq!(
+        vec![
+            Diagnostic::new(
+                Severity::Info,
+                SourceLocation::new(5),
+                "free bytes: 8".to_string()
+            ),
+            Diagnostic::new(
+                Severity::Warning,
+                SourceLocation::new(6),
+                "board revision 2 is untested".to_string()
+            )
+        ],
+        rx.try_iter().collect::<Vec<Diagnostic>>()
+    );
+}
+
+#[test]
+fn should_abort_assembly_on_error_directive() {
+    let input = "
+nop
+.error \"unsupported board\"
+";
+
+    assert_eq!(
+        Err("line 3: error: unsupported board".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_resolve_labels_in_phased_blocks_to_the_run_address() {
+    let input = "
+.origin 0x8000
+  jmp copy
+.phase 0x0200
+copy:
+  nop
+  jmp copy
+.dephase
+after:
+  jmp after
+";
+
+    assert_eq!(
+        Ok(vec![crate::Origin::with_offset(
+            0x8000,
+            vec![0x4c, 0x00, 0x02, 0xea, 0x4c, 0x00, 0x02, 0x4c, 0x07, 0x80]
+        )]),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_ignore_block_and_line_comments() {
+    let input = "
+/*
+  nop
+  nop
+*/
+init: // entry
+  lda /* zeropage */ 0x10
+  jmp init /* loop
+  forever */
+";
+
+    assert_eq!(
+        Ok(vec![0xa5, 0x10, 0x4c, 0x00, 0x00]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_resolve_symbols_injected_by_definitions() {
+    let input = "
+lda #revision
+.assert board == 2, error, \"unsupported board\"
+";
+
+    let options = crate::AssemblerOptions::new()
+        .with_definition("revision", 0x12)
+        .with_definition("board", 2);
+    assert_eq!(
+        Ok(vec![0xa9, 0x12]),
+        crate::assemble_with_options(&builtin::MOS6502, input, options).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_override_source_defines_with_definitions() {
+    let input = "
+.define byte revision 0x01
+lda #revision
+.origin 0x10
+.define byte board 1
+.assert board == 2, error, \"unsupported board\"
+";
+
+    let options = crate::AssemblerOptions::new()
+        .with_definition("revision", 0x12)
+        .with_definition("board", 2);
+    assert_eq!(
+        Ok(vec![crate::Origin::new(vec![0xa9, 0x12])]),
+        crate::assemble_with_options(&builtin::MOS6502, input, options)
+    );
+}
+
+#[test]
+fn should_select_zeropage_addressing_for_references_below_0x100() {
+    let input = "
+.define byte ptr 0x10
+.define word table 0x0300
+
+lda ptr
+ldx ptr,Y
+sta ptr,Y
+lda table,X
+";
+
+    assert_eq!(
+        Ok(vec![
+            0xa5, 0x10, 0xb6, 0x10, 0x99, 0x10, 0x00, 0xbd, 0x00, 0x03
+        ]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_move_subsequent_labels_when_forward_references_shrink() {
+    let input = "
+  lda counter
+  jmp end
+end:
+  nop
+.define byte counter 0x10
+";
+
+    assert_eq!(
+        Ok(vec![0xa5, 0x10, 0x4c, 0x05, 0x00, 0xea]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_resolve_references_in_each_addressing_mode() {
+    let input = "
+.define byte counter 0x10
+.define word vector 0x0300
+.origin 0x8000
+init:
+  ldx #0x00
+loop:
+  lda table,X
+  sta buffer,Y
+  inc counter,X
+  bne loop
+  beq done
+  jmp (vector)
+done:
+  rts
+table:
+buffer:
+";
+
+    assert_eq!(
+        Ok(vec![crate::Origin::with_offset(
+            0x8000,
+            vec![
+                0xa2, 0x00, 0xbd, 0x12, 0x80, 0x99, 0x12, 0x80, 0xf6, 0x10, 0xd0, 0xf6, 0xf0, 0x03,
+                0x6c, 0x00, 0x03, 0x60
+            ]
+        )]),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_not_mistake_labels_beginning_with_a_for_the_accumulator() {
+    let input = "
+Again:
+  asl A
+  jmp Again
+";
+
+    assert_eq!(
+        Ok(vec![0x0a, 0x4c, 0x00, 0x00]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_throw_an_error_on_out_of_range_branches_to_labels() {
+    let input = "
+init:
+  bne far
+.origin 0x0100
+far:
+  nop
+";
+
+    assert_eq!(
+        Err("branch to far out of range: 254 bytes".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_prefer_explicit_operand_sizes_over_automatic_selection() {
+    let input = "
+.define byte ptr 0x10
+lda a:$0010
+lda.w ptr,X
+lda z:$0010
+ldx.b $0020,Y
+lda z:later
+.define word later 0x0030
+";
+
+    assert_eq!(
+        Ok(vec![
+            0xad, 0x10, 0x00, 0xbd, 0x10, 0x00, 0xa5, 0x10, 0xb6, 0x20, 0xa5, 0x30
+        ]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_throw_an_error_when_a_forced_zeropage_operand_exceeds_the_zeropage() {
+    let input = "
+lda z:$1234
+";
+
+    assert_eq!(
+        Err("operand 0x1234 exceeds the zero page".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_when_an_operand_exceeds_16_bits_without_a_long_form() {
+    let input = "
+.define doubleword big $12345
+lda big,y
+";
+
+    assert_eq!(
+        Err("operand 0x012345 exceeds 16 bits".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+    assert_eq!(
+        Err("operand 0x012345 exceeds 16 bits".to_string()),
+        assemble(&builtin::W65816, input)
+    );
+}
+
+#[test]
+fn should_relax_out_of_range_branches_when_enabled() {
+    let input = "
+init:
+  beq far
+  bne init
+.origin 0x0100
+far:
+  nop
+";
+
+    let options = crate::AssemblerOptions::new().with_branch_relaxation();
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::new(vec![0xd0, 0x03, 0x4c, 0x00, 0x01, 0xd0, 0xf9]),
+            crate::Origin::with_offset(0x0100, vec![0xea])
+        ]),
+        crate::assemble_with_options(&builtin::MOS6502, input, options)
+    );
+}
+
+#[test]
+fn should_select_the_shortest_form_of_pseudo_branch_mnemonics() {
+    let input = "
+init:
+  jne init
+  jcc far
+.origin 0x0100
+far:
+  jeq init
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::new(vec![0xd0, 0xfe, 0xb0, 0x03, 0x4c, 0x00, 0x01]),
+            crate::Origin::with_offset(0x0100, vec![0xd0, 0x03, 0x4c, 0x00, 0x00])
+        ]),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_assemble_65c02_instructions() {
+    let input = "
+.define byte ptr 0x10
+init:
+  bra init
+  stz ptr,X
+  stz 0x1234
+  phx
+  ply
+  trb ptr
+  tsb 0x1234
+  inc A
+  dec A
+  bit #0x80
+  bit ptr,X
+  lda (ptr)
+  sta (0x20)
+  jmp (table,X)
+  jmp (0x1234)
+table:
+";
+
+    assert_eq!(
+        Ok(vec![
+            0x80, 0xfe, 0x74, 0x10, 0x9c, 0x34, 0x12, 0xda, 0x7a, 0x14, 0x10, 0x0c, 0x34, 0x12,
+            0x1a, 0x3a, 0x89, 0x80, 0x34, 0x10, 0xb2, 0x10, 0x92, 0x20, 0x7c, 0x1e, 0x00, 0x6c,
+            0x34, 0x12
+        ]),
+        assemble(&builtin::MOS65C02, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_assemble_rockwell_bit_instructions_for_the_w65c02() {
+    let input = "
+.define byte flags 0x10
+loop:
+  rmb0 flags
+  smb7 0x20
+  bbr3 flags, loop
+  bbs1 0x20, *0x02
+  stp
+";
+
+    assert_eq!(
+        Ok(vec![
+            0x07, 0x10, 0xf7, 0x20, 0x3f, 0x10, 0xf9, 0x9f, 0x20, 0x02, 0xdb
+        ]),
+        assemble(&builtin::W65C02, input).map(|res| res.emit())
+    );
+    assert!(assemble(&builtin::MOS65C02, input).is_err());
+}
+
+#[test]
+fn should_reject_65c02_instructions_on_the_nmos_6502() {
+    let input = "
+init:
+  bra init
+";
+
+    assert!(assemble(&builtin::MOS6502, input).is_err());
+}
+
+#[test]
+fn should_assemble_undocumented_instructions_when_selected() {
+    let input = "
+.cpu 6502x
+.define byte ptr 0x10
+  lax 0x10
+  lax ptr,Y
+  sax 0x1234
+  dcp (0x10,X)
+  isc 0x1234,Y
+  slo 0x10,X
+  rla (0x10),Y
+  sre 0x1234,X
+  rra ptr
+  anc #0x0f
+  alr #0x01
+  arr #0x7f
+  sbx #0x02
+  nop #0x00
+  nop 0x10
+  nop 0x1234,X
+  nop
+";
+
+    assert_eq!(
+        Ok(vec![
+            0xa7, 0x10, 0xb7, 0x10, 0x8f, 0x34, 0x12, 0xc3, 0x10, 0xfb, 0x34, 0x12, 0x17, 0x10,
+            0x33, 0x10, 0x5f, 0x34, 0x12, 0x67, 0x10, 0x0b, 0x0f, 0x4b, 0x01, 0x6b, 0x7f, 0xcb,
+            0x02, 0x80, 0x00, 0x04, 0x10, 0x1c, 0x34, 0x12, 0xea
+        ]),
+        assemble(&builtin::MOS6502, input).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_reject_cpus_outside_of_the_backends_family() {
+    let input = "
+  nop
+.cpu 65816
+  nop
+";
+
+    assert_eq!(
+        Err("line 3: cpu 65816 is outside the family of the selected backend".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+    assert_eq!(
+        Ok(vec![0xea, 0xea]),
+        assemble(&builtin::W65816, input).map(|res| res.emit())
+    );
+    assert!(assemble(&builtin::W65816, &input.replace("65816", "6502x")).is_err());
+}
+
+#[test]
+fn should_reject_undocumented_instructions_unless_selected() {
+    let input = "
+  lax 0x10
+";
+
+    assert!(assemble(&builtin::MOS6502, input).is_err());
+    assert_eq!(
+        Ok(vec![0xa7, 0x10]),
+        crate::assemble_with_options(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new().with_cpu("6502x")
+        )
+        .map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_warn_on_unstable_undocumented_instructions() {
+    use crate::diagnostics::{Diagnostic, Severity, SourceLocation};
+
+    let input = "
+.cpu 6502x
+  lax #0x00
+  lax 0x10
+";
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    assert_eq!(
+        Ok(vec![0xab, 0x00, 0xa7, 0x10]),
+        crate::assemble_with_options(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new().with_diagnostics(tx)
+        )
+        .map(|res| res.emit())
+    );
+    assert_eq!(
+        vec![Diagnostic::new(
+            Severity::Warning,
+            SourceLocation::new(3),
+            "unstable undocumented instruction: LAX".to_string()
+        )],
+        rx.try_iter().collect::<Vec<Diagnostic>>()
+    );
+}
+
+#[test]
+fn should_assemble_65816_long_and_stack_relative_instructions() {
+    let input = "
+.origin 0x8000
+.define byte ptr 0x10
+.a16
+.i8
+start:
+  clc
+  xce
+  rep #0x30
+  lda #0x1234
+  ldx #0x12
+  lda 0x123456
+  sta 0x123456,X
+  lda [ptr]
+  lda [ptr],Y
+  lda 0x03,S
+  lda (0x03,S),Y
+  mvn 0x01, 0x02
+  jsl far
+  jml [0x1234]
+  brl start
+  lda #^far
+  lda.l ptr
+  phb
+  rtl
+.origin 0x010000
+far:
+  rtl
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::with_offset(
+                0x8000,
+                vec![
+                    0x18, 0xfb, 0xc2, 0x30, 0xa9, 0x34, 0x12, 0xa2, 0x12, 0xaf, 0x56, 0x34, 0x12,
+                    0x9f, 0x56, 0x34, 0x12, 0xa7, 0x10, 0xb7, 0x10, 0xa3, 0x03, 0xb3, 0x03, 0x54,
+                    0x02, 0x01, 0x22, 0x00, 0x00, 0x01, 0xdc, 0x34, 0x12, 0x82, 0xda, 0xff, 0xa9,
+                    0x01, 0x00, 0xaf, 0x10, 0x00, 0x00, 0x8b, 0x6b
+                ]
+            ),
+            crate::Origin::with_offset(0x010000, vec![0x6b])
+        ]),
+        assemble(&builtin::W65816, input)
+    );
+}
+
+#[test]
+fn should_reject_16_bit_immediates_with_8_bit_registers() {
+    let input = "
+.a8
+  lda #0x1234
+";
+
+    assert!(assemble(&builtin::W65816, input).is_err());
+    assert_eq!(
+        Ok(vec![0xa9, 0x34, 0x12]),
+        assemble(&builtin::W65816, &input.replace(".a8", ".a16")).map(|res| res.emit())
+    );
+}
+
+#[test]
+fn should_sum_cycles_between_labels() {
+    let input = "
+.origin 0x10
+start:
+  ldx #0x08
+loop:
+  lda 0x1000,X
+  dex
+  bne loop
+end:
+  rts
+";
+
+    assert_eq!(
+        Ok((
+            vec![crate::Origin::with_offset(
+                0x10,
+                vec![0xa2, 0x08, 0xbd, 0x00, 0x10, 0xca, 0xd0, 0xfa, 0x60]
+            )],
+            crate::CycleCount {
+                base: 10,
+                worst_case: 13
+            }
+        )),
+        crate::assemble_counting_cycles(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new(),
+            "start",
+            "end"
+        )
+    );
+}
+
+#[test]
+fn should_only_sum_cycles_between_labels_in_source_order() {
+    let input = "
+start:
+  nop
+end:
+  rts
+.phase 0x0000
+  lda $1234
+.dephase
+";
+
+    assert_eq!(
+        Ok((
+            vec![crate::Origin::new(vec![0xea, 0x60, 0xad, 0x34, 0x12])],
+            crate::CycleCount {
+                base: 2,
+                worst_case: 2
+            }
+        )),
+        crate::assemble_counting_cycles(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new(),
+            "start",
+            "end"
+        )
+    );
+}
+
+#[test]
+fn should_throw_an_error_when_counting_cycles_between_origins() {
+    let input = "
+.origin 0x10
+start:
+  nop
+.origin 0x20
+end:
+  rts
+";
+
+    assert_eq!(
+        Err("start and end are defined in different origins".to_string()),
+        crate::assemble_counting_cycles(
+            &builtin::MOS6502,
+            input,
+            crate::AssemblerOptions::new(),
+            "start",
+            "end"
+        )
+    );
+}
+
+#[test]
+fn should_fail_cycle_assertions_that_dont_hold() {
+    let input = "
+start:
+  nop
+  nop
+end:
+.assert_cycles start, end, 4
+.assert_cycles start, end, 5
+";
+
+    assert_eq!(
+        Err("line 7: assertion failed: expected 5 cycles from start to end, found 4".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_place_vectors_in_their_own_origin() {
+    let input = "
+.origin 0x8000
+reset:
+  jmp reset
+nmi:
+irq:
+  rti
+.vectors nmi=nmi, reset=reset, irq=irq
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::with_offset(0x8000, vec![0x4c, 0x00, 0x80, 0x40]),
+            crate::Origin::with_offset(0xfffa, vec![0x03, 0x80, 0x00, 0x80, 0x03, 0x80])
+        ]),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_when_an_origin_overlaps_the_vectors() {
+    let input = "
+reset:
+  rti
+.vectors reset=reset, nmi=reset, irq=reset
+.origin 0xfff8
+  nop
+  nop
+  nop
+";
+
+    assert_eq!(
+        Err("line 4: origin 0xfff8 overlaps the vector table".to_string()),
+        assemble(&builtin::MOS6502, input)
+    );
+}
+
+#[test]
+fn should_assemble_z80_instructions_with_labels() {
+    let input = "
+start:
+  ld b, 0x10
+loop:
+  djnz loop
+  jr nz, start
+  ld (ix+2), a
+  bit 7, (iy-1)
+  jp start
+";
+
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![
+            0x06, 0x10, 0x10, 0xfe, 0x20, 0xfa, 0xdd, 0x77, 0x02, 0xfd, 0xcb, 0xff, 0x7e, 0xc3,
+            0x00, 0x00
+        ])]),
+        assemble(&builtin::Z80, input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_on_out_of_range_z80_relative_jumps() {
+    let input = "
+init:
+  jr far
+.origin 0x0100
+far:
+  nop
+";
+
+    assert_eq!(
+        Err("branch to far out of range: 254 bytes".to_string()),
+        assemble(&builtin::Z80, input)
+    );
+}
+
+#[test]
+fn should_throw_an_error_with_location_on_unsupported_register_widths() {
+    let input = "
+  nop
+.a16
+  nop
+";
+
+    assert_eq!(
+        Err("line 3: register widths are unsupported".to_string()),
+        assemble(&builtin::Z80, input)
+    );
+}
+
+#[test]
+fn should_assemble_i8080_instructions_with_labels() {
+    let input = "
+start:
+  MVI A,05H
+  LXI H,table
+loop:
+  DCR A
+  JNZ loop
+  JMP start
+table:
+  .byte 0xff
+";
+
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![
+            0x3e, 0x05, 0x21, 0x0c, 0x00, 0x3d, 0xc2, 0x05, 0x00, 0xc3, 0x00, 0x00, 0xff
+        ])]),
+        assemble(&builtin::I8080, input)
+    );
+}
+
+#[test]
+fn should_only_assemble_rim_and_sim_when_targeting_the_8085() {
+    let input = "rim\nsim\n";
+
+    assert!(assemble(&builtin::I8080, input).is_err());
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![0x20, 0x30])]),
+        crate::assemble_with_options(
+            &builtin::I8080,
+            input,
+            crate::AssemblerOptions::new().with_cpu("8085")
+        )
+    );
+}
+
+#[test]
+fn should_assemble_6809_instructions_big_endian_with_labels() {
+    let input = "
+setdp $20
+start:
+  lda #$05
+  sta $2010
+  ldx #table
+loop:
+  deca
+  bne loop
+  lbra start
+table:
+  .word $1234
+";
+
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![
+            0x86, 0x05, 0x97, 0x10, 0x8e, 0x00, 0x0d, 0x4a, 0x26, 0xfd, 0x16, 0xff, 0xf3, 0x12,
+            0x34
+        ])]),
+        assemble(&builtin::Mc6809, input)
+    );
+}
+
+#[test]
+fn should_size_6809_indexed_offsets_by_their_resolved_value() {
+    let input = "
+  lda offset,x
+  lda [offset,y]
+  leax -200,u
+offset:
+  rts
+";
+
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![
+            0xa6, 0x09, 0xa6, 0xb8, 0x09, 0x30, 0xc9, 0xff, 0x38, 0x39
+        ])]),
+        assemble(&builtin::Mc6809, input)
+    );
+}
+
+#[test]
+fn should_assemble_6800_instructions_big_endian_with_labels() {
+    let input = "
+start:
+  ldaa #$05
+  staa $20
+  ldx #table
+loop:
+  deca
+  bne loop
+  jmp start
+table:
+  .word $1234
+";
+
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![
+            0x86, 0x05, 0x97, 0x20, 0xce, 0x00, 0x0d, 0x4a, 0x26, 0xfd, 0x7e, 0x00, 0x00, 0x12,
+            0x34
+        ])]),
+        assemble(&builtin::Mc6800, input)
+    );
+}
+
+#[test]
+fn should_only_assemble_6801_extensions_when_targeting_the_6801() {
+    let input = "mul\npshx\n";
+
+    assert!(assemble(&builtin::Mc6800, input).is_err());
+    assert_eq!(
+        Ok(vec![zero_origin!(vec![0x3d, 0x3c])]),
+        crate::assemble_with_options(
+            &builtin::Mc6800,
+            input,
+            crate::AssemblerOptions::new().with_cpu("6803")
+        )
+    );
+}
+
+#[test]
+fn should_assemble_rv32i_instructions_with_labels_and_pseudo_instructions() {
+    let input = "
+start:
+  li a0, 5
+  la a1, table
+loop:
+  addi a0, a0, -1
+  bnez a0, loop
+  call start
+  ret
+table:
+  .word 0x1234
+";
+
+    let words: Vec<u32> = vec![
+        0x00500513, // li a0, 5
+        0x00000597, // auipc a1, 0
+        0x01c58593, // addi a1, a1, 28
+        0xfff50513, // addi a0, a0, -1
+        0xfe051ee3, // bnez a0, loop
+        0x00000097, // auipc ra, 0
+        0xfec080e7, // jalr ra, -20(ra)
+        0x00008067, // ret
+    ];
+    let mut expected: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    expected.extend([0x34, 0x12]);
+
+    assert_eq!(
+        Ok(vec![zero_origin!(expected)]),
+        assemble(&builtin::Rv32i, input)
+    );
+}
+
+#[test]
+fn should_assemble_chip8_instructions_at_the_default_origin() {
+    let input = "
+start:
+  cls
+  ld i, sprite
+  ld v0, $0a
+  drw v0, v1, 5
+  jp start
+sprite:
+  .word 0xf090
+";
+
+    assert_eq!(
+        Ok(vec![crate::Origin::with_offset(
+            0x200,
+            vec![0x00, 0xe0, 0xa2, 0x0a, 0x60, 0x0a, 0xd0, 0x15, 0x12, 0x00, 0xf0, 0x90]
+        )]),
+        assemble(&builtin::Chip8, input)
+    );
+}
+
+#[test]
+fn should_error_on_instructions_beyond_the_address_space() {
+    let input = "
+.origin 0x0ffe
+  cls
+  cls
+";
+
+    assert_eq!(
+        Err("0x1000 exceeds the 12-bit address space".to_string()),
+        assemble(&builtin::Chip8, input)
+    );
+}
+
+#[test]
+fn should_assemble_sm83_instructions_across_origins() {
+    let input = "
+.origin 0x0100
+  nop
+  jp start
+.origin 0x0150
+start:
+  ld hl, sp-2
+  ldh a, ($ff44)
+  ld (hl+), a
+  swap a
+  jr nz, start
+  stop
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::with_offset(0x100, vec![0x00, 0xc3, 0x50, 0x01]),
+            crate::Origin::with_offset(
+                0x150,
+                vec![0xf8, 0xfe, 0xf0, 0x44, 0x22, 0xcb, 0x37, 0x20, 0xf7, 0x10, 0x00]
+            )
+        ]),
+        assemble(&builtin::Sm83, input)
+    );
+}
+
+#[test]
+fn should_assemble_mcs51_instructions_with_predefined_symbols() {
+    let input = "
+.origin 0x0000
+  ljmp start
+.origin 0x0030
+start:
+  mov sp, #0x5f
+  mov r2, #0x03
+loop:
+  cpl P1.0
+  djnz r2, loop
+  acall delay
+  sjmp start
+delay:
+  ret
+  .word 0x1234
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::new(vec![0x02, 0x00, 0x30]),
+            crate::Origin::with_offset(
+                0x30,
+                vec![
+                    0x75, 0x81, 0x5f, 0x7a, 0x03, 0xb2, 0x90, 0xda, 0xfc, 0x11, 0x3d, 0x80, 0xf3,
+                    0x22, 0x12, 0x34
+                ]
+            )
+        ]),
+        assemble(&builtin::Mcs51, input)
+    );
+}
+
+#[test]
+fn should_assemble_avr_instructions_with_word_addressed_labels() {
+    let input = "
+.origin 0x0000
+  rjmp reset
+.origin 0x0040
+reset:
+  ldi r16, lo8(table)
+  ldi r17, hi8(table)
+  movw r31:r30, r17:r16
+loop:
+  ld r0, z+
+  dec r16
+  brne loop
+  rcall done
+  rjmp reset
+done:
+  ret
+table:
+  .word 0x1234
+";
+
+    assert_eq!(
+        Ok(vec![
+            crate::Origin::new(vec![0x1f, 0xc0]),
+            crate::Origin::with_offset(
+                0x40,
+                vec![
+                    0x09, 0xe2, 0x10, 0xe0, 0xf8, 0x01, 0x01, 0x90, 0x0a, 0x95, 0xe9, 0xf7, 0x01,
+                    0xd0, 0xf8, 0xcf, 0x08, 0x95, 0x34, 0x12
+                ]
+            )
+        ]),
+        assemble(&builtin::Avr, input)
+    );
+}
+
+#[test]
+fn should_assemble_avr_byte_addresses_of_labels() {
+    let input = "
+.origin 0x0000
+  ldi r30, lo8(byte(table))
+  ldi r31, hi8(byte(table))
+  lpm r16, z+
+  ret
+table:
+  .word 0x1234
+";
+
+    assert_eq!(
+        Ok(vec![crate::Origin::new(vec![
+            0xe8, 0xe0, 0xf0, 0xe0, 0x05, 0x91, 0x08, 0x95, 0x34, 0x12
+        ])]),
+        assemble(&builtin::Avr, input)
+    );
+}
+
+#[test]
+fn should_assemble_instructions_against_a_table_driven_description() {
+    let description = crate::IsaDescription::try_from(
+        "
+endianness big
+
+nop              => 0x00
+ld r{d}, #{k}    => 0b0001 d:4 k:8
+ld r{d}, ({a})   => 0b0010 d:4 a:16
+jmp {a}          => 0x40 a:16
+beq {o:relative} => 0x50 o:8
+",
+    )
+    .unwrap();
+    let input = "
+.origin 0x0100
+start:
+  ld r1, #0x2a
+  ld r2, (value)
+loop:
+  beq loop
+  nop
+  jmp start
+value:
+  .word 0x1234
+";
+
+    assert_eq!(
+        Ok(vec![crate::Origin::with_offset(
+            0x100,
+            vec![0x11, 0x2a, 0x22, 0x01, 0x0b, 0x50, 0xfe, 0x00, 0x40, 0x01, 0x00, 0x12, 0x34]
+        )]),
+        assemble(&builtin::Table::new(description), input)
     );
 }